@@ -8,11 +8,13 @@ use eframe::{
     epaint::vec2,
 };
 use trsync_core::{
+    config::RemovedWorkspacePolicy,
     instance::{Instance, InstanceId, Workspace},
     security::set_password,
+    tr,
     user::UserRequest,
 };
-use trsync_manager::message::DaemonMessage;
+use trsync_manager::{message::DaemonMessage, types::TrsyncUid};
 
 use crate::{
     event::Event,
@@ -20,7 +22,7 @@ use crate::{
     panel::{
         add::AddInstancePainter,
         instance::{GuiInstance, InstancePainter},
-        root::ConfigurationPainter,
+        root::{policy_text, ConfigurationPainter},
         Panel,
     },
     state::State,
@@ -37,6 +39,10 @@ pub struct App {
     event_sender: Sender<Event>,
     updating: Vec<InstanceId>,
     delete_instance: Option<InstanceId>,
+    /// Workspaces selection waiting for confirmation of removed workspaces policy : removed
+    /// workspaces in synchronization folder, and removed workspaces in folders chosen by user
+    /// (never archived nor deleted)
+    removed_workspaces: Option<(GuiInstance, Vec<Workspace>, Vec<Workspace>)>,
     user_request_receiver: Receiver<UserRequest>,
 }
 
@@ -45,7 +51,10 @@ impl eframe::App for App {
         ctx.set_pixels_per_point(PIXELS_PER_POINT);
         let mut events: Vec<Event> = self.event_receiver.try_iter().collect();
 
-        if self.windowed_error.is_none() && self.delete_instance.is_none() {
+        if self.windowed_error.is_none()
+            && self.delete_instance.is_none()
+            && self.removed_workspaces.is_none()
+        {
             CentralPanel::default().show(ctx, |ui| {
                 events.extend(self.header(ui));
                 ui.separator();
@@ -63,6 +72,10 @@ impl eframe::App for App {
             self.windowed_error = Some(format!("{:#}", error))
         };
 
+        if let Err(error) = self.removed_workspaces_window(ctx) {
+            self.windowed_error = Some(format!("{:#}", error))
+        };
+
         // Exit window if user request something from systray
         if !self.user_request_receiver.is_empty() {
             frame.close()
@@ -86,6 +99,7 @@ impl App {
             event_sender,
             updating: vec![],
             delete_instance: None,
+            removed_workspaces: None,
             user_request_receiver,
         }
     }
@@ -198,7 +212,7 @@ impl App {
                     self.updating.retain(|i| i != &instance.name);
                     self.add_instance_errors(
                         instance.name.clone(),
-                        "Identifiant ou mot de passe invalide".to_string(),
+                        tr!("Invalid login or password").to_string(),
                     );
                 }
                 Event::InstanceCredentialsFailed(instance, error) => {
@@ -216,8 +230,16 @@ impl App {
                     self.add_instance_errors(id, error);
                 }
                 Event::InstanceSelectedWorkspacesValidated(instance) => {
-                    self.update_instance_selected_workspaces(&instance);
-                    self.save_config()?;
+                    let (removed_workspaces, chosen_folders) = self.removed_workspaces(&instance);
+                    if self.state.removed_workspace_policy != RemovedWorkspacePolicy::Keep
+                        && !removed_workspaces.is_empty()
+                    {
+                        self.removed_workspaces =
+                            Some((instance, removed_workspaces, chosen_folders));
+                    } else {
+                        self.update_instance_selected_workspaces(&instance);
+                        self.save_config()?;
+                    }
                 }
                 Event::ValidateNewInstance(instance) => {
                     self.check_instance_credentials(instance)?
@@ -244,9 +266,28 @@ impl App {
         };
     }
 
+    /// Instance address, or account and address when another account of this address is
+    /// already configured
+    fn new_instance_id(&self, instance: &GuiInstance) -> InstanceId {
+        let is_used = |id: &InstanceId| self.state.instances.iter().any(|i| &i.name == id);
+
+        let instance_id = InstanceId(instance.address.clone());
+        if !is_used(&instance_id) {
+            return instance_id;
+        }
+        let account_id = format!("{}@{}", instance.username, instance.address);
+        let mut instance_id = InstanceId(account_id.clone());
+        let mut index = 2;
+        while is_used(&instance_id) {
+            instance_id = InstanceId(format!("{} ({})", account_id, index));
+            index += 1;
+        }
+        instance_id
+    }
+
     fn add_instance(&mut self, instance: &GuiInstance) -> GuiInstance {
         let mut instance_ = instance.clone();
-        instance_.name = InstanceId(instance.address.clone());
+        instance_.name = self.new_instance_id(instance);
 
         // Add a panel for this new instance
         self.state.available_panels.insert(
@@ -278,15 +319,12 @@ impl App {
     }
 
     fn save_credentials(&self, instance: &GuiInstance) -> Result<()> {
-        set_password(
-            &instance.name.to_string(),
-            &whoami::username(),
-            &instance.password,
-        )
-        .context(format!(
-            "Save password in keyring system for '{}'",
-            &instance.name.to_string()
-        ))?;
+        set_password(&instance.address, &instance.username, &instance.password).context(
+            format!(
+                "Save password in keyring system for '{}'",
+                &instance.name.to_string()
+            ),
+        )?;
         Ok(())
     }
 
@@ -298,7 +336,13 @@ impl App {
             .filter(|i| &i.name == id)
             .collect::<Vec<&Instance>>()
             .first()
-            .map(|i| i.workspaces_ids.clone())
+            .map(|i| {
+                workspaces
+                    .iter()
+                    .map(|workspace| workspace.workspace_id)
+                    .filter(|workspace_id| i.is_workspace_selected(workspace_id))
+                    .collect()
+            })
             .unwrap_or(vec![]);
         if let Some(gui_instance) = self
             .state
@@ -324,7 +368,14 @@ impl App {
             .iter_mut()
             .find(|i| i.name == instance.name)
         {
-            instance_.workspaces_ids = selected_workspace_ids.clone();
+            instance_.all_workspaces = instance.all_workspaces;
+            instance_.excluded_workspaces_ids = instance.excluded_workspace_ids();
+            instance_.workspaces_configs = instance.workspaces_configs.clone();
+            instance_.workspaces_ids = if instance.all_workspaces {
+                vec![]
+            } else {
+                selected_workspace_ids.clone()
+            };
         };
 
         if let Some(instance_) = self
@@ -341,6 +392,38 @@ impl App {
         };
     }
 
+    /// Workspaces currently synchronized which are not selected in given instance anymore,
+    /// split between ones synchronized in synchronization folder and ones synchronized in a
+    /// folder chosen by user
+    fn removed_workspaces(&self, instance: &GuiInstance) -> (Vec<Workspace>, Vec<Workspace>) {
+        let previous = match self
+            .state
+            .instances
+            .iter()
+            .find(|i| i.name == instance.name)
+        {
+            Some(previous) => previous,
+            None => return (vec![], vec![]),
+        };
+        let selected: Instance = instance.clone().into();
+
+        instance
+            .workspaces
+            .iter()
+            .flatten()
+            .filter(|workspace| {
+                previous.is_workspace_selected(&workspace.workspace_id)
+                    && !selected.is_workspace_selected(&workspace.workspace_id)
+            })
+            .cloned()
+            .partition(|workspace| {
+                previous
+                    .workspace_config(&workspace.workspace_id)
+                    .folder_path
+                    .is_none()
+            })
+    }
+
     fn save_config(&mut self) -> Result<()> {
         let config = self.state.to_config();
         config.write()?;
@@ -354,14 +437,14 @@ impl App {
         let mut close = false;
 
         if let Some(error) = &self.windowed_error {
-            Window::new("⚠ Une erreur est survenue ⚠")
+            Window::new(tr!("⚠ An error occurred ⚠"))
                 .collapsible(false)
                 .resizable(false)
                 .anchor(Align2::CENTER_CENTER, vec2(0., 0.))
                 .show(ctx, |ui| {
                     ui.label(error.to_string());
                     ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
-                        close = ui.button("Fermer").clicked();
+                        close = ui.button(tr!("Close")).clicked();
                     })
                 });
         }
@@ -376,15 +459,18 @@ impl App {
         let mut confirm = false;
 
         if let Some(instance_id) = &self.delete_instance {
-            Window::new(&format!("Supprimer {} ?", instance_id))
+            Window::new(tr!("Delete {} ?", instance_id))
                 .collapsible(false)
                 .resizable(false)
                 .anchor(Align2::CENTER_CENTER, vec2(0., 0.))
                 .show(ctx, |ui| {
-                    ui.label(format!("Voulez-vous réellement supprimer {} ?\nLes données sur votre disque dur ne seront pas supprimés.", instance_id));
+                    ui.label(tr!(
+                        "Do you really want to delete {} ?\nData on your hard drive will not be deleted.",
+                        instance_id
+                    ));
                     ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
-                        confirm = ui.button("Supprimer").clicked();
-                        close = ui.button("Annuler").clicked();
+                        confirm = ui.button(tr!("Delete")).clicked();
+                        close = ui.button(tr!("Cancel")).clicked();
                     })
                 });
 
@@ -400,20 +486,80 @@ impl App {
         Ok(())
     }
 
+    fn removed_workspaces_window(&mut self, ctx: &EguiContext) -> Result<()> {
+        let mut cancel = false;
+        let mut keep = false;
+        let mut confirm = false;
+
+        if let Some((instance, workspaces, chosen_folders)) = &self.removed_workspaces {
+            Window::new(tr!("Workspaces removed from synchronization"))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(Align2::CENTER_CENTER, vec2(0., 0.))
+                .show(ctx, |ui| {
+                    ui.label(match self.state.removed_workspace_policy {
+                        RemovedWorkspacePolicy::Delete => {
+                            tr!("Local folders of these workspaces will be deleted :")
+                        }
+                        _ => tr!("Local folders of these workspaces will be archived :"),
+                    });
+                    for workspace in workspaces {
+                        ui.label(format!("• {}", workspace.label));
+                    }
+                    if !chosen_folders.is_empty() {
+                        ui.label(tr!("Folders chosen for these workspaces are kept :"));
+                        for workspace in chosen_folders {
+                            ui.label(format!("• {}", workspace.label));
+                        }
+                    }
+                    ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
+                        confirm = ui
+                            .button(policy_text(self.state.removed_workspace_policy))
+                            .clicked();
+                        keep = ui.button(tr!("Keep folders")).clicked();
+                        cancel = ui.button(tr!("Cancel")).clicked();
+                    })
+                });
+
+            if keep || confirm {
+                let instance = instance.clone();
+                let trsync_uids = workspaces
+                    .iter()
+                    .map(|workspace| TrsyncUid::new(instance.name.clone(), workspace.workspace_id))
+                    .collect();
+                self.update_instance_selected_workspaces(&instance);
+                self.save_config()?;
+                if confirm {
+                    if let Err(error) = self
+                        .main_sender
+                        .send(DaemonMessage::CleanRemovedWorkspaces(trsync_uids))
+                    {
+                        self.windowed_error = Some(error.to_string())
+                    }
+                }
+            }
+        }
+
+        if cancel || keep || confirm {
+            self.removed_workspaces = None;
+        }
+        Ok(())
+    }
+
     fn check_instance_credentials(&mut self, instance: GuiInstance) -> Result<()> {
         self.reset_instance_errors(&instance.name);
         let mut errors = vec![];
 
         if instance.address.trim().is_empty() {
-            errors.push("Veuillez saisir une adresse (ex. mon.tracim.fr)".to_string());
+            errors.push(tr!("Please enter an address (ex. my.tracim.fr)").to_string());
         }
 
         if instance.username.trim().is_empty() {
-            errors.push("Veuillez saisir un identifiant (username ou email)".to_string());
+            errors.push(tr!("Please enter a login (username or email)").to_string());
         }
 
         if instance.password.trim().is_empty() {
-            errors.push("Veuillez saisir un mot de passe".to_string());
+            errors.push(tr!("Please enter a password").to_string());
         }
 
         if !errors.is_empty() {
@@ -46,6 +46,7 @@ impl CredentialUpdater {
             self.instance.api_url(None),
             self.instance.username.clone(),
             self.instance.password.clone(),
+            &self.instance.transport,
         )
         .context("Construct http client")?
         .check_credentials()
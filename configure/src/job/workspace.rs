@@ -44,6 +44,7 @@ impl WorkspacesGrabber {
             self.instance.api_url(None),
             self.instance.username.clone(),
             self.instance.password.clone(),
+            &self.instance.transport,
         )
         .context("Construct http client")?
         .workspaces()
@@ -4,6 +4,8 @@ use eframe::{
     epaint::Color32,
 };
 
+use trsync_core::tr;
+
 use crate::event::Event;
 
 use super::instance::GuiInstance;
@@ -49,17 +51,17 @@ impl<'a> AddInstancePainter<'a> {
                 .striped(true)
                 .min_col_width(MIN_COL_WIDTH)
                 .show(ui, |ui| {
-                    let address_label = ui.label("Adresse (ex. mon.tracim.fr)");
+                    let address_label = ui.label(tr!("Address (ex. my.tracim.fr)"));
                     ui.text_edit_singleline(&mut self.instance.address)
                         .labelled_by(address_label.id);
                     ui.end_row();
 
-                    let username_label = ui.label("Identifiant (username ou email)");
+                    let username_label = ui.label(tr!("Login (username or email)"));
                     ui.text_edit_singleline(&mut self.instance.username)
                         .labelled_by(username_label.id);
                     ui.end_row();
 
-                    let password_label = ui.label("Mot de passe");
+                    let password_label = ui.label(tr!("Password"));
                     ui.add(TextEdit::singleline(&mut self.instance.password).password(true))
                         .labelled_by(password_label.id);
                     ui.end_row();
@@ -67,7 +69,7 @@ impl<'a> AddInstancePainter<'a> {
                     ui.label("");
 
                     ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
-                        if ui.button("Ajouter").clicked() {
+                        if ui.button(tr!("Add")).clicked() {
                             events.push(Event::ValidateNewInstance(self.instance.clone()));
                         }
                     });
@@ -80,7 +82,7 @@ impl<'a> AddInstancePainter<'a> {
                 ui.spacing_mut().item_spacing.x = 0.0;
                 ui.add(Spinner::new());
                 ui.add_space(4.0);
-                ui.label("Test de vos identifiants ...");
+                ui.label(tr!("Checking your credentials ..."));
             });
         }
 
@@ -1,11 +1,16 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, collections::HashMap};
 
 use eframe::{
     egui::{Grid, Layout, ScrollArea, Spinner, TextEdit, Ui},
     emath::Align,
     epaint::Color32,
 };
-use trsync_core::instance::{Instance, InstanceId, Workspace, WorkspaceId};
+use trsync_core::{
+    instance::{Instance, InstanceId, Workspace, WorkspaceConfig, WorkspaceId},
+    tr,
+    transfer::BandwidthConfig,
+    transport::TransportConfig,
+};
 
 use crate::event::Event;
 
@@ -52,17 +57,17 @@ impl<'a> InstancePainter<'a> {
                 .striped(true)
                 .min_col_width(MIN_COL_WIDTH)
                 .show(ui, |ui| {
-                    let address_label = ui.label("Adresse (ex. mon.tracim.fr)");
+                    let address_label = ui.label(tr!("Address (ex. my.tracim.fr)"));
                     ui.text_edit_singleline(&mut self.instance.address)
                         .labelled_by(address_label.id);
                     ui.end_row();
 
-                    let username_label = ui.label("Identifiant (username ou email)");
+                    let username_label = ui.label(tr!("Login (username or email)"));
                     ui.text_edit_singleline(&mut self.instance.username)
                         .labelled_by(username_label.id);
                     ui.end_row();
 
-                    let password_label = ui.label("Mot de passe");
+                    let password_label = ui.label(tr!("Password"));
                     ui.add(TextEdit::singleline(&mut self.instance.password).password(true))
                         .labelled_by(password_label.id);
                     ui.end_row();
@@ -70,10 +75,10 @@ impl<'a> InstancePainter<'a> {
                     ui.label("");
 
                     ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
-                        if ui.button("Valider").clicked() {
+                        if ui.button(tr!("Validate")).clicked() {
                             events.push(Event::InstanceCredentialsUpdated(self.instance.clone()));
                         }
-                        if ui.button("Supprimer").clicked() {
+                        if ui.button(tr!("Delete")).clicked() {
                             events.push(Event::DeleteInstanceWanted(self.instance.name.clone()));
                         }
                     });
@@ -92,7 +97,7 @@ impl<'a> InstancePainter<'a> {
                 ui.spacing_mut().item_spacing.x = 0.0;
                 ui.add(Spinner::new());
                 ui.add_space(4.0);
-                ui.label("La liste des espaces est en cours de chargement");
+                ui.label(tr!("Workspaces list is loading"));
             });
         }
 
@@ -103,16 +108,33 @@ impl<'a> InstancePainter<'a> {
                 .striped(true)
                 .min_col_width(MIN_COL_WIDTH)
                 .show(ui, |ui| {
-                    ui.label("Espaces à synchroniser");
+                    ui.label("");
+                    ui.checkbox(
+                        &mut self.instance.all_workspaces,
+                        tr!("All my workspaces, including future ones"),
+                    );
+                    ui.end_row();
+
+                    ui.label(tr!("Workspaces to synchronize"));
                     ui.set_height(320.);
                     ScrollArea::vertical()
                         .auto_shrink([false, false])
                         .show(ui, |ui| {
                             ui.vertical(|ui| {
-                                for (checked, _, label) in
-                                    &mut self.instance.workspaces_ids_checkboxes
+                                let instance = &mut *self.instance;
+                                for (checked, workspace_id, label) in
+                                    &mut instance.workspaces_ids_checkboxes
                                 {
-                                    ui.checkbox(checked, label.clone());
+                                    ui.horizontal(|ui| {
+                                        ui.checkbox(checked, label.clone());
+                                        workspace_folder(
+                                            ui,
+                                            instance
+                                                .workspaces_configs
+                                                .entry(*workspace_id)
+                                                .or_default(),
+                                        );
+                                    });
                                 }
                             })
                         });
@@ -120,7 +142,7 @@ impl<'a> InstancePainter<'a> {
 
                     ui.label("");
                     ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
-                        if ui.button("Valider").clicked() {
+                        if ui.button(tr!("Validate")).clicked() {
                             events.push(Event::InstanceSelectedWorkspacesValidated(
                                 self.instance.clone(),
                             ));
@@ -133,6 +155,31 @@ impl<'a> InstancePainter<'a> {
     }
 }
 
+/// Folder override of a workspace, with buttons to choose or reset it
+fn workspace_folder(ui: &mut Ui, workspace_config: &mut WorkspaceConfig) {
+    match &workspace_config.folder_path {
+        Some(folder_path) => {
+            ui.label(folder_path.clone());
+            if ui.small_button(tr!("Default folder")).clicked() {
+                workspace_config.folder_path = None;
+            }
+        }
+        None => {
+            if ui
+                .small_button(tr!("Choose folder"))
+                .on_hover_text(tr!(
+                    "Synchronize this workspace outside of synchronization folder"
+                ))
+                .clicked()
+            {
+                if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                    workspace_config.folder_path = Some(path.display().to_string());
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GuiInstance {
     pub name: InstanceId,
@@ -142,6 +189,11 @@ pub struct GuiInstance {
     pub password: String,
     pub workspaces: Option<Vec<Workspace>>,
     pub workspaces_ids_checkboxes: Vec<(bool, WorkspaceId, String)>,
+    pub all_workspaces: bool,
+    pub excluded_workspaces_ids: Vec<WorkspaceId>,
+    pub transport: TransportConfig,
+    pub bandwidth: BandwidthConfig,
+    pub workspaces_configs: HashMap<WorkspaceId, WorkspaceConfig>,
 }
 
 impl Default for GuiInstance {
@@ -154,6 +206,11 @@ impl Default for GuiInstance {
             password: Default::default(),
             workspaces: Default::default(),
             workspaces_ids_checkboxes: Default::default(),
+            all_workspaces: Default::default(),
+            excluded_workspaces_ids: Default::default(),
+            transport: Default::default(),
+            bandwidth: Default::default(),
+            workspaces_configs: Default::default(),
         }
     }
 }
@@ -176,6 +233,11 @@ impl GuiInstance {
             password,
             workspaces,
             workspaces_ids_checkboxes: vec![],
+            all_workspaces: false,
+            excluded_workspaces_ids: vec![],
+            transport: TransportConfig::default(),
+            bandwidth: BandwidthConfig::default(),
+            workspaces_configs: HashMap::new(),
         };
         self_.rebuild_workspaces_ids_checkboxes(&selected_workspaces_ids);
         self_
@@ -198,8 +260,11 @@ impl GuiInstance {
 
     pub fn api_url(&self, suffix: Option<&str>) -> String {
         let suffix = suffix.unwrap_or("");
-        let scheme = if self.unsecure { "http" } else { "https" };
-        format!("{}://{}/api{}", scheme, self.address, suffix)
+        format!(
+            "{}{}",
+            self.transport.api_url(self.unsecure, &self.address),
+            suffix
+        )
     }
 
     pub fn selected_workspace_ids(&self) -> Vec<WorkspaceId> {
@@ -209,11 +274,27 @@ impl GuiInstance {
             .filter_map(|(checked, id, _)| if *checked { Some(*id) } else { None })
             .collect()
     }
+
+    /// Unchecked workspaces when all workspaces are synchronized (previous ones if
+    /// workspaces are not loaded)
+    pub fn excluded_workspace_ids(&self) -> Vec<WorkspaceId> {
+        if !self.all_workspaces {
+            return vec![];
+        }
+        if self.workspaces_ids_checkboxes.is_empty() {
+            return self.excluded_workspaces_ids.clone();
+        }
+
+        self.workspaces_ids_checkboxes
+            .iter()
+            .filter_map(|(checked, id, _)| if *checked { None } else { Some(*id) })
+            .collect()
+    }
 }
 
 impl From<&Instance> for GuiInstance {
     fn from(instance: &Instance) -> Self {
-        Self::new(
+        let mut gui_instance = Self::new(
             instance.name.clone(),
             instance.address.clone(),
             instance.unsecure,
@@ -221,7 +302,13 @@ impl From<&Instance> for GuiInstance {
             instance.password.clone(),
             None,
             instance.workspaces_ids.clone(),
-        )
+        );
+        gui_instance.all_workspaces = instance.all_workspaces;
+        gui_instance.excluded_workspaces_ids = instance.excluded_workspaces_ids.clone();
+        gui_instance.transport = instance.transport.clone();
+        gui_instance.bandwidth = instance.bandwidth.clone();
+        gui_instance.workspaces_configs = instance.workspaces_configs.clone();
+        gui_instance
     }
 }
 
@@ -234,6 +321,11 @@ impl From<GuiInstance> for Instance {
             username: val.username.clone(),
             password: val.password.clone(),
             workspaces_ids: val.selected_workspace_ids(),
+            all_workspaces: val.all_workspaces,
+            excluded_workspaces_ids: val.excluded_workspace_ids(),
+            transport: val.transport.clone(),
+            bandwidth: val.bandwidth.clone(),
+            workspaces_configs: val.workspaces_configs.clone(),
         }
     }
 }
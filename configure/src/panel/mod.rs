@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use trsync_core::tr;
+
 use self::instance::GuiInstance;
 
 pub mod add;
@@ -16,9 +18,9 @@ pub enum Panel {
 impl Display for Panel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Panel::Root => f.write_str("Configuration"),
+            Panel::Root => f.write_str(tr!("Configuration")),
             Panel::Instance(instance) => f.write_str(&instance.name.to_string()),
-            Panel::AddInstance(_) => f.write_str("Ajouter"),
+            Panel::AddInstance(_) => f.write_str(tr!("Add")),
         }
     }
 }
@@ -1,4 +1,5 @@
-use eframe::egui::{Grid, Ui};
+use eframe::egui::{ComboBox, Grid, Ui};
+use trsync_core::{config::RemovedWorkspacePolicy, tr};
 
 use crate::{event::Event, state::State, utils::label_with_help};
 
@@ -28,6 +29,8 @@ impl ConfigurationPainter {
                 events.extend(self.base_folder(ui, state));
                 ui.end_row();
                 events.extend(self.prevent_sync_delete(ui, state));
+                ui.end_row();
+                events.extend(self.removed_workspace_policy(ui, state));
             });
 
         events
@@ -36,7 +39,7 @@ impl ConfigurationPainter {
     pub fn base_folder(&mut self, ui: &mut Ui, state: &mut State) -> Vec<Event> {
         let mut events = vec![];
 
-        ui.label("Dossier de synchronisation");
+        ui.label(tr!("Synchronization folder"));
         ui.horizontal_wrapped(|ui| {
             let ellipsis = "...".to_string();
             let text = match state.base_folder.char_indices().nth(28) {
@@ -45,7 +48,7 @@ impl ConfigurationPainter {
             };
             ui.label(text);
 
-            if ui.button("Sélectionner").clicked() {
+            if ui.button(tr!("Select")).clicked() {
                 if let Some(path) = rfd::FileDialog::new().pick_folder() {
                     state.base_folder = path.display().to_string();
                     events.push(Event::GlobalConfigurationUpdated);
@@ -60,11 +63,10 @@ impl ConfigurationPainter {
         let mut events = vec![];
 
         ui.add(label_with_help(
-            "Confirmer les opérations au démarrage",
-            "Lorsque TrSync effectue une synchronisation de départ \
-            (au démarrage ou après une interruption de connexion) \
-            une confirmation des opérations vous sera demandé dans la fenêtre \
-            du moniteur.",
+            tr!("Confirm startup operations"),
+            tr!("When TrSync runs a startup synchronization \
+                (at startup or after a connection interruption) \
+                you will be asked to confirm operations in monitor window."),
         ));
         if ui.checkbox(&mut state.confirm_startup_sync, "").changed() {
             events.push(Event::GlobalConfigurationUpdated);
@@ -73,9 +75,8 @@ impl ConfigurationPainter {
         ui.end_row();
 
         ui.add(label_with_help(
-            "Popup de confirmation des opérations au démarrage",
-            "Affiche la fenêtre de confirmation de la synchronization de départ \
-            lorsque elle est disponible.",
+            tr!("Startup operations confirmation popup"),
+            tr!("Display startup synchronization confirmation window when it is available."),
         ));
         if ui
             .checkbox(&mut state.popup_confirm_startup_sync, "")
@@ -86,4 +87,46 @@ impl ConfigurationPainter {
 
         events
     }
+
+    pub fn removed_workspace_policy(&mut self, ui: &mut Ui, state: &mut State) -> Vec<Event> {
+        let mut events = vec![];
+
+        ui.add(label_with_help(
+            tr!("Folders of removed workspaces"),
+            tr!(
+                "What to do with the local folder of a workspace which is not synchronized \
+                anymore. You will be asked to confirm before archiving or deleting it."
+            ),
+        ));
+        ComboBox::from_id_source("removed_workspace_policy")
+            .selected_text(policy_text(state.removed_workspace_policy))
+            .show_ui(ui, |ui| {
+                for policy in [
+                    RemovedWorkspacePolicy::Keep,
+                    RemovedWorkspacePolicy::Archive,
+                    RemovedWorkspacePolicy::Delete,
+                ] {
+                    if ui
+                        .selectable_value(
+                            &mut state.removed_workspace_policy,
+                            policy,
+                            policy_text(policy),
+                        )
+                        .changed()
+                    {
+                        events.push(Event::GlobalConfigurationUpdated);
+                    }
+                }
+            });
+
+        events
+    }
+}
+
+pub fn policy_text(policy: RemovedWorkspacePolicy) -> &'static str {
+    match policy {
+        RemovedWorkspacePolicy::Keep => tr!("Keep"),
+        RemovedWorkspacePolicy::Archive => tr!("Archive"),
+        RemovedWorkspacePolicy::Delete => tr!("Delete"),
+    }
 }
@@ -1,5 +1,5 @@
 use trsync_core::{
-    config::ManagerConfig,
+    config::{ManagerConfig, RemovedWorkspacePolicy},
     instance::{Instance, InstanceId},
 };
 
@@ -12,7 +12,10 @@ pub struct State {
     pub icons_path: Option<String>,
     pub confirm_startup_sync: bool,
     pub popup_confirm_startup_sync: bool,
+    pub removed_workspace_policy: RemovedWorkspacePolicy,
     pub instances: Vec<Instance>,
+    // Keep values which are not editable in this window
+    config: ManagerConfig,
 }
 
 impl State {
@@ -35,7 +38,9 @@ impl State {
             icons_path: config.icons_path.clone(),
             confirm_startup_sync: config.confirm_startup_sync,
             popup_confirm_startup_sync: config.popup_confirm_startup_sync,
+            removed_workspace_policy: config.removed_workspace_policy,
             instances: config.instances.clone(),
+            config: config.clone(),
         }
     }
 
@@ -47,6 +52,8 @@ impl State {
             allow_raw_passwords: false,
             confirm_startup_sync: self.confirm_startup_sync,
             popup_confirm_startup_sync: self.popup_confirm_startup_sync,
+            removed_workspace_policy: self.removed_workspace_policy,
+            ..self.config.clone()
         }
     }
 
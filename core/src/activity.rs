@@ -9,7 +9,15 @@ use std::{
     time::Duration,
 };
 
-use crate::{change::Change, job::JobIdentifier, sync::SyncChannels};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    change::Change,
+    job::JobIdentifier,
+    status::{JobStatus, StatusUpdate},
+    sync::SyncChannels,
+    tr,
+};
 
 #[derive(Debug)]
 pub enum State {
@@ -17,14 +25,18 @@ pub enum State {
     Working,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Activity {
     Idle,
     Job(String),
     StartupSync(Option<Change>),
     WaitingStartupSyncConfirmation,
     WaitingConnection,
+    OutsideSyncWindow,
+    Paused,
     Error,
+    /// Workspace was deleted or user is not member of it anymore, synchronization is stopped
+    AccessLost,
 }
 
 impl Activity {
@@ -35,26 +47,34 @@ impl Activity {
     fn is_startup_sync(&self) -> bool {
         matches!(self, Activity::StartupSync(_))
     }
+
+    pub fn is_paused(&self) -> bool {
+        matches!(self, Activity::Paused)
+    }
 }
 
 impl Display for Activity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Activity::Idle => f.write_str("En veille"),
+            Activity::Idle => f.write_str(tr!("Idle")),
             Activity::Job(message) => f.write_str(message),
             Activity::StartupSync(change) => match change {
-                Some(change) => f.write_str(&format!("Synchronisation ({})", change)),
-                None => f.write_str("Synchronisation"),
+                Some(change) => f.write_str(&tr!("Synchronizing ({})", change)),
+                None => f.write_str(tr!("Synchronizing")),
             },
-            Activity::WaitingStartupSyncConfirmation => f.write_str("Attend confirmation"),
-            Activity::WaitingConnection => f.write_str("Attend connection"),
-            Activity::Error => f.write_str("Erreur"),
+            Activity::WaitingStartupSyncConfirmation => f.write_str(tr!("Waiting confirmation")),
+            Activity::WaitingConnection => f.write_str(tr!("Waiting connection")),
+            Activity::OutsideSyncWindow => f.write_str(tr!("Outside sync hours")),
+            Activity::Paused => f.write_str(tr!("Paused")),
+            Activity::Error => f.write_str(tr!("Error")),
+            Activity::AccessLost => f.write_str(tr!("Access lost")),
         }
     }
 }
 
 pub struct ActivityState {
     activities: HashMap<JobIdentifier, Activity>,
+    statuses: HashMap<JobIdentifier, JobStatus>,
     pending_startup_sync: Vec<(JobIdentifier, SyncChannels)>,
 }
 
@@ -62,14 +82,29 @@ impl ActivityState {
     pub fn new() -> Self {
         Self {
             activities: HashMap::new(),
+            statuses: HashMap::new(),
             pending_startup_sync: vec![],
         }
     }
 
     pub fn set_activity(&mut self, job_identifier: JobIdentifier, activity: Activity) {
+        // Replace the key too, its folder can have changed
+        self.activities.remove(&job_identifier);
         self.activities.insert(job_identifier, activity);
     }
 
+    pub fn update_status(&mut self, job_identifier: JobIdentifier, update: StatusUpdate) {
+        self.statuses
+            .entry(job_identifier)
+            .or_default()
+            .apply(update);
+    }
+
+    pub fn remove_job(&mut self, job_identifier: &JobIdentifier) {
+        self.activities.remove(job_identifier);
+        self.statuses.remove(job_identifier);
+    }
+
     pub fn is_working(&self) -> bool {
         for activity in self.activities.values() {
             if activity.is_job() || activity.is_startup_sync() {
@@ -80,10 +115,24 @@ impl ActivityState {
         false
     }
 
+    /// At least one job can't reach its server
+    pub fn is_disconnected(&self) -> bool {
+        self.statuses
+            .values()
+            .any(|status| status.is_disconnected())
+    }
+
     pub fn activities(&self) -> &HashMap<JobIdentifier, Activity> {
         &self.activities
     }
 
+    pub fn status(&self, job_identifier: &JobIdentifier) -> JobStatus {
+        self.statuses
+            .get(job_identifier)
+            .cloned()
+            .unwrap_or_default()
+    }
+
     pub fn new_pending_startup_sync(&mut self, startup_sync: (JobIdentifier, SyncChannels)) {
         self.pending_startup_sync.push(startup_sync)
     }
@@ -95,17 +144,39 @@ impl Default for ActivityState {
     }
 }
 
+#[derive(Debug, Clone)]
+pub enum JobUpdate {
+    Activity(Activity),
+    Status(StatusUpdate),
+    /// Job is not synchronized anymore (workspace removed)
+    Removed,
+}
+
 #[derive(Debug)]
 pub struct WrappedActivity {
     job_identifier: JobIdentifier,
-    activity: Activity,
+    update: JobUpdate,
 }
 
 impl WrappedActivity {
     pub fn new(job: JobIdentifier, activity: Activity) -> Self {
         Self {
             job_identifier: job,
-            activity,
+            update: JobUpdate::Activity(activity),
+        }
+    }
+
+    pub fn status(job: JobIdentifier, update: StatusUpdate) -> Self {
+        Self {
+            job_identifier: job,
+            update: JobUpdate::Status(update),
+        }
+    }
+
+    pub fn removed(job: JobIdentifier) -> Self {
+        Self {
+            job_identifier: job,
+            update: JobUpdate::Removed,
         }
     }
 
@@ -113,8 +184,8 @@ impl WrappedActivity {
         &self.job_identifier
     }
 
-    pub fn activity(&self) -> &Activity {
-        &self.activity
+    pub fn update(&self) -> &JobUpdate {
+        &self.update
     }
 }
 
@@ -141,10 +212,19 @@ impl ActivityMonitor {
         loop {
             match self.receiver.recv_timeout(Duration::from_millis(250)) {
                 // TODO : no unwrap
-                Ok(wrapped_activity) => self.state.lock().unwrap().set_activity(
-                    wrapped_activity.job_identifier().clone(),
-                    wrapped_activity.activity().clone(),
-                ),
+                Ok(wrapped_activity) => {
+                    let job_identifier = wrapped_activity.job_identifier().clone();
+                    let mut state = self.state.lock().unwrap();
+                    match wrapped_activity.update() {
+                        JobUpdate::Activity(activity) => {
+                            state.set_activity(job_identifier, activity.clone())
+                        }
+                        JobUpdate::Status(update) => {
+                            state.update_status(job_identifier, update.clone())
+                        }
+                        JobUpdate::Removed => state.remove_job(&job_identifier),
+                    }
+                }
                 Err(RecvTimeoutError::Timeout) => {
                     if self.stop_signal.load(Ordering::Relaxed) {
                         log::info!("Finished ActivityMonitor (on stop signal)");
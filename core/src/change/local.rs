@@ -1,6 +1,8 @@
 use std::{fmt::Display, path::PathBuf};
 
-#[derive(Debug, Eq, PartialEq, Clone)]
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub enum LocalChange {
     New(PathBuf),
     Disappear(PathBuf),
@@ -1,11 +1,13 @@
 use std::fmt::Display;
 
+use serde_derive::{Deserialize, Serialize};
+
 use self::{local::LocalChange, remote::RemoteChange};
 
 pub mod local;
 pub mod remote;
 
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub enum Change {
     Local(LocalChange),
     Remote(RemoteChange),
@@ -1,8 +1,10 @@
 use std::{fmt::Display, path::PathBuf};
 
+use serde_derive::{Deserialize, Serialize};
+
 use crate::instance::ContentId;
 
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub enum RemoteChange {
     New(ContentId, PathBuf),
     Disappear(ContentId, PathBuf),
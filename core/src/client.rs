@@ -1,4 +1,4 @@
-use std::{fs, io, path::PathBuf, time::Duration};
+use std::{fs, io, path::PathBuf};
 
 use mockall::automock;
 use reqwest::{
@@ -11,17 +11,31 @@ use std::str::FromStr;
 use thiserror::Error;
 
 use crate::{
+    error::ErrorClass,
     instance::{ContentFileName, ContentId, RevisionId, Workspace, WorkspaceId},
+    server::{ServerCapabilities, ServerVersion},
+    transfer::TransferControl,
+    transport::TransportConfig,
     types::ContentType,
     user::UserId,
     utils::extract_html_body,
     HTML_DOCUMENT_LOCAL_EXTENSION,
 };
 
+// Tracim error codes (see `ErrorCode` of tracim backend)
 pub const CONTENT_ALREADY_EXIST_ERR_CODE: u64 = 3002;
 pub const CONTENT_IN_NOT_EDITABLE_STATE_ERR_CODE: u64 = 2044;
 pub const CONTENT_NOT_FOUND: u64 = 1003;
+pub const WORKSPACE_NOT_FOUND_ERR_CODE: u64 = 1002;
+pub const FILE_SIZE_OVER_MAX_LIMITATION_ERR_CODE: u64 = 3008;
+pub const FILE_SIZE_OVER_WORKSPACE_EMPTY_SPACE_ERR_CODE: u64 = 3009;
+pub const FILE_SIZE_OVER_OWNER_EMPTY_SPACE_ERR_CODE: u64 = 3010;
+pub const INSUFFICIENT_USER_ROLE_ERR_CODE: u64 = 5001;
+pub const INSUFFICIENT_USER_PROFILE_ERR_CODE: u64 = 5002;
+pub const FORBIDDEN_FILENAME_ERR_CODE: u64 = 2067;
 pub const DEFAULT_CLIENT_TIMEOUT: u64 = 30;
+/// Contents count asked by request when listing workspace contents
+pub const CONTENTS_PAGE_SIZE: usize = 500;
 
 #[derive(Debug, Clone, Error)]
 pub enum TracimClientError {
@@ -45,23 +59,92 @@ pub enum TracimClientError {
     AuthenticationError,
     #[error("File {0} not found: {1}")]
     FileNotFound(PathBuf, String),
+    #[error("Permission denied: `{0}`")]
+    PermissionDenied(String),
+    #[error("Quota exceeded: `{0}`")]
+    QuotaExceeded(String),
+    #[error("File is too large: `{0}`")]
+    FileTooLarge(String),
+    #[error("Insufficient role: `{0}`")]
+    InsufficientRole(String),
+    #[error("Workspace not found")]
+    WorkspaceNotFound,
+    #[error("Forbidden file name: `{0}`")]
+    ForbiddenFileName(String),
+    #[error("Unsupported server: {0}")]
+    UnsupportedServer(String),
 }
 
 impl TracimClientError {
-    fn from_code(error_code: u64) -> Option<TracimClientError> {
+    fn from_code(error_code: u64, message: String) -> Option<TracimClientError> {
         match error_code {
             CONTENT_NOT_FOUND => Some(TracimClientError::ContentNotFound),
             CONTENT_ALREADY_EXIST_ERR_CODE => Some(TracimClientError::ContentAlreadyExist),
             CONTENT_IN_NOT_EDITABLE_STATE_ERR_CODE => {
                 Some(TracimClientError::ContentDeletedOrArchived)
             }
+            WORKSPACE_NOT_FOUND_ERR_CODE => Some(TracimClientError::WorkspaceNotFound),
+            FILE_SIZE_OVER_MAX_LIMITATION_ERR_CODE => {
+                Some(TracimClientError::FileTooLarge(message))
+            }
+            FILE_SIZE_OVER_WORKSPACE_EMPTY_SPACE_ERR_CODE
+            | FILE_SIZE_OVER_OWNER_EMPTY_SPACE_ERR_CODE => {
+                Some(TracimClientError::QuotaExceeded(message))
+            }
+            INSUFFICIENT_USER_ROLE_ERR_CODE | INSUFFICIENT_USER_PROFILE_ERR_CODE => {
+                Some(TracimClientError::InsufficientRole(message))
+            }
+            FORBIDDEN_FILENAME_ERR_CODE => Some(TracimClientError::ForbiddenFileName(message)),
             _ => None,
         }
     }
-}
 
-impl From<reqwest::Error> for TracimClientError {
-    fn from(error: reqwest::Error) -> Self {
+    /// Error which will occur again while the local file is unchanged, so retrying
+    /// it is useless
+    pub fn is_file_rejection(&self) -> bool {
+        matches!(
+            self,
+            TracimClientError::FileTooLarge(_) | TracimClientError::ForbiddenFileName(_)
+        )
+    }
+
+    /// Error deduced from response status code, when Tracim error code is unknown
+    fn from_status(status_code: u16, message: String) -> Option<TracimClientError> {
+        match status_code {
+            401 => Some(TracimClientError::AuthenticationError),
+            403 => Some(TracimClientError::PermissionDenied(message)),
+            413 => Some(TracimClientError::FileTooLarge(message)),
+            507 => Some(TracimClientError::QuotaExceeded(message)),
+            _ => None,
+        }
+    }
+
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            TracimClientError::ConnectionError | TracimClientError::TimeoutError => {
+                ErrorClass::Network
+            }
+            TracimClientError::AuthenticationError => ErrorClass::Authentication,
+            TracimClientError::PermissionDenied(_)
+            | TracimClientError::InsufficientRole(_)
+            | TracimClientError::WorkspaceNotFound => ErrorClass::Permission,
+            TracimClientError::QuotaExceeded(_) | TracimClientError::FileTooLarge(_) => {
+                ErrorClass::Quota
+            }
+            TracimClientError::ContentNotFound | TracimClientError::ContentDeletedOrArchived => {
+                ErrorClass::NotFound
+            }
+            TracimClientError::FileNotFound(_, _) => ErrorClass::LocalIo,
+            TracimClientError::ContentAlreadyExist
+            | TracimClientError::Unknown(_)
+            | TracimClientError::PrepareError(_)
+            | TracimClientError::InvalidResponse(_, _)
+            | TracimClientError::ForbiddenFileName(_)
+            | TracimClientError::UnsupportedServer(_) => ErrorClass::Other,
+        }
+    }
+
+    pub fn from_reqwest(error: &reqwest::Error) -> Self {
         if error.is_connect() {
             return Self::ConnectionError;
         }
@@ -74,6 +157,12 @@ impl From<reqwest::Error> for TracimClientError {
     }
 }
 
+impl From<reqwest::Error> for TracimClientError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::from_reqwest(&error)
+    }
+}
+
 impl From<anyhow::Error> for TracimClientError {
     fn from(error: anyhow::Error) -> Self {
         TracimClientError::Unknown(format!("{:#}", error))
@@ -141,8 +230,11 @@ pub trait TracimClient {
         file_name: &ContentFileName,
         parent_id: ParentIdParameter,
     ) -> Result<Option<ContentId>, TracimClientError>;
-    // TODO : Iterable
-    fn get_contents(&self) -> Result<Vec<RemoteContent>, TracimClientError>;
+    /// Read one page of workspace contents (first one if no token given)
+    fn get_contents_page(
+        &self,
+        page_token: Option<String>,
+    ) -> Result<ContentsPage, TracimClientError>;
     #[allow(clippy::ptr_arg)]
     fn fill_file_with_content(
         &self,
@@ -166,6 +258,7 @@ pub struct Client {
     username: String,
     password: String,
     client: reqwest::blocking::Client,
+    async_client: reqwest::Client,
 }
 
 impl Client {
@@ -173,15 +266,16 @@ impl Client {
         address: String,
         username: String,
         password: String,
+        transport: &TransportConfig,
     ) -> Result<Self, TracimClientError> {
-        let client = reqwest::blocking::Client::builder()
-            .timeout(Duration::from_secs(DEFAULT_CLIENT_TIMEOUT))
-            .build()?;
+        let client = transport.client()?;
+        let async_client = transport.async_client()?;
         Ok(Self {
             address,
             username,
             password,
             client,
+            async_client,
         })
     }
 
@@ -230,6 +324,77 @@ impl Client {
             response.status()
         )))
     }
+
+    /// Live messages stream of given user (all its workspaces)
+    pub async fn live_messages_response(
+        &self,
+        user_id: &UserId,
+    ) -> Result<reqwest::Response, TracimClientError> {
+        let response = self
+            .async_client
+            .request(
+                Method::GET,
+                format!("{}/users/{}/live_messages", self.address, user_id),
+            )
+            .basic_auth(self.username.clone(), Some(self.password.clone()))
+            .send()
+            .await?;
+
+        match response.status().as_u16() {
+            200 => Ok(response),
+            401 => Err(TracimClientError::AuthenticationError),
+            status_code => Err(TracimClientError::Unknown(format!(
+                "Unexpected response status {} : '{}'",
+                status_code,
+                response.text().await?
+            ))),
+        }
+    }
+}
+
+/// Iterate over workspace contents of given client, page by page
+pub fn contents_pages(client: &dyn TracimClient) -> ContentsPages<'_> {
+    ContentsPages {
+        client,
+        next_page_token: None,
+        finished: false,
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ContentsPage {
+    pub contents: Vec<RemoteContent>,
+    /// Token to give to read next page, None if this is the last one
+    pub next_page_token: Option<String>,
+}
+
+/// Follow workspace contents pagination, one request by page
+pub struct ContentsPages<'a> {
+    client: &'a dyn TracimClient,
+    next_page_token: Option<String>,
+    finished: bool,
+}
+
+impl Iterator for ContentsPages<'_> {
+    type Item = Result<Vec<RemoteContent>, TracimClientError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        match self.client.get_contents_page(self.next_page_token.take()) {
+            Ok(page) => {
+                self.finished = page.next_page_token.is_none();
+                self.next_page_token = page.next_page_token;
+                Some(Ok(page.contents))
+            }
+            Err(error) => {
+                self.finished = true;
+                Some(Err(error))
+            }
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -250,6 +415,10 @@ pub struct Tracim {
     base_address: String,
     workspace_id: WorkspaceId,
     client: reqwest::blocking::Client,
+    transfer_client: reqwest::blocking::Client,
+    async_client: reqwest::Client,
+    transfer: TransferControl,
+    capabilities: Option<ServerCapabilities>,
     username: String,
     password: String,
 }
@@ -258,17 +427,112 @@ impl Tracim {
     pub fn new(
         base_address: String,
         workspace_id: WorkspaceId,
-        client: reqwest::blocking::Client,
+        transport: &TransportConfig,
         username: String,
         password: String,
-    ) -> Self {
-        Self {
+    ) -> Result<Self, TracimClientError> {
+        Ok(Self {
             base_address,
             workspace_id,
-            client,
+            client: transport.client()?,
+            transfer_client: transport.transfer_client()?,
+            async_client: transport.async_client()?,
+            transfer: TransferControl::default(),
+            capabilities: None,
             username,
             password,
+        })
+    }
+
+    pub fn transfer_control(mut self, value: TransferControl) -> Self {
+        self.transfer = value;
+        self
+    }
+
+    /// Server capabilities, if already known. Recent server behaviors are assumed else.
+    pub fn capabilities(mut self, value: Option<ServerCapabilities>) -> Self {
+        self.capabilities = value;
+        self
+    }
+
+    /// Query server version and enabled content types
+    pub fn server_capabilities(&self) -> Result<ServerCapabilities, TracimClientError> {
+        let response = self
+            .client
+            .request(Method::GET, format!("{}system/about", self.base_address))
+            .basic_auth(self.username.clone(), Some(self.password.clone()))
+            .send()?;
+        match response.status().as_u16() {
+            200 => {}
+            404 => {
+                return Err(TracimClientError::UnsupportedServer(
+                    "Server don't expose Tracim version (is it a Tracim server ?)".to_string(),
+                ))
+            }
+            _ => return Err(self.response_error(response)?),
+        }
+        let about = response.json::<Value>().map_err(|error| {
+            TracimClientError::UnsupportedServer(format!("Unreadable server version : {}", error))
+        })?;
+        let version = about["version"]
+            .as_str()
+            .ok_or(TracimClientError::UnsupportedServer(format!(
+                "No version in server description : {}",
+                about
+            )))?
+            .parse::<ServerVersion>()
+            .map_err(TracimClientError::UnsupportedServer)?;
+
+        let response = self
+            .client
+            .request(
+                Method::GET,
+                format!("{}system/content_types", self.base_address),
+            )
+            .basic_auth(self.username.clone(), Some(self.password.clone()))
+            .send()?;
+        if response.status().as_u16() != 200 {
+            return Err(self.response_error(response)?);
         }
+        let content_types = response
+            .json::<Vec<Value>>()?
+            .iter()
+            .filter_map(|content_type| content_type["slug"].as_str())
+            .filter_map(|slug| ContentType::from_str(slug).ok())
+            .collect();
+
+        ServerCapabilities::new(version, content_types)
+            .map_err(TracimClientError::UnsupportedServer)
+    }
+
+    /// False if workspace has been deleted or user is not member of it anymore
+    pub fn workspace_accessible(&self) -> Result<bool, TracimClientError> {
+        let response = self
+            .client
+            .request(
+                Method::GET,
+                format!("{}workspaces/{}", self.base_address, self.workspace_id),
+            )
+            .basic_auth(self.username.clone(), Some(self.password.clone()))
+            .send()?;
+        match response.status().as_u16() {
+            200 => Ok(true),
+            404 => Ok(false),
+            _ => match self.response_error(response)? {
+                TracimClientError::WorkspaceNotFound
+                | TracimClientError::PermissionDenied(_)
+                | TracimClientError::InsufficientRole(_) => Ok(false),
+                error => Err(error),
+            },
+        }
+    }
+
+    /// True if server is able to manage this content type (assumed if unknown)
+    fn supports(&self, content_type: &ContentType) -> bool {
+        self.capabilities
+            .as_ref()
+            .map(|capabilities| capabilities.supports(content_type))
+            .unwrap_or(true)
     }
 
     pub fn workspace_url(&self, suffix: &str) -> String {
@@ -339,7 +603,16 @@ impl Tracim {
     }
 
     fn response_error(&self, response: Response) -> Result<TracimClientError, TracimClientError> {
-        let content_value = response.json::<Value>()?;
+        let status_code = response.status().as_u16();
+        let content_value = match response.json::<Value>() {
+            Ok(content_value) => content_value,
+            Err(error) => {
+                return Ok(
+                    TracimClientError::from_status(status_code, error.to_string())
+                        .unwrap_or_else(|| error.into()),
+                )
+            }
+        };
         let error_code =
             content_value["code"]
                 .as_u64()
@@ -348,15 +621,19 @@ impl Tracim {
                     content_value["code"].clone(),
                 ))?;
 
-        if let Some(error) = TracimClientError::from_code(error_code) {
+        let message = content_value["message"]
+            .as_str()
+            .unwrap_or("Unknown error")
+            .to_string();
+        if let Some(error) = TracimClientError::from_code(error_code, message.clone()) {
             return Ok(error);
         }
 
-        if let Some(message) = content_value["message"].as_str() {
-            return Ok(TracimClientError::Unknown(message.to_string()));
+        if let Some(error) = TracimClientError::from_status(status_code, message.clone()) {
+            return Ok(error);
         }
 
-        Ok(TracimClientError::Unknown("Unknown error".to_string()))
+        Ok(TracimClientError::Unknown(message))
     }
 
     fn create_folder(
@@ -451,18 +728,11 @@ impl Tracim {
             form = form.text("parent_id", parent_id.to_string());
         };
         let url = self.workspace_url("files");
-        form = form.file("files", &path).map_err(|e| {
-            TracimClientError::PrepareError(format!(
-                "Error during preparation of form for file {} : {}",
-                path.display(),
-                e
-            ))
-        })?;
+        form = form.part("files", self.file_part(&path)?);
 
-        let client = reqwest::blocking::Client::builder()
-            .timeout(Duration::from_secs(600))
-            .build()?;
-        let response = client
+        let _permit = self.transfer.permit();
+        let response = self
+            .transfer_client
             .request(Method::POST, url)
             .basic_auth(self.username.clone(), Some(self.password.clone()))
             .multipart(form)
@@ -476,15 +746,7 @@ impl Tracim {
         content_id: ContentId,
         path: &PathBuf,
     ) -> Result<RevisionId, TracimClientError> {
-        let form = multipart::Form::new()
-            .file("files", path)
-            .map_err(|error| {
-                TracimClientError::PrepareError(format!(
-                    "Error during preparation of form for file {} : {}",
-                    path.display(),
-                    error
-                ))
-            })?;
+        let form = multipart::Form::new().part("files", self.file_part(path)?);
         let file_name = path
             .file_name()
             .ok_or(TracimClientError::PrepareError(format!(
@@ -498,10 +760,9 @@ impl Tracim {
             )))?;
         let url = self.workspace_url(&format!("files/{}/raw/{}", content_id, file_name));
 
-        let client = reqwest::blocking::Client::builder()
-            .timeout(Duration::from_secs(600))
-            .build()?;
-        let response = client
+        let _permit = self.transfer.permit();
+        let response = self
+            .transfer_client
             .request(Method::PUT, url)
             .basic_auth(self.username.clone(), Some(self.password.clone()))
             .multipart(form)
@@ -512,6 +773,31 @@ impl Tracim {
         Ok(content.current_revision_id)
     }
 
+    /// Multipart file part, read through upload throttling
+    fn file_part(&self, path: &PathBuf) -> Result<multipart::Part, TracimClientError> {
+        let prepare_error = |error: String| {
+            TracimClientError::PrepareError(format!(
+                "Error during preparation of form for file {} : {}",
+                path.display(),
+                error
+            ))
+        };
+        let file = fs::File::open(path).map_err(|error| prepare_error(error.to_string()))?;
+        let length = file
+            .metadata()
+            .map_err(|error| prepare_error(error.to_string()))?
+            .len();
+        let mime = mime_guess::from_path(path).first_or_octet_stream();
+        let mut part =
+            multipart::Part::reader_with_length(self.transfer.upload_reader(file), length)
+                .mime_str(mime.essence_str())
+                .map_err(|error| prepare_error(error.to_string()))?;
+        if let Some(file_name) = path.file_name() {
+            part = part.file_name(file_name.to_string_lossy().into_owned());
+        }
+        Ok(part)
+    }
+
     fn fill_content_note_with_file(
         &self,
         content_id: ContentId,
@@ -538,12 +824,19 @@ impl Tracim {
         self.created_revision_id(response)
     }
 
+    /// Live messages stream, beginning after given event if any (server must support
+    /// event replay)
     pub async fn get_user_live_messages_response(
         &self,
         user_id: i32,
+        after_event_id: Option<i32>,
     ) -> Result<reqwest::Response, TracimClientError> {
-        let url = format!("{}users/{}/live_messages", self.base_address, user_id);
-        let response = reqwest::Client::new()
+        let mut url = format!("{}users/{}/live_messages", self.base_address, user_id);
+        if let Some(after_event_id) = after_event_id {
+            url = format!("{}?after_event_id={}", url, after_event_id);
+        }
+        let response = self
+            .async_client
             .request(Method::GET, url)
             .basic_auth(self.username.clone(), Some(self.password.clone()))
             .send()
@@ -706,23 +999,50 @@ impl TracimClient for Tracim {
         Ok(path)
     }
 
-    fn get_contents(&self) -> Result<Vec<RemoteContent>, TracimClientError> {
+    fn get_contents_page(
+        &self,
+        page_token: Option<String>,
+    ) -> Result<ContentsPage, TracimClientError> {
         let url = self.workspace_url("contents");
+        let paginated = self
+            .capabilities
+            .as_ref()
+            .map(|capabilities| capabilities.paginated_contents)
+            .unwrap_or(true);
 
-        let response = self
+        let mut request = self
             .client
             .request(Method::GET, url)
-            .basic_auth(self.username.clone(), Some(self.password.clone()))
-            .send()?;
+            .basic_auth(self.username.clone(), Some(self.password.clone()));
+        if paginated {
+            request = request.query(&[("count", CONTENTS_PAGE_SIZE.to_string())]);
+            if let Some(page_token) = page_token {
+                request = request.query(&[("page_token", page_token)]);
+            }
+        }
+        let response = request.send()?;
 
         let status_code = response.status().as_u16();
         match status_code {
-            200 => Ok(response
-                .json::<Paginated<Vec<RemoteContent>>>()?
-                .items
-                .into_iter()
-                .filter(|c| ContentType::from_str(c.content_type.as_str()).is_ok())
-                .collect::<Vec<RemoteContent>>()),
+            200 => {
+                let (contents, next_page_token) = if paginated {
+                    let page = response.json::<Paginated<Vec<RemoteContent>>>()?;
+                    let next_page_token = page.has_next.then_some(page.next_page_token);
+                    (page.items, next_page_token)
+                } else {
+                    (response.json::<Vec<RemoteContent>>()?, None)
+                };
+                Ok(ContentsPage {
+                    contents: contents
+                        .into_iter()
+                        .filter(|c| {
+                            ContentType::from_str(c.content_type.as_str())
+                                .is_ok_and(|content_type| self.supports(&content_type))
+                        })
+                        .collect(),
+                    next_page_token,
+                })
+            }
             _ => Err(self.response_error(response)?),
         }
     }
@@ -735,8 +1055,9 @@ impl TracimClient for Tracim {
     ) -> Result<(), TracimClientError> {
         match type_ {
             ContentType::File => {
-                let mut response = self
-                    .client
+                let _permit = self.transfer.permit();
+                let response = self
+                    .transfer_client
                     .request(
                         Method::GET,
                         self.workspace_url(&format!("files/{}/raw/_", content_id)),
@@ -751,6 +1072,7 @@ impl TracimClient for Tracim {
                         error
                     ))
                 })?;
+                let mut response = self.transfer.download_reader(response);
                 io::copy(&mut response, &mut out).map_err(|error| {
                     TracimClientError::PrepareError(format!(
                         "Error when fill file at {}: {}",
@@ -795,6 +1117,10 @@ impl TracimClient for Tracim {
             base_address: self.base_address.clone(),
             workspace_id: self.workspace_id,
             client: self.client.clone(),
+            transfer_client: self.transfer_client.clone(),
+            async_client: self.async_client.clone(),
+            transfer: self.transfer.clone(),
+            capabilities: self.capabilities.clone(),
             username: self.username.clone(),
             password: self.password.clone(),
         })
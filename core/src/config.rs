@@ -1,26 +1,45 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use anyhow::{Context, Error, Result};
 
-use ini::Ini;
+use ini::{Ini, Properties};
 
 use crate::{
-    instance::{Instance, InstanceId, WorkspaceId},
+    instance::{Instance, InstanceId, WorkspaceConfig, WorkspaceId},
+    layout::FolderLayout,
+    schedule::SyncSchedule,
     security::get_password,
+    transfer::BandwidthConfig,
+    transport::TransportConfig,
     utils::strbool,
 };
 
+/// Unlimited, set `max_parallel_transfers` in `[server]` section to limit simultaneous transfers
+pub const DEFAULT_MAX_PARALLEL_TRANSFERS: usize = 0;
+/// `workspaces_ids` value meaning all workspaces user belongs to
+pub const ALL_WORKSPACES: &str = "*";
+
 #[derive(Debug, Clone)]
 pub struct ManagerConfig {
     pub local_folder: String,
+    pub folder_layout: FolderLayout,
     pub icons_path: Option<String>,
     pub instances: Vec<Instance>,
     pub allow_raw_passwords: bool,
     pub confirm_startup_sync: bool,
     pub popup_confirm_startup_sync: bool,
+    pub bandwidth: BandwidthConfig,
+    pub max_parallel_transfers: usize,
+    pub schedule: SyncSchedule,
+    pub control_socket: Option<String>,
+    pub removed_workspace_policy: RemovedWorkspacePolicy,
+    /// Run each workspace synchronization in its own `trsync` process
+    pub isolated_processes: bool,
+    /// `trsync` executable used by isolated processes (default is the one next to manager)
+    pub trsync_bin: Option<String>,
 }
 impl ManagerConfig {
-    fn path() -> Result<PathBuf> {
+    pub fn path() -> Result<PathBuf> {
         let user_home_folder_path = dirs::home_dir().context("Unable to determine home folder")?;
 
         if cfg!(target_os = "windows") {
@@ -33,6 +52,20 @@ impl ManagerConfig {
         }
     }
 
+    /// True if transfers are limited across workspaces (bandwidth, parallel transfers or
+    /// priorities). Isolated processes can't share these limits.
+    pub fn has_shared_transfer_limits(&self) -> bool {
+        self.bandwidth.is_limited()
+            || self.max_parallel_transfers > 0
+            || self.instances.iter().any(|instance| {
+                instance.bandwidth.is_limited()
+                    || instance
+                        .workspaces_configs
+                        .values()
+                        .any(|workspace_config| workspace_config.priority != 0)
+            })
+    }
+
     pub fn from_env(allow_raw_passwords: bool) -> Result<Self> {
         let config_file_path = Self::path()?;
         let config_ini = Ini::load_from_file(&config_file_path).context(format!(
@@ -43,7 +76,6 @@ impl ManagerConfig {
     }
 
     pub fn from_ini(config_ini: Ini, allow_raw_passwords: bool) -> Result<Self> {
-        let os_username = whoami::username();
         let user_home_folder_path = dirs::home_dir().context("Unable to determine home folder")?;
         let server = config_ini
             .section(Some("server"))
@@ -57,7 +89,24 @@ impl ManagerConfig {
             .map(|v| v.to_string())
             .unwrap_or_else(|| user_home_folder_path.join("Tracim").display().to_string())
             .to_string();
+        let folder_layout = FolderLayout::from_section(server)
+            .map_err(|error| Error::msg(format!("Invalid 'server' section : {}", error)))?;
         let icons_path = server.get("icons_path").map(|v| v.to_string());
+        let removed_workspace_policy = RemovedWorkspacePolicy::from_section(server)
+            .map_err(|error| Error::msg(format!("Invalid 'server' section : {}", error)))?;
+        let control_socket = server.get("control_socket").map(|v| v.to_string());
+        let isolated_processes = strbool(server.get("isolated_processes").unwrap_or("0"));
+        let trsync_bin = server.get("trsync_bin").map(|v| v.to_string());
+        let bandwidth = BandwidthConfig::from_section(server)
+            .map_err(|error| Error::msg(format!("Invalid 'server' section : {}", error)))?;
+        let max_parallel_transfers = server
+            .get("max_parallel_transfers")
+            .unwrap_or(&DEFAULT_MAX_PARALLEL_TRANSFERS.to_string())
+            .parse::<usize>()
+            .context("Invalid max_parallel_transfers value in 'server' section")?;
+        let schedule = SyncSchedule::from_section(server)
+            .map_err(|error| Error::msg(format!("Invalid 'server' section : {}", error)))?
+            .unwrap_or_default();
         let instances_ids: Vec<InstanceId> = server
             .get("instances")
             .unwrap_or("")
@@ -66,6 +115,13 @@ impl ManagerConfig {
             .map(|v| InstanceId(v.to_string()))
             .collect();
 
+        let addresses: Vec<&str> = instances_ids
+            .iter()
+            .filter_map(|instance_id| {
+                config_ini.get_from(Some(format!("instance.{}", instance_id)), "address")
+            })
+            .collect();
+
         let mut instances = vec![];
         for instance_id in instances_ids {
             let section_name = format!("instance.{}", instance_id);
@@ -87,35 +143,58 @@ impl ManagerConfig {
                 ))?
                 .to_string();
             let unsecure = strbool(instance_config.get("unsecure").unwrap_or("0"));
-            let (workspaces_ids, errors): (Vec<_>, Vec<_>) = instance_config
-                .get("workspaces_ids")
-                .unwrap_or("")
-                .split(',')
-                .filter(|v| !v.trim().is_empty())
-                .map(|v| v.parse::<i32>())
-                .partition(Result::is_ok);
-            if !errors.is_empty() {
-                return Result::Err(Error::msg(format!(
+            let raw_workspaces_ids = instance_config.get("workspaces_ids").unwrap_or("");
+            let all_workspaces = raw_workspaces_ids.trim() == ALL_WORKSPACES;
+            let workspaces_ids = if all_workspaces {
+                vec![]
+            } else {
+                parse_workspaces_ids(raw_workspaces_ids).context(format!(
                     "Some workspaces ids are invalid in '{}' section",
                     &section_name
-                )));
+                ))?
+            };
+            let excluded_workspaces_ids =
+                parse_workspaces_ids(instance_config.get("excluded_workspaces_ids").unwrap_or(""))
+                    .context(format!(
+                        "Some excluded workspaces ids are invalid in '{}' section",
+                        &section_name
+                    ))?;
+            let transport = TransportConfig::from_section(instance_config).map_err(|error| {
+                Error::msg(format!("Invalid '{}' section : {}", &section_name, error))
+            })?;
+            let bandwidth = BandwidthConfig::from_section(instance_config).map_err(|error| {
+                Error::msg(format!("Invalid '{}' section : {}", &section_name, error))
+            })?;
+
+            let mut workspaces_configs = HashMap::new();
+            let workspace_section_prefix = format!("{}::", &section_name);
+            for (workspace_section_name, workspace_section) in config_ini.iter() {
+                let workspace_id = match workspace_section_name
+                    .and_then(|name| name.strip_prefix(&workspace_section_prefix))
+                {
+                    Some(workspace_id) => WorkspaceId(workspace_id.parse::<i32>().context(
+                        format!("Invalid workspace id in section name '{}'", workspace_id),
+                    )?),
+                    None => continue,
+                };
+                let workspace_config =
+                    WorkspaceConfig::from_section(workspace_section).map_err(|error| {
+                        Error::msg(format!(
+                            "Invalid '{}{}' section : {}",
+                            &workspace_section_prefix, workspace_id, error
+                        ))
+                    })?;
+                workspaces_configs.insert(workspace_id, workspace_config);
             }
-            let workspaces_ids = workspaces_ids
-                .into_iter()
-                .filter_map(|v| match v {
-                    Ok(v) => Some(v),
-                    Err(_) => None,
-                })
-                .map(WorkspaceId)
-                .collect();
 
             // try to get password from keyring
-            let password = match get_password(&address, &os_username) {
+            let single_account = addresses.iter().filter(|a| **a == address).count() == 1;
+            let password = match get_password(&address, &username, single_account) {
                 Ok(password_) => password_,
                 Err(error) => {
                     if !allow_raw_passwords {
                         log::error!(
-                            "Unable to read password from keyring for instance '{}' and user '{os_username}', this instance will be ignored : '{}'",
+                            "Unable to read password from keyring for instance '{}' and user '{username}', this instance will be ignored : '{}'",
                             &address,
                             error,
                         );
@@ -126,7 +205,7 @@ impl ManagerConfig {
                         Some(password) => password.to_string(),
                         None => {
                             log::error!(
-                                "Unable to read password from config for instance '{}' and user '{os_username}', this instance will be ignored : '{}'",
+                                "Unable to read password from config for instance '{}' and user '{username}', this instance will be ignored : '{}'",
                                 &address,
                                 error,
                             );
@@ -143,17 +222,63 @@ impl ManagerConfig {
                 username,
                 password,
                 workspaces_ids,
+                all_workspaces,
+                excluded_workspaces_ids,
+                transport,
+                bandwidth,
+                workspaces_configs,
             })
         }
 
-        Ok(Self {
+        let config = Self {
             local_folder,
+            folder_layout,
             icons_path,
             instances,
             allow_raw_passwords,
             confirm_startup_sync,
             popup_confirm_startup_sync,
-        })
+            bandwidth,
+            max_parallel_transfers,
+            schedule,
+            control_socket,
+            removed_workspace_policy,
+            isolated_processes,
+            trsync_bin,
+        };
+        if config.max_parallel_transfers == 0
+            && config.instances.iter().any(|instance| {
+                instance
+                    .workspaces_configs
+                    .values()
+                    .any(|workspace_config| workspace_config.priority != 0)
+            })
+        {
+            log::warn!(
+                "Workspaces priorities have no effect without max_parallel_transfers in 'server' \
+                section"
+            );
+        }
+        if config.isolated_processes && config.has_shared_transfer_limits() {
+            return Err(Error::msg(
+                "Invalid 'server' section : isolated_processes can't be used with bandwidth \
+                limits, max_parallel_transfers or workspaces priorities",
+            ));
+        }
+
+        Ok(config)
+    }
+
+    /// Schedule of given workspace : its own one if defined, global one else
+    pub fn workspace_schedule(
+        &self,
+        instance: &Instance,
+        workspace_id: &WorkspaceId,
+    ) -> SyncSchedule {
+        instance
+            .workspace_config(workspace_id)
+            .schedule
+            .unwrap_or_else(|| self.schedule.clone())
     }
 
     pub fn write(&self) -> Result<()> {
@@ -165,6 +290,66 @@ impl ManagerConfig {
     }
 }
 
+/// What to do with local folder of a workspace removed from configuration, read from
+/// `removed_workspace_policy` key. Archive and delete are only applied after user confirmation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RemovedWorkspacePolicy {
+    #[default]
+    Keep,
+    /// Move folder into `.trsync_archives` folder of local folder
+    Archive,
+    Delete,
+}
+
+impl RemovedWorkspacePolicy {
+    pub fn from_section(section: &Properties) -> Result<Self, String> {
+        match section.get("removed_workspace_policy").map(|v| v.trim()) {
+            None | Some("keep") => Ok(Self::Keep),
+            Some("archive") => Ok(Self::Archive),
+            Some("delete") => Ok(Self::Delete),
+            Some(value) => Err(format!(
+                "Invalid removed_workspace_policy value '{}' (expected keep, archive or delete)",
+                value
+            )),
+        }
+    }
+
+    pub fn write_section(&self, section: &mut Properties) {
+        if self != &Self::default() {
+            section.insert("removed_workspace_policy", self.as_str());
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RemovedWorkspacePolicy::Keep => "keep",
+            RemovedWorkspacePolicy::Archive => "archive",
+            RemovedWorkspacePolicy::Delete => "delete",
+        }
+    }
+}
+
+/// Parse comma separated workspaces ids (ex. "1,42")
+fn parse_workspaces_ids(raw: &str) -> Result<Vec<WorkspaceId>> {
+    raw.split(',')
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| {
+            v.trim()
+                .parse::<i32>()
+                .map(WorkspaceId)
+                .context(format!("Invalid workspace id '{}'", v))
+        })
+        .collect()
+}
+
+fn join_workspaces_ids(workspaces_ids: &[WorkspaceId]) -> String {
+    workspaces_ids
+        .iter()
+        .map(|w| w.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
 impl From<ManagerConfig> for Ini {
     fn from(val: ManagerConfig) -> Self {
         let mut conf = Ini::new();
@@ -178,34 +363,76 @@ impl From<ManagerConfig> for Ini {
         let local_folder = val.local_folder.clone();
         let confirm_startup_sync = val.confirm_startup_sync.to_string();
         let popup_confirm_startup_sync = val.popup_confirm_startup_sync.to_string();
+        let max_parallel_transfers = val.max_parallel_transfers.to_string();
 
         conf.with_section(Some("server"))
             .set("instances", instances_ids)
             .set("local_folder", local_folder)
             .set("confirm_startup_sync", confirm_startup_sync)
-            .set("popup_confirm_startup_sync", popup_confirm_startup_sync);
+            .set("popup_confirm_startup_sync", popup_confirm_startup_sync)
+            .set("max_parallel_transfers", max_parallel_transfers);
+        if let Some(section) = conf.section_mut(Some("server")) {
+            val.folder_layout.write_section(section);
+            val.bandwidth.write_section(section);
+            val.schedule.write_section(section);
+            val.removed_workspace_policy.write_section(section);
+        }
 
         if let Some(icons_path) = val.icons_path {
             conf.with_section(Some("server"))
                 .set("icons_path", icons_path);
         }
 
+        if let Some(control_socket) = val.control_socket {
+            conf.with_section(Some("server"))
+                .set("control_socket", control_socket);
+        }
+
+        if val.isolated_processes {
+            conf.with_section(Some("server"))
+                .set("isolated_processes", "true");
+        }
+
+        if let Some(trsync_bin) = val.trsync_bin {
+            conf.with_section(Some("server"))
+                .set("trsync_bin", trsync_bin);
+        }
+
         for instance in &val.instances {
             let address = instance.address.clone();
             let username = instance.username.clone();
             let unsecure = instance.unsecure.to_string();
-            let workspaces_ids = instance
-                .workspaces_ids
-                .iter()
-                .map(|w| w.to_string())
-                .collect::<Vec<String>>()
-                .join(",");
+            let workspaces_ids = if instance.all_workspaces {
+                ALL_WORKSPACES.to_string()
+            } else {
+                join_workspaces_ids(&instance.workspaces_ids)
+            };
 
             conf.with_section(Some(format!("instance.{}", instance.name)))
                 .set("address", address)
                 .set("username", username)
                 .set("unsecure", unsecure)
                 .set("workspaces_ids", workspaces_ids);
+            if let Some(section) = conf.section_mut(Some(format!("instance.{}", instance.name))) {
+                if instance.all_workspaces && !instance.excluded_workspaces_ids.is_empty() {
+                    section.insert(
+                        "excluded_workspaces_ids",
+                        join_workspaces_ids(&instance.excluded_workspaces_ids),
+                    );
+                }
+                instance.transport.write_section(section);
+                instance.bandwidth.write_section(section);
+            }
+
+            for (workspace_id, workspace_config) in &instance.workspaces_configs {
+                if workspace_config.is_default() {
+                    continue;
+                }
+                let section = conf
+                    .entry(Some(instance.workspace_section_name(workspace_id)))
+                    .or_insert(Properties::new());
+                workspace_config.write_section(section);
+            }
         }
 
         conf
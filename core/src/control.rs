@@ -13,6 +13,8 @@ use crate::{
 #[derive(Clone)]
 pub struct RemoteControl {
     stop_signal: Arc<AtomicBool>,
+    pause_signal: Arc<AtomicBool>,
+    resync_signal: Arc<AtomicBool>,
     activity_sender: Option<Sender<WrappedActivity>>,
     sync_channels: Option<SyncChannels>,
     error_channels: Option<ErrorChannels>,
@@ -23,6 +25,8 @@ pub struct RemoteControl {
 
 pub struct RemoteControlBuilder {
     stop_signal: Arc<AtomicBool>,
+    pause_signal: Arc<AtomicBool>,
+    resync_signal: Arc<AtomicBool>,
     activity_sender: Option<Sender<WrappedActivity>>,
     sync_channels: Option<SyncChannels>,
     error_channels: Option<ErrorChannels>,
@@ -35,6 +39,8 @@ impl RemoteControlBuilder {
     fn new() -> Self {
         Self {
             stop_signal: Arc::new(AtomicBool::new(false)),
+            pause_signal: Arc::new(AtomicBool::new(false)),
+            resync_signal: Arc::new(AtomicBool::new(false)),
             activity_sender: None,
             sync_channels: None,
             error_channels: None,
@@ -49,6 +55,16 @@ impl RemoteControlBuilder {
         self
     }
 
+    pub fn pause_signal(mut self, value: Arc<AtomicBool>) -> Self {
+        self.pause_signal = value;
+        self
+    }
+
+    pub fn resync_signal(mut self, value: Arc<AtomicBool>) -> Self {
+        self.resync_signal = value;
+        self
+    }
+
     pub fn activity_sender(mut self, value: Option<Sender<WrappedActivity>>) -> Self {
         self.activity_sender = value;
         self
@@ -82,6 +98,8 @@ impl RemoteControlBuilder {
     pub fn build(self) -> RemoteControl {
         RemoteControl::new(
             self.stop_signal,
+            self.pause_signal,
+            self.resync_signal,
             self.activity_sender,
             self.sync_channels,
             self.error_channels,
@@ -93,8 +111,11 @@ impl RemoteControlBuilder {
 }
 
 impl RemoteControl {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         stop_signal: Arc<AtomicBool>,
+        pause_signal: Arc<AtomicBool>,
+        resync_signal: Arc<AtomicBool>,
         activity_sender: Option<Sender<WrappedActivity>>,
         sync_channels: Option<SyncChannels>,
         error_channels: Option<ErrorChannels>,
@@ -104,6 +125,8 @@ impl RemoteControl {
     ) -> Self {
         Self {
             stop_signal,
+            pause_signal,
+            resync_signal,
             activity_sender,
             sync_channels,
             error_channels,
@@ -136,6 +159,14 @@ impl RemoteControl {
         &self.stop_signal
     }
 
+    pub fn pause_signal(&self) -> &Arc<AtomicBool> {
+        &self.pause_signal
+    }
+
+    pub fn resync_signal(&self) -> &Arc<AtomicBool> {
+        &self.resync_signal
+    }
+
     pub fn activity_sender(&self) -> Option<&Sender<WrappedActivity>> {
         self.activity_sender.as_ref()
     }
@@ -143,6 +174,18 @@ impl RemoteControl {
     pub fn error_channels(&self) -> Option<&ErrorChannels> {
         self.error_channels.as_ref()
     }
+
+    pub fn sync_channels(&self) -> Option<&SyncChannels> {
+        self.sync_channels.as_ref()
+    }
+
+    pub fn popup_confirm_startup_sync(&self) -> bool {
+        self.popup_confirm_startup_sync
+    }
+
+    pub fn user_request_sender(&self) -> Option<&Sender<UserRequest>> {
+        self.user_request_sender.as_ref()
+    }
 }
 
 impl Default for RemoteControlBuilder {
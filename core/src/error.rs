@@ -1,22 +1,147 @@
 use std::{
     collections::HashMap,
+    fmt::Display,
     io,
     path::PathBuf,
     sync::{Arc, Mutex},
 };
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
+use serde_derive::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
     client::TracimClientError, control::RemoteControlError, instance::ContentId,
-    job::JobIdentifier, sync::SyncPoliticError,
+    job::JobIdentifier, sync::SyncPoliticError, tr,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Decision {
     RestartSpaceSync,
     IgnoreAndRestartSpaceSync(ContentId),
+    /// Don't operate the failed event if it occurs again at restart
+    SkipEventAndRestartSpaceSync,
+    PauseSpaceSync,
+    /// Forget local state then index workspace like at first synchronization
+    ResetAndRestartSpaceSync,
+    /// Remove local copy of the content to download it again
+    RedownloadAndRestartSpaceSync(ContentId),
+    IgnorePathAndRestartSpaceSync(PathBuf),
+}
+
+/// What the error was about, when known
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ErrorSubject {
+    /// Failed event, as displayable text
+    pub event: Option<String>,
+    pub content_id: Option<ContentId>,
+    /// Path relative to workspace folder
+    pub path: Option<PathBuf>,
+}
+
+/// Nature of an error, independently of where it happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorClass {
+    /// Server can't be reached
+    Network,
+    Authentication,
+    Permission,
+    Quota,
+    /// Content disappeared (deleted, archived, etc.)
+    NotFound,
+    LocalIo,
+    Other,
+}
+
+/// How to react to an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// Wait then restart
+    Backoff,
+    /// Ignore the event which produced the error
+    SkipEvent,
+    /// Wait for user decision (or stop if there is no user to ask)
+    Prompt,
+    /// Stop workspace synchronization, until it is restarted (ex. config reload) : retrying or
+    /// asking user can't help
+    Stop,
+}
+
+impl ErrorClass {
+    pub fn recovery(&self) -> Recovery {
+        match self {
+            ErrorClass::Network => Recovery::Backoff,
+            ErrorClass::NotFound => Recovery::SkipEvent,
+            ErrorClass::Authentication => Recovery::Stop,
+            ErrorClass::Permission
+            | ErrorClass::Quota
+            | ErrorClass::LocalIo
+            | ErrorClass::Other => Recovery::Prompt,
+        }
+    }
+
+    /// What user can do about this error, if anything specific
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            ErrorClass::Network => Some(tr!("Server is unreachable, check your connection")),
+            ErrorClass::Authentication => {
+                Some(tr!("Credentials refused, check instance configuration"))
+            }
+            ErrorClass::Permission => Some(tr!(
+                "Your role in this workspace does not allow this operation"
+            )),
+            ErrorClass::Quota => Some(tr!("Storage space or maximum file size reached")),
+            ErrorClass::LocalIo => Some(tr!("A local file could not be read or written")),
+            ErrorClass::NotFound | ErrorClass::Other => None,
+        }
+    }
+
+    fn from_io(error: &io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::TimedOut => ErrorClass::Network,
+            _ => ErrorClass::LocalIo,
+        }
+    }
+
+    /// Class of first typed error found in error causes
+    fn from_anyhow(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(error) = cause.downcast_ref::<TracimClientError>() {
+                return error.class();
+            }
+            if let Some(error) = cause.downcast_ref::<ExecutorError>() {
+                return error.class();
+            }
+            if let Some(error) = cause.downcast_ref::<StateError>() {
+                return error.class();
+            }
+            if let Some(error) = cause.downcast_ref::<reqwest::Error>() {
+                return TracimClientError::from_reqwest(error).class();
+            }
+            if let Some(error) = cause.downcast_ref::<io::Error>() {
+                return Self::from_io(error);
+            }
+        }
+        ErrorClass::Other
+    }
+}
+
+impl Display for ErrorClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorClass::Network => f.write_str(tr!("network")),
+            ErrorClass::Authentication => f.write_str(tr!("authentication")),
+            ErrorClass::Permission => f.write_str(tr!("permission")),
+            ErrorClass::Quota => f.write_str(tr!("quota")),
+            ErrorClass::NotFound => f.write_str(tr!("content not found")),
+            ErrorClass::LocalIo => f.write_str(tr!("local file")),
+            ErrorClass::Other => f.write_str(tr!("other")),
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -29,6 +154,37 @@ pub enum RunnerError {
     SyncPoliticError(#[from] SyncPoliticError),
     #[error("Unexpected error: {0:#}")]
     Unexpected(#[from] anyhow::Error),
+    /// Error of a runner executed in another process, as it reported it
+    #[error("{message}")]
+    Isolated {
+        message: String,
+        class: ErrorClass,
+        content_id: Option<ContentId>,
+    },
+}
+
+impl RunnerError {
+    /// Content which can be ignored to get rid of this error, if any
+    pub fn ignorable_content_id(&self) -> Option<ContentId> {
+        match self {
+            RunnerError::OperatorError(OperatorError::StateError(
+                StateError::PathAlreadyExist(_, content_id),
+            )) => Some(*content_id),
+            RunnerError::Isolated { content_id, .. } => *content_id,
+            _ => None,
+        }
+    }
+
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            RunnerError::OperatorError(error) => error.class(),
+            RunnerError::Unexpected(error) => ErrorClass::from_anyhow(error),
+            RunnerError::Isolated { class, .. } => *class,
+            RunnerError::RemoteControlError(_) | RunnerError::SyncPoliticError(_) => {
+                ErrorClass::Other
+            }
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -47,6 +203,20 @@ pub enum OperatorError {
     ClientError(#[from] TracimClientError),
 }
 
+impl OperatorError {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            OperatorError::ExecutorError(error) => error.class(),
+            OperatorError::StateError(error) => error.class(),
+            OperatorError::ClientError(error) => error.class(),
+            OperatorError::Unexpected(error) => ErrorClass::from_anyhow(error),
+            OperatorError::ActivityError(_) | OperatorError::MissingParentError(_) => {
+                ErrorClass::Other
+            }
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ExecutorError {
     #[error("Unexpected error: {0:#}")]
@@ -71,6 +241,22 @@ pub enum ExecutorError {
     RelatedLocalFileIoError(PathBuf, io::Error),
 }
 
+impl ExecutorError {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            ExecutorError::Tracim(error) => error.class(),
+            ExecutorError::State(error) => error.class(),
+            ExecutorError::Unexpected(error) => ErrorClass::from_anyhow(error),
+            ExecutorError::MaximumRetryCount(_) => ErrorClass::Network,
+            ExecutorError::RelatedLocalFileIoError(_, _) => ErrorClass::LocalIo,
+            ExecutorError::Unexpected2(_)
+            | ExecutorError::MissingParent(_, _)
+            | ExecutorError::Programmatic(_)
+            | ExecutorError::NotFoundAfterContentAlreadyExist(_) => ErrorClass::Other,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum StateError {
     #[error("Unexpected error: {0:#}")]
@@ -83,9 +269,21 @@ pub enum StateError {
     PathAlreadyExist(PathBuf, ContentId),
 }
 
+impl StateError {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            StateError::UnexpectedError(error) => ErrorClass::from_anyhow(error),
+            StateError::UnknownError(_)
+            | StateError::UnknownContent(_)
+            | StateError::PathAlreadyExist(_, _) => ErrorClass::Other,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ErrorChannels {
     error: Arc<Mutex<Option<RunnerError>>>,
+    subject: Arc<Mutex<Option<ErrorSubject>>>,
     seen: Arc<Mutex<bool>>,
     decision_sender: Sender<Decision>,
     decision_receiver: Receiver<Decision>,
@@ -95,6 +293,7 @@ impl ErrorChannels {
     pub fn new(decision_sender: Sender<Decision>, decision_receiver: Receiver<Decision>) -> Self {
         Self {
             error: Arc::new(Mutex::new(None)),
+            subject: Arc::new(Mutex::new(None)),
             seen: Arc::new(Mutex::new(false)),
             decision_sender,
             decision_receiver,
@@ -113,6 +312,10 @@ impl ErrorChannels {
         self.error.as_ref()
     }
 
+    pub fn subject(&self) -> &Mutex<Option<ErrorSubject>> {
+        self.subject.as_ref()
+    }
+
     pub fn seen(&self) -> bool {
         // TODO : no unwrap
         *self.seen.lock().unwrap()
@@ -153,3 +356,34 @@ impl Default for ErrorExchanger {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use anyhow::Context;
+
+    use super::*;
+
+    #[test]
+    fn test_error_class_through_causes() {
+        // Given
+        let network: Result<(), TracimClientError> = Err(TracimClientError::ConnectionError);
+        let network =
+            RunnerError::Unexpected(network.context("Determine remote changes").unwrap_err());
+        let quota = RunnerError::OperatorError(OperatorError::ExecutorError(
+            ExecutorError::Tracim(TracimClientError::QuotaExceeded("Too big".to_string())),
+        ));
+        let local = RunnerError::OperatorError(OperatorError::ExecutorError(
+            ExecutorError::RelatedLocalFileIoError(
+                PathBuf::from("a.txt"),
+                io::Error::from(io::ErrorKind::PermissionDenied),
+            ),
+        ));
+
+        // When-Then
+        assert_eq!(network.class(), ErrorClass::Network);
+        assert_eq!(network.class().recovery(), Recovery::Backoff);
+        assert_eq!(quota.class(), ErrorClass::Quota);
+        assert_eq!(local.class(), ErrorClass::LocalIo);
+        assert_eq!(ErrorClass::Authentication.recovery(), Recovery::Stop);
+    }
+}
@@ -0,0 +1,123 @@
+/// French translations, by english message
+pub const CATALOG: &[(&str, &str)] = &[
+    ("Idle", "En veille"),
+    ("Synchronizing ({})", "Synchronisation ({})"),
+    ("Synchronizing", "Synchronisation"),
+    ("Waiting confirmation", "Attend confirmation"),
+    ("Waiting connection", "Attend connexion"),
+    ("Outside sync hours", "Hors plage horaire"),
+    ("Paused", "En pause"),
+    ("Error", "Erreur"),
+    ("unknown", "inconnu"),
+    ("connected", "connecté"),
+    ("disconnected", "déconnecté"),
+    ("listening", "à l'écoute"),
+    ("synchronized {}", "synchronisé {}"),
+    ("never synchronized", "jamais synchronisé"),
+    (", pending : {} local, {} remote", ", en attente : {} local(aux), {} distant(s)"),
+    (", {} error(s)", ", {} erreur(s)"),
+    ("{} s ago", "il y a {} s"),
+    ("{} min ago", "il y a {} min"),
+    ("{} h ago", "il y a {} h"),
+    ("{} d ago", "il y a {} j"),
+    ("Server is unreachable, check your connection", "Le serveur est injoignable, vérifiez votre connexion"),
+    ("Credentials refused, check instance configuration", "Identifiants refusés, vérifiez la configuration de l'instance"),
+    ("Your role in this workspace does not allow this operation", "Vos droits dans cet espace ne permettent pas cette opération"),
+    ("Storage space or maximum file size reached", "Espace de stockage ou taille de fichier maximale atteint"),
+    ("A local file could not be read or written", "Un fichier local n'a pas pu être lu ou écrit"),
+    ("network", "réseau"),
+    ("authentication", "authentification"),
+    ("content not found", "contenu introuvable"),
+    ("local file", "fichier local"),
+    ("other", "autre"),
+    ("never", "jamais"),
+    ("Workspace : {} ({})", "Espace : {} ({})"),
+    ("Activity : {}", "Activité : {}"),
+    ("Last full synchronization : {}", "Dernière synchronisation complète : {}"),
+    ("Last event : {}", "Dernier évènement : {}"),
+    ("Pending : {} local, {} remote", "En attente : {} local(aux), {} distant(s)"),
+    ("Errors : {}", "Erreurs : {}"),
+    ("Connection : {}", "Connexion : {}"),
+    ("Live messages : {}", "Messages temps réel : {}"),
+    ("This workspace encountered an error ({}) :", "Cet espace de travail a rencontré une erreur ({}) :"),
+    ("Event : {}", "Évènement : {}"),
+    ("Path : {}", "Chemin : {}"),
+    ("Detail", "Détail"),
+    ("Workspaces", "Espaces"),
+    ("Errors", "Erreurs"),
+    ("q: quit  tab: panel  ↑↓: select  p: pause/resume  s: resynchronize", "q: quitter  tab: panneau  ↑↓: sélection  p: pause/reprendre  s: resynchroniser"),
+    ("q: quit  tab: panel  ↑↓: select  a: accept  r: refuse", "q: quitter  tab: panneau  ↑↓: sélection  a: accepter  r: refuser"),
+    ("q: quit  tab: panel  ↑↓: select  r: restart  i: ignore content  s: skip event  p: pause  x: reset  d: redownload  g: ignore path  o: open folder", "q: quitter  tab: panneau  ↑↓: sélection  r: redémarrer  i: ignorer le contenu  s: passer l'évènement  p: pause  x: réinitialiser  d: retélécharger  g: ignorer le chemin  o: ouvrir le dossier"),
+    ("This error is not about a content", "Cette erreur ne concerne pas un contenu"),
+    ("This error is not about an event", "Cette erreur ne concerne pas un évènement"),
+    ("This error is not about a path", "Cette erreur ne concerne pas un chemin"),
+    ("Unable to open {} : {}", "Impossible d'ouvrir {} : {}"),
+    ("Unable to communicate with {}", "Impossible de communiquer avec {}"),
+    ("Unable to communicate with manager", "Impossible de communiquer avec le gestionnaire"),
+    ("Monitor", "Moniteur"),
+    ("Synchronizations", "Synchronisations"),
+    ("History", "Historique"),
+    ("State by workspace", "État par espace"),
+    ("Resume", "Reprendre"),
+    ("Workspaces waiting confirmation", "Espaces en attente de confirmation"),
+    ("Startup synchronization of this workspace will include following changes :", "La synchronisation de départ de cet espace inclura les changements suivants :"),
+    ("Refuse", "Refuser"),
+    ("Accept", "Accepter"),
+    ("Workspaces in error", "Espaces en erreur"),
+    ("A content seems duplicated : '{}'", "Un contenu semble dupliqué : '{}'"),
+    ("Ignore in the future & Restart synchronization", "Ignorer à l'avenir & Redémarrer la synchronisation"),
+    ("Skip this event & Restart synchronization", "Passer cet évènement & Redémarrer la synchronisation"),
+    ("Redownload this content", "Retélécharger ce contenu"),
+    ("Ignore this path in the future", "Ignorer ce chemin à l'avenir"),
+    ("Reset local database & Reindex", "Réinitialiser la base locale & Réindexer"),
+    ("Open folder", "Ouvrir le dossier"),
+    ("Restart synchronization", "Redémarrer la synchronisation"),
+    ("Workspace", "Espace"),
+    ("No operation recorded for this workspace", "Aucune opération enregistrée pour cet espace"),
+    ("revision {}", "révision {}"),
+    ("Pause synchronization", "Mettre en pause"),
+    ("Address (ex. my.tracim.fr)", "Adresse (ex. mon.tracim.fr)"),
+    ("Login (username or email)", "Identifiant (username ou email)"),
+    ("Password", "Mot de passe"),
+    ("Add", "Ajouter"),
+    ("Checking your credentials ...", "Test de vos identifiants ..."),
+    ("Validate", "Valider"),
+    ("Delete", "Supprimer"),
+    ("Workspaces list is loading", "La liste des espaces est en cours de chargement"),
+    ("Workspaces to synchronize", "Espaces à synchroniser"),
+    ("Synchronization folder", "Dossier de synchronisation"),
+    ("Select", "Sélectionner"),
+    ("Confirm startup operations", "Confirmer les opérations au démarrage"),
+    ("When TrSync runs a startup synchronization (at startup or after a connection interruption) you will be asked to confirm operations in monitor window.", "Lorsque TrSync effectue une synchronisation de départ (au démarrage ou après une interruption de connexion) une confirmation des opérations vous sera demandée dans la fenêtre du moniteur."),
+    ("Startup operations confirmation popup", "Popup de confirmation des opérations au démarrage"),
+    ("Display startup synchronization confirmation window when it is available.", "Affiche la fenêtre de confirmation de la synchronisation de départ lorsqu'elle est disponible."),
+    ("Invalid login or password", "Identifiant ou mot de passe invalide"),
+    ("⚠ An error occurred ⚠", "⚠ Une erreur est survenue ⚠"),
+    ("Close", "Fermer"),
+    ("Delete {} ?", "Supprimer {} ?"),
+    ("Do you really want to delete {} ?\nData on your hard drive will not be deleted.", "Voulez-vous réellement supprimer {} ?\nLes données sur votre disque dur ne seront pas supprimées."),
+    ("Cancel", "Annuler"),
+    ("Please enter an address (ex. my.tracim.fr)", "Veuillez saisir une adresse (ex. mon.tracim.fr)"),
+    ("Please enter a login (username or email)", "Veuillez saisir un identifiant (username ou email)"),
+    ("Please enter a password", "Veuillez saisir un mot de passe"),
+    ("Configure", "Configurer"),
+    ("Pause all", "Tout mettre en pause"),
+    ("Resume all", "Tout reprendre"),
+    ("Pause {}", "Mettre en pause {}"),
+    ("Resume {}", "Reprendre {}"),
+    ("Quit", "Quitter"),
+    ("All my workspaces, including future ones", "Tous mes espaces, y compris les futurs"),
+    ("Default folder", "Dossier par défaut"),
+    ("Choose folder", "Choisir un dossier"),
+    ("Synchronize this workspace outside of synchronization folder", "Synchroniser cet espace en dehors du dossier de synchronisation"),
+    ("Folders of removed workspaces", "Dossiers des espaces retirés"),
+    ("What to do with the local folder of a workspace which is not synchronized anymore. You will be asked to confirm before archiving or deleting it.", "Que faire du dossier local d'un espace qui n'est plus synchronisé. Une confirmation vous sera demandée avant de l'archiver ou de le supprimer."),
+    ("Keep", "Conserver"),
+    ("Archive", "Archiver"),
+    ("Workspaces removed from synchronization", "Espaces retirés de la synchronisation"),
+    ("Local folders of these workspaces will be deleted :", "Les dossiers locaux de ces espaces vont être supprimés :"),
+    ("Local folders of these workspaces will be archived :", "Les dossiers locaux de ces espaces vont être archivés :"),
+    ("Keep folders", "Conserver les dossiers"),
+    ("Folders chosen for these workspaces are kept :", "Les dossiers choisis pour ces espaces sont conservés :"),
+    ("Access lost", "Accès perdu"),
+];
@@ -0,0 +1,137 @@
+//! User-facing messages translation. Messages are written in english in source code and
+//! used as keys of other languages catalogs (english is used when no translation exist).
+use std::{env, fmt::Display, sync::OnceLock};
+
+mod fr;
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    English,
+    French,
+}
+
+impl Locale {
+    /// Locale from `LC_ALL`, `LC_MESSAGES` or `LANG` environment variables (in this order),
+    /// english if none of them is set
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .map(|value| Self::from_tag(&value))
+            .unwrap_or(Self::English)
+    }
+
+    /// Locale from tags like "fr_FR.UTF-8", "fr-BE" or "en"
+    pub fn from_tag(tag: &str) -> Self {
+        match tag.to_lowercase().get(..2) {
+            Some("fr") => Self::French,
+            _ => Self::English,
+        }
+    }
+
+    fn catalog(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::English => &[],
+            Locale::French => fr::CATALOG,
+        }
+    }
+}
+
+/// Locale used by all user-facing messages, read from environment at first use
+pub fn locale() -> Locale {
+    *LOCALE.get_or_init(Locale::from_env)
+}
+
+/// Force locale instead of environment one. Must be called before any translation.
+pub fn set_locale(locale: Locale) {
+    if LOCALE.set(locale).is_err() {
+        log::warn!("Locale already set, ignore {:?}", locale)
+    }
+}
+
+pub fn translate(locale: Locale, message: &'static str) -> &'static str {
+    locale
+        .catalog()
+        .iter()
+        .find(|(source, _)| *source == message)
+        .map(|(_, translation)| *translation)
+        .unwrap_or(message)
+}
+
+/// Translate message in current locale
+pub fn tr(message: &'static str) -> &'static str {
+    translate(locale(), message)
+}
+
+/// Replace "{}" placeholders of message by given values, in order
+pub fn format(message: &str, values: &[&dyn Display]) -> String {
+    let mut parts = message.split("{}");
+    let mut formatted = parts.next().unwrap_or("").to_string();
+    let mut values = values.iter();
+
+    for part in parts {
+        match values.next() {
+            Some(value) => formatted.push_str(&value.to_string()),
+            None => formatted.push_str("{}"),
+        }
+        formatted.push_str(part);
+    }
+
+    formatted
+}
+
+/// Translate message in current locale, with optional values for its "{}" placeholders
+///
+/// ```
+/// use trsync_core::tr;
+/// let paused = tr!("Paused");
+/// let errors = tr!("Errors : {}", 3);
+/// ```
+#[macro_export]
+macro_rules! tr {
+    ($message:literal) => {
+        $crate::i18n::tr($message)
+    };
+    ($message:literal, $($value:expr),+ $(,)?) => {
+        $crate::i18n::format($crate::i18n::tr($message), &[$(&$value),+])
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_translate() {
+        // Given
+        let french = Locale::from_tag("fr_FR.UTF-8");
+        let english = Locale::from_tag("en_US.UTF-8");
+
+        // When
+        let french_paused = translate(french, "Paused");
+        let english_paused = translate(english, "Paused");
+        let formatted = format(translate(french, "Errors : {}"), &[&3]);
+
+        // Then
+        assert_eq!(french, Locale::French);
+        assert_eq!(english, Locale::English);
+        assert_eq!(french_paused, "En pause");
+        assert_eq!(english_paused, "Paused");
+        assert_eq!(formatted, "Erreurs : 3");
+    }
+
+    #[test]
+    fn test_catalogs_placeholders() {
+        for (source, translation) in fr::CATALOG {
+            assert_eq!(
+                source.matches("{}").count(),
+                translation.matches("{}").count(),
+                "Placeholders of '{}' translation",
+                source
+            );
+        }
+    }
+}
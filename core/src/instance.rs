@@ -1,9 +1,13 @@
+use ini::Properties;
 use serde_derive::{Deserialize, Serialize};
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display};
 
-use crate::types::ContentType;
+use crate::{
+    schedule::SyncSchedule, transfer::BandwidthConfig, transport::TransportConfig,
+    types::ContentType,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Hash)]
 pub struct InstanceId(pub String);
 
 impl Display for InstanceId {
@@ -35,13 +39,94 @@ pub struct Instance {
     pub username: String,
     pub password: String,
     pub workspaces_ids: Vec<WorkspaceId>,
+    /// Synchronize all workspaces user belongs to (`workspaces_ids = *`), instead of
+    /// `workspaces_ids` ones
+    pub all_workspaces: bool,
+    /// Workspaces not synchronized when `all_workspaces` is set
+    pub excluded_workspaces_ids: Vec<WorkspaceId>,
+    pub transport: TransportConfig,
+    pub bandwidth: BandwidthConfig,
+    pub workspaces_configs: HashMap<WorkspaceId, WorkspaceConfig>,
 }
 
 impl Instance {
+    /// True if given workspace must be synchronized, according to workspaces selection
+    pub fn is_workspace_selected(&self, workspace_id: &WorkspaceId) -> bool {
+        if self.all_workspaces {
+            !self.excluded_workspaces_ids.contains(workspace_id)
+        } else {
+            self.workspaces_ids.contains(workspace_id)
+        }
+    }
+
+    pub fn workspace_config(&self, workspace_id: &WorkspaceId) -> WorkspaceConfig {
+        self.workspaces_configs
+            .get(workspace_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn workspace_section_name(&self, workspace_id: &WorkspaceId) -> String {
+        format!("instance.{}::{}", self.name, workspace_id)
+    }
+
     pub fn url(&self, suffix: Option<&str>) -> String {
         let suffix = suffix.unwrap_or("");
-        let scheme = if self.unsecure { "http" } else { "https" };
-        format!("{}://{}/api{}", scheme, self.address, suffix)
+        format!(
+            "{}{}",
+            self.transport.api_url(self.unsecure, &self.address),
+            suffix
+        )
+    }
+}
+
+/// Workspace specific settings, read from `[instance.<name>::<workspace_id>]` sections
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkspaceConfig {
+    /// Transfers of workspaces with higher priority are started first
+    pub priority: i32,
+    /// Replace global schedule when defined
+    pub schedule: Option<SyncSchedule>,
+    /// Replace folder computed from folder layout when defined (relative to local folder
+    /// if not absolute)
+    pub folder_path: Option<String>,
+}
+
+impl WorkspaceConfig {
+    pub fn from_section(section: &Properties) -> Result<Self, String> {
+        let priority = match section.get("priority") {
+            Some(value) => value
+                .trim()
+                .parse::<i32>()
+                .map_err(|_| format!("Invalid priority value '{}'", value))?,
+            None => 0,
+        };
+        let schedule = SyncSchedule::from_section(section)?;
+        let folder_path = section
+            .get("folder_path")
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+        Ok(Self {
+            priority,
+            schedule,
+            folder_path,
+        })
+    }
+
+    pub fn write_section(&self, section: &mut Properties) {
+        if self.priority != 0 {
+            section.insert("priority", self.priority.to_string());
+        }
+        if let Some(schedule) = &self.schedule {
+            schedule.write_section(section);
+        }
+        if let Some(folder_path) = &self.folder_path {
+            section.insert("folder_path", folder_path.clone());
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
     }
 }
 
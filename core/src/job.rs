@@ -1,18 +1,33 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
 
-#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+use serde_derive::{Deserialize, Serialize};
+
+/// Identify a workspace synchronization job. Equality only consider instance and workspace,
+/// so a job is still the same when its local folder changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JobIdentifier {
     pub instance_name: String,
     pub workspace_id: i32,
     pub workspace_name: String,
+    /// Local folder of the workspace
+    pub folder_path: String,
 }
 
 impl JobIdentifier {
-    pub fn new(instance_name: String, workspace_id: i32, workspace_name: String) -> Self {
+    pub fn new(
+        instance_name: String,
+        workspace_id: i32,
+        workspace_name: String,
+        folder_path: String,
+    ) -> Self {
         Self {
             instance_name,
             workspace_id,
             workspace_name,
+            folder_path,
         }
     }
 }
@@ -25,3 +40,41 @@ impl Display for JobIdentifier {
         ))
     }
 }
+
+impl PartialEq for JobIdentifier {
+    fn eq(&self, other: &Self) -> bool {
+        self.instance_name == other.instance_name && self.workspace_id == other.workspace_id
+    }
+}
+
+impl Eq for JobIdentifier {}
+
+impl Hash for JobIdentifier {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.instance_name.hash(state);
+        self.workspace_id.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_identity_ignore_folder() {
+        // Given
+        let job = JobIdentifier::new("a".to_string(), 1, "W".to_string(), "/a".to_string());
+        let moved = JobIdentifier::new("a".to_string(), 1, "W".to_string(), "/b".to_string());
+        let other = JobIdentifier::new("a".to_string(), 2, "W".to_string(), "/a".to_string());
+
+        // When
+        let jobs: HashSet<JobIdentifier> = [job.clone(), moved.clone(), other.clone()].into();
+
+        // Then
+        assert_eq!(job, moved);
+        assert_ne!(job, other);
+        assert_eq!(jobs.len(), 2);
+    }
+}
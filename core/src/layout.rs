@@ -0,0 +1,204 @@
+use std::path::PathBuf;
+
+use ini::Properties;
+
+use crate::instance::{Instance, Workspace};
+
+/// Default layout, the one used before layout was configurable
+pub const DEFAULT_FOLDER_LAYOUT: &str = "{address}/{label}";
+const PLACEHOLDERS: [&str; 5] = [
+    "{instance}",
+    "{address}",
+    "{username}",
+    "{label}",
+    "{workspace_id}",
+];
+/// Characters refused in file names by at least one supported platform
+const FORBIDDEN_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Template of workspaces folders paths, relative to local folder (ex. "{instance}/{label}"
+/// or "{address}/{label} ({workspace_id})"), read from `folder_layout` key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FolderLayout {
+    template: String,
+}
+
+impl Default for FolderLayout {
+    fn default() -> Self {
+        Self {
+            template: DEFAULT_FOLDER_LAYOUT.to_string(),
+        }
+    }
+}
+
+impl FolderLayout {
+    pub fn new(template: &str) -> Result<Self, String> {
+        let template = template.trim().trim_matches('/').to_string();
+
+        let mut remaining = template.clone();
+        for placeholder in PLACEHOLDERS {
+            remaining = remaining.replace(placeholder, "");
+        }
+        if remaining.contains('{') || remaining.contains('}') {
+            return Err(format!(
+                "Unknown placeholder in folder layout '{}'",
+                template
+            ));
+        }
+        if !template.contains("{label}") && !template.contains("{workspace_id}") {
+            return Err(format!(
+                "Folder layout '{}' must contain {{label}} or {{workspace_id}}",
+                template
+            ));
+        }
+        if template
+            .split('/')
+            .any(|part| part.trim().is_empty() || part == "." || part == "..")
+        {
+            return Err(format!("Invalid folder layout '{}'", template));
+        }
+
+        Ok(Self { template })
+    }
+
+    pub fn from_section(section: &Properties) -> Result<Self, String> {
+        match section.get("folder_layout") {
+            Some(template) => Self::new(template),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn write_section(&self, section: &mut Properties) {
+        if self != &Self::default() {
+            section.insert("folder_layout", self.template.clone());
+        }
+    }
+
+    /// Workspace folder path, relative to local folder. Values are sanitized so they can't
+    /// produce additional path components. When several accounts of the same address are
+    /// configured (`shared_address`) and layout doesn't contain `{instance}` or `{username}`,
+    /// `{address}` is completed with username to separate accounts folders.
+    pub fn path(
+        &self,
+        instance: &Instance,
+        workspace: &Workspace,
+        shared_address: bool,
+    ) -> PathBuf {
+        let address = if shared_address
+            && !self.template.contains("{instance}")
+            && !self.template.contains("{username}")
+        {
+            format!("{} ({})", instance.address, instance.username)
+        } else {
+            instance.address.clone()
+        };
+
+        self.template
+            .split('/')
+            .map(|part| {
+                sanitize(
+                    &part
+                        .replace("{instance}", &sanitize(&instance.name.0))
+                        .replace("{address}", &sanitize(&address))
+                        .replace("{username}", &sanitize(&instance.username))
+                        .replace("{label}", &sanitize(&workspace.label))
+                        .replace("{workspace_id}", &workspace.workspace_id.to_string()),
+                )
+            })
+            .collect()
+    }
+
+    /// Path used before folder layout was configurable (values were not sanitized), for
+    /// default layout only. Allow to keep folders of these installations.
+    pub fn legacy_path(&self, instance: &Instance, workspace: &Workspace) -> Option<PathBuf> {
+        (self == &Self::default()).then(|| PathBuf::from(&instance.address).join(&workspace.label))
+    }
+}
+
+/// Make given value usable as a file name on all supported platforms
+pub fn sanitize(value: &str) -> String {
+    let sanitized: String = value
+        .chars()
+        .map(|c| {
+            if c.is_control() || FORBIDDEN_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    // Windows refuses names ending with dot or space
+    let sanitized = sanitized.trim().trim_end_matches('.').to_string();
+
+    match sanitized.as_str() {
+        "" | "." | ".." => "_".to_string(),
+        _ => sanitized,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{
+        instance::{InstanceId, WorkspaceId},
+        transfer::BandwidthConfig,
+        transport::TransportConfig,
+    };
+
+    use super::*;
+
+    fn instance() -> Instance {
+        Instance {
+            name: InstanceId("algoo".to_string()),
+            address: "algoo.tracim.fr".to_string(),
+            unsecure: false,
+            username: "bux".to_string(),
+            password: "".to_string(),
+            workspaces_ids: vec![],
+            all_workspaces: true,
+            excluded_workspaces_ids: vec![],
+            transport: TransportConfig::default(),
+            bandwidth: BandwidthConfig::default(),
+            workspaces_configs: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_folder_layout() {
+        // Given
+        let default = FolderLayout::default();
+        let with_id = FolderLayout::new("{instance}/{label} ({workspace_id})").unwrap();
+        let workspace = Workspace {
+            label: "Projects/2024: \"draft\".".to_string(),
+            workspace_id: WorkspaceId(42),
+        };
+
+        // When
+        let default_path = default.path(&instance(), &workspace, false);
+        let shared_path = default.path(&instance(), &workspace, true);
+        let with_id_path = with_id.path(&instance(), &workspace, true);
+
+        // Then
+        assert_eq!(
+            default_path,
+            PathBuf::from("algoo.tracim.fr/Projects_2024_ _draft_")
+        );
+        assert_eq!(
+            shared_path,
+            PathBuf::from("algoo.tracim.fr (bux)/Projects_2024_ _draft_")
+        );
+        assert_eq!(
+            with_id_path,
+            PathBuf::from("algoo/Projects_2024_ _draft_ (42)")
+        );
+        assert_eq!(
+            default.legacy_path(&instance(), &workspace),
+            Some(PathBuf::from("algoo.tracim.fr/Projects/2024: \"draft\"."))
+        );
+        assert_eq!(with_id.legacy_path(&instance(), &workspace), None);
+        assert!(FolderLayout::new("{address}").is_err());
+        assert!(FolderLayout::new("{address}/{name}").is_err());
+        assert!(FolderLayout::new("../{label}").is_err());
+    }
+}
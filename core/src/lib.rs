@@ -5,10 +5,17 @@ pub mod config;
 pub mod content;
 pub mod control;
 pub mod error;
+pub mod i18n;
 pub mod instance;
 pub mod job;
+pub mod layout;
+pub mod schedule;
 pub mod security;
+pub mod server;
+pub mod status;
 pub mod sync;
+pub mod transfer;
+pub mod transport;
 pub mod types;
 pub mod user;
 pub mod utils;
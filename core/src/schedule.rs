@@ -0,0 +1,334 @@
+use std::{
+    net::IpAddr,
+    str::FromStr,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use chrono::{Datelike, Local, NaiveDateTime, NaiveTime, Weekday};
+use ini::Properties;
+use ipnet::IpNet;
+
+/// Local addresses are read again after this delay (schedules are checked very often)
+const ADDRESSES_CACHE_DURATION: Duration = Duration::from_secs(5);
+static LOCAL_ADDRESSES: Mutex<Option<(Instant, Vec<IpAddr>)>> = Mutex::new(None);
+
+const DAYS: [(&str, Weekday); 7] = [
+    ("mon", Weekday::Mon),
+    ("tue", Weekday::Tue),
+    ("wed", Weekday::Wed),
+    ("thu", Weekday::Thu),
+    ("fri", Weekday::Fri),
+    ("sat", Weekday::Sat),
+    ("sun", Weekday::Sun),
+];
+
+/// Time windows and networks when synchronization is allowed to transfer. Empty hours or
+/// days mean no time restriction. A range ending before its start (ex. "22:00-06:00")
+/// goes over midnight and belongs to the day it starts. When networks are given,
+/// transfers are also allowed (or only allowed, without time windows) while one of local
+/// addresses is in one of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncSchedule {
+    hours: Vec<(NaiveTime, NaiveTime)>,
+    days: Vec<Weekday>,
+    networks: Vec<IpNet>,
+}
+
+impl SyncSchedule {
+    /// Read `sync_hours` (ex. "08:00-12:00,14:00-19:00"), `sync_days`
+    /// (ex. "mon-fri" or "mon,wed,fri") and `sync_networks` (ex. "192.168.10.0/24")
+    /// keys. Return None if section don't define them.
+    pub fn from_section(section: &Properties) -> Result<Option<Self>, String> {
+        let raw_hours = section.get("sync_hours").unwrap_or("").trim();
+        let raw_days = section.get("sync_days").unwrap_or("").trim();
+        let raw_networks = section.get("sync_networks").unwrap_or("").trim();
+        if raw_hours.is_empty() && raw_days.is_empty() && raw_networks.is_empty() {
+            return Ok(None);
+        }
+
+        Self::new(raw_hours, raw_days, raw_networks).map(Some)
+    }
+
+    /// Schedule from `sync_hours`, `sync_days` and `sync_networks` values (empty for no
+    /// restriction)
+    pub fn new(raw_hours: &str, raw_days: &str, raw_networks: &str) -> Result<Self, String> {
+        Ok(Self {
+            hours: parse_hours(raw_hours.trim())?,
+            days: parse_days(raw_days.trim())?,
+            networks: parse_networks(raw_networks.trim())?,
+        })
+    }
+
+    pub fn write_section(&self, section: &mut Properties) {
+        if !self.hours.is_empty() {
+            section.insert(
+                "sync_hours",
+                self.hours
+                    .iter()
+                    .map(|(start, end)| {
+                        format!("{}-{}", start.format("%H:%M"), end.format("%H:%M"))
+                    })
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+        }
+        if !self.days.is_empty() {
+            section.insert(
+                "sync_days",
+                self.days
+                    .iter()
+                    .filter_map(|day| DAYS.iter().find(|(_, day_)| day_ == day))
+                    .map(|(name, _)| name.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+        }
+        if !self.networks.is_empty() {
+            section.insert(
+                "sync_networks",
+                self.networks
+                    .iter()
+                    .map(|network| network.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+        }
+    }
+
+    pub fn is_always(&self) -> bool {
+        self.hours.is_empty() && self.days.is_empty() && self.networks.is_empty()
+    }
+
+    pub fn is_open(&self) -> bool {
+        if self.is_always() {
+            return true;
+        }
+        let addresses = match self.networks.is_empty() {
+            true => vec![],
+            false => local_addresses(),
+        };
+        self.is_open_with(Local::now().naive_local(), &addresses)
+    }
+
+    /// True if given time is in time windows, or one of given local addresses is in
+    /// allowed networks
+    pub fn is_open_with(&self, datetime: NaiveDateTime, addresses: &[IpAddr]) -> bool {
+        let has_time_windows = !self.hours.is_empty() || !self.days.is_empty();
+        let on_network = addresses.iter().any(|address| {
+            self.networks
+                .iter()
+                .any(|network| network.contains(address))
+        });
+
+        match (has_time_windows, self.networks.is_empty()) {
+            (false, true) => true,
+            (true, true) => self.is_open_at(datetime),
+            (false, false) => on_network,
+            (true, false) => on_network || self.is_open_at(datetime),
+        }
+    }
+
+    /// True if given time is in time windows
+    pub fn is_open_at(&self, datetime: NaiveDateTime) -> bool {
+        let day = datetime.weekday();
+        let is_allowed_day = |day: Weekday| self.days.is_empty() || self.days.contains(&day);
+        if self.hours.is_empty() {
+            return is_allowed_day(day);
+        }
+
+        let time = datetime.time();
+        self.hours.iter().any(|(start, end)| {
+            if start <= end {
+                &time >= start && &time < end && is_allowed_day(day)
+            } else if &time >= start {
+                is_allowed_day(day)
+            } else {
+                // Range going over midnight started the day before
+                &time < end && is_allowed_day(day.pred())
+            }
+        })
+    }
+}
+
+/// Addresses of local network interfaces
+/// Non loopback local addresses, cached for `ADDRESSES_CACHE_DURATION`
+fn local_addresses() -> Vec<IpAddr> {
+    let mut cache = LOCAL_ADDRESSES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some((read_at, addresses)) = cache.as_ref() {
+        if read_at.elapsed() < ADDRESSES_CACHE_DURATION {
+            return addresses.clone();
+        }
+    }
+
+    let addresses = read_local_addresses();
+    *cache = Some((Instant::now(), addresses.clone()));
+    addresses
+}
+
+fn read_local_addresses() -> Vec<IpAddr> {
+    match if_addrs::get_if_addrs() {
+        Ok(interfaces) => interfaces
+            .iter()
+            .filter(|interface| !interface.is_loopback())
+            .map(|interface| interface.ip())
+            .collect(),
+        Err(error) => {
+            log::error!("Unable to read local network addresses : {}", error);
+            vec![]
+        }
+    }
+}
+
+fn parse_hours(raw: &str) -> Result<Vec<(NaiveTime, NaiveTime)>, String> {
+    let mut hours = vec![];
+    for range in raw.split(',').filter(|v| !v.trim().is_empty()) {
+        let (start, end) = range
+            .split_once('-')
+            .ok_or(format!("Invalid hours range '{}'", range))?;
+        hours.push((parse_time(start)?, parse_time(end)?));
+    }
+    Ok(hours)
+}
+
+/// Networks in CIDR notation, or single addresses
+fn parse_networks(raw: &str) -> Result<Vec<IpNet>, String> {
+    let mut networks = vec![];
+    for value in raw.split(',').map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        let network = match IpNet::from_str(value) {
+            Ok(network) => network,
+            Err(_) => IpAddr::from_str(value)
+                .map(IpNet::from)
+                .map_err(|_| format!("Invalid network '{}'", value))?,
+        };
+        networks.push(network);
+    }
+    Ok(networks)
+}
+
+fn parse_time(raw: &str) -> Result<NaiveTime, String> {
+    let raw = raw.trim();
+    NaiveTime::parse_from_str(raw, "%H:%M").map_err(|_| format!("Invalid time '{}'", raw))
+}
+
+fn parse_days(raw: &str) -> Result<Vec<Weekday>, String> {
+    let mut days = vec![];
+    for value in raw.split(',').filter(|v| !v.trim().is_empty()) {
+        match value.split_once('-') {
+            Some((first, last)) => {
+                let first = day_position(first)?;
+                let last = day_position(last)?;
+                let mut position = first;
+                loop {
+                    days.push(DAYS[position].1);
+                    if position == last {
+                        break;
+                    }
+                    position = (position + 1) % DAYS.len();
+                }
+            }
+            None => days.push(DAYS[day_position(value)?].1),
+        }
+    }
+    Ok(days)
+}
+
+fn day_position(raw: &str) -> Result<usize, String> {
+    let raw = raw.trim().to_lowercase();
+    DAYS.iter()
+        .position(|(name, _)| raw.starts_with(name))
+        .ok_or(format!("Invalid day '{}'", raw))
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn schedule(hours: &str, days: &str) -> SyncSchedule {
+        let mut section = Properties::new();
+        section.insert("sync_hours", hours);
+        section.insert("sync_days", days);
+        SyncSchedule::from_section(&section).unwrap().unwrap()
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // 2023-01-02 is a monday
+        NaiveDate::from_ymd_opt(2023, 1, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_office_hours() {
+        let schedule = schedule("08:00-19:00", "mon-fri");
+        assert!(schedule.is_open_at(at(2, 8, 0)));
+        assert!(schedule.is_open_at(at(6, 18, 59)));
+        assert!(!schedule.is_open_at(at(2, 19, 0)));
+        assert!(!schedule.is_open_at(at(2, 7, 59)));
+        assert!(!schedule.is_open_at(at(7, 10, 0)));
+    }
+
+    #[test]
+    fn test_night_hours() {
+        let schedule = schedule("22:00-06:00", "");
+        assert!(schedule.is_open_at(at(2, 23, 0)));
+        assert!(schedule.is_open_at(at(3, 5, 0)));
+        assert!(!schedule.is_open_at(at(3, 12, 0)));
+    }
+
+    #[test]
+    fn test_night_hours_belong_to_start_day() {
+        // Friday night window continues on saturday morning, but doesn't start on saturday
+        let schedule = schedule("22:00-06:00", "mon-fri");
+        assert!(schedule.is_open_at(at(6, 23, 0)));
+        assert!(schedule.is_open_at(at(7, 5, 0)));
+        assert!(!schedule.is_open_at(at(7, 23, 0)));
+        assert!(!schedule.is_open_at(at(2, 5, 0)));
+        assert!(schedule.is_open_at(at(3, 5, 0)));
+    }
+
+    #[test]
+    fn test_networks() {
+        let mut section = Properties::new();
+        section.insert("sync_networks", "192.168.10.0/24, 10.1.2.3");
+        let office_only = SyncSchedule::from_section(&section).unwrap().unwrap();
+        section.insert("sync_hours", "22:00-06:00");
+        let night_or_office = SyncSchedule::from_section(&section).unwrap().unwrap();
+        let office: IpAddr = "192.168.10.42".parse().unwrap();
+        let home: IpAddr = "192.168.1.42".parse().unwrap();
+
+        assert!(office_only.is_open_with(at(2, 12, 0), &[home, office]));
+        assert!(!office_only.is_open_with(at(2, 23, 0), &[home]));
+        assert!(office_only.is_open_with(at(2, 12, 0), &["10.1.2.3".parse().unwrap()]));
+        assert!(night_or_office.is_open_with(at(2, 12, 0), &[office]));
+        assert!(night_or_office.is_open_with(at(2, 23, 0), &[home]));
+        assert!(!night_or_office.is_open_with(at(2, 12, 0), &[home]));
+        assert!(SyncSchedule::new("", "", "192.168.10.0/33").is_err());
+    }
+
+    #[test]
+    fn test_weekend_days() {
+        let schedule = schedule("", "sat,sun");
+        assert!(schedule.is_open_at(at(8, 12, 0)));
+        assert!(!schedule.is_open_at(at(4, 12, 0)));
+    }
+
+    #[test]
+    fn test_section_round_trip() {
+        let mut schedule = schedule("08:00-12:00,14:00-19:00", "fri-mon");
+        schedule.networks = parse_networks("192.168.10.0/24,10.1.2.3").unwrap();
+        let mut section = Properties::new();
+        schedule.write_section(&mut section);
+        assert_eq!(section.get("sync_days"), Some("fri,sat,sun,mon"));
+        assert_eq!(
+            section.get("sync_networks"),
+            Some("192.168.10.0/24,10.1.2.3/32")
+        );
+        assert_eq!(SyncSchedule::from_section(&section), Ok(Some(schedule)));
+    }
+}
@@ -3,7 +3,15 @@ extern crate keyring;
 
 use std::error::Error;
 
-pub fn get_password(instance_address: &str, username: &str) -> Result<String, Box<dyn Error>> {
+/// Password of given Tracim account. Passwords stored before several accounts per address
+/// were supported (keyed by OS username) are read as fallback only if `single_account` (the
+/// only account configured for this address, so the legacy password can't be another
+/// account one). Such legacy password is then moved to the account username.
+pub fn get_password(
+    instance_address: &str,
+    username: &str,
+    single_account: bool,
+) -> Result<String, Box<dyn Error>> {
     let service = format!("trsync::{}", instance_address);
     let entry = keyring::Entry::new(&service, username);
     log::info!(
@@ -11,7 +19,31 @@ pub fn get_password(instance_address: &str, username: &str) -> Result<String, Bo
         &service,
         &username
     );
-    Ok(entry.get_password()?)
+    match entry.get_password() {
+        Ok(password) => Ok(password),
+        Err(error) => {
+            let os_username = whoami::username();
+            if os_username == username || !single_account {
+                return Err(error.into());
+            }
+            log::info!(
+                "Get legacy password for service '{}' and user '{}'",
+                &service,
+                &os_username
+            );
+            let legacy_entry = keyring::Entry::new(&service, &os_username);
+            let password = legacy_entry.get_password().map_err(|_| error)?;
+            match entry.set_password(&password) {
+                Ok(_) => {
+                    if let Err(error) = legacy_entry.delete_password() {
+                        log::error!("Unable to delete legacy password : '{}'", error)
+                    }
+                }
+                Err(error) => log::error!("Unable to migrate legacy password : '{}'", error),
+            }
+            Ok(password)
+        }
+    }
 }
 
 pub fn set_password(instance_address: &str, username: &str, password: &str) -> Result<()> {
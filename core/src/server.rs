@@ -0,0 +1,130 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::types::ContentType;
+
+/// Oldest Tracim version trsync can synchronize with
+pub const MINIMUM_VERSION: ServerVersion = ServerVersion::new(3, 0, 0);
+/// Tracim version since which workspace contents are listed by pages
+pub const PAGINATED_CONTENTS_VERSION: ServerVersion = ServerVersion::new(4, 0, 0);
+/// Tracim version since which live messages can be replayed from a given event
+pub const EVENT_REPLAY_VERSION: ServerVersion = ServerVersion::new(3, 6, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ServerVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ServerVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl Display for ServerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for ServerVersion {
+    type Err = String;
+
+    /// Parse versions like "4.5.2", "4.5" or "4.5.2-beta"
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parts = value.trim().split('.').map(|part| {
+            part.chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect::<String>()
+                .parse::<u32>()
+        });
+        let mut next = || parts.next().transpose().map_err(|error| error.to_string());
+
+        let major = next()?.ok_or(format!("Empty version '{}'", value))?;
+        let minor = next()?.unwrap_or(0);
+        let patch = next()?.unwrap_or(0);
+        Ok(Self::new(major, minor, patch))
+    }
+}
+
+/// Behaviors of the Tracim server, deduced from its version and configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerCapabilities {
+    pub version: ServerVersion,
+    pub paginated_contents: bool,
+    pub event_replay: bool,
+    /// Enabled content types which trsync can synchronize
+    pub content_types: Vec<ContentType>,
+}
+
+impl ServerCapabilities {
+    /// Capabilities of given server version, or error message if version is not supported
+    pub fn new(version: ServerVersion, content_types: Vec<ContentType>) -> Result<Self, String> {
+        if version < MINIMUM_VERSION {
+            return Err(format!(
+                "Tracim version {} is not supported (minimum is {})",
+                version, MINIMUM_VERSION
+            ));
+        }
+
+        Ok(Self {
+            version,
+            paginated_contents: version >= PAGINATED_CONTENTS_VERSION,
+            event_replay: version >= EVENT_REPLAY_VERSION,
+            content_types,
+        })
+    }
+
+    pub fn supports(&self, content_type: &ContentType) -> bool {
+        self.content_types.contains(content_type)
+    }
+}
+
+impl Display for ServerCapabilities {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let content_types: Vec<String> = self
+            .content_types
+            .iter()
+            .map(|content_type| content_type.to_string())
+            .collect();
+        write!(
+            f,
+            "Tracim {} (paginated contents: {}, event replay: {}, content types: {})",
+            self.version,
+            self.paginated_contents,
+            self.event_replay,
+            content_types.join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_server_capabilities() {
+        // Given
+        let old: ServerVersion = "2.7".parse().unwrap();
+        let recent: ServerVersion = "4.5.2-beta".parse().unwrap();
+
+        // When
+        let old_capabilities = ServerCapabilities::new(old, vec![ContentType::File]);
+        let recent_capabilities = ServerCapabilities::new(recent, vec![ContentType::File]).unwrap();
+
+        // Then
+        assert!(old_capabilities.is_err());
+        assert_eq!(recent, ServerVersion::new(4, 5, 2));
+        assert!(recent_capabilities.paginated_contents);
+        assert!(recent_capabilities.event_replay);
+        assert!(recent_capabilities.supports(&ContentType::File));
+        assert!(!recent_capabilities.supports(&ContentType::HtmlDocument));
+    }
+}
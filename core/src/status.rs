@@ -0,0 +1,156 @@
+use std::{
+    fmt::Display,
+    time::{Duration, SystemTime},
+};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::tr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ConnectionState {
+    #[default]
+    Unknown,
+    Connected,
+    Disconnected,
+}
+
+impl Display for ConnectionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionState::Unknown => f.write_str(tr!("unknown")),
+            ConnectionState::Connected => f.write_str(tr!("connected")),
+            ConnectionState::Disconnected => f.write_str(tr!("disconnected")),
+        }
+    }
+}
+
+/// State of Tracim live messages stream listening
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TlmState {
+    #[default]
+    Unknown,
+    Listening,
+    Disconnected,
+}
+
+impl Display for TlmState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TlmState::Unknown => f.write_str(tr!("unknown")),
+            TlmState::Listening => f.write_str(tr!("listening")),
+            TlmState::Disconnected => f.write_str(tr!("disconnected")),
+        }
+    }
+}
+
+/// Health of a job, complementary to its current `Activity`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobStatus {
+    pub last_full_sync: Option<SystemTime>,
+    pub last_event: Option<SystemTime>,
+    pub pending_local: usize,
+    pub pending_remote: usize,
+    pub error_count: usize,
+    pub connection: ConnectionState,
+    pub tlm: TlmState,
+}
+
+impl JobStatus {
+    pub fn apply(&mut self, update: StatusUpdate) {
+        match update {
+            StatusUpdate::FullSyncDone => self.last_full_sync = Some(SystemTime::now()),
+            StatusUpdate::EventDone => self.last_event = Some(SystemTime::now()),
+            StatusUpdate::Pending(local, remote) => {
+                self.pending_local = local;
+                self.pending_remote = remote;
+            }
+            StatusUpdate::Failed => self.error_count += 1,
+            StatusUpdate::Connection(connection) => self.connection = connection,
+            StatusUpdate::Tlm(tlm) => self.tlm = tlm,
+        }
+    }
+
+    /// Server can't be reached (by requests or live messages)
+    pub fn is_disconnected(&self) -> bool {
+        self.connection == ConnectionState::Disconnected || self.tlm == TlmState::Disconnected
+    }
+
+    /// Most recent successful synchronization (full or by event)
+    pub fn last_sync(&self) -> Option<SystemTime> {
+        match (self.last_full_sync, self.last_event) {
+            (Some(full_sync), Some(event)) => Some(full_sync.max(event)),
+            (full_sync, event) => full_sync.or(event),
+        }
+    }
+}
+
+impl Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.last_sync() {
+            Some(last_sync) => f.write_str(&tr!("synchronized {}", ago(last_sync)))?,
+            None => f.write_str(tr!("never synchronized"))?,
+        }
+        if self.pending_local + self.pending_remote > 0 {
+            f.write_str(&tr!(
+                ", pending : {} local, {} remote",
+                self.pending_local,
+                self.pending_remote
+            ))?;
+        }
+        if self.error_count > 0 {
+            f.write_str(&tr!(", {} error(s)", self.error_count))?;
+        }
+        if self.is_disconnected() {
+            write!(f, ", {}", ConnectionState::Disconnected)?;
+        }
+        Ok(())
+    }
+}
+
+/// Change of a job status, sent by the job runner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StatusUpdate {
+    FullSyncDone,
+    EventDone,
+    /// Pending local and remote events count
+    Pending(usize, usize),
+    Failed,
+    Connection(ConnectionState),
+    Tlm(TlmState),
+}
+
+/// Human readable elapsed time since given time (ex. "2 min ago")
+pub fn ago(time: SystemTime) -> String {
+    let elapsed = time.elapsed().unwrap_or(Duration::ZERO).as_secs();
+    match elapsed {
+        0..=59 => tr!("{} s ago", elapsed),
+        60..=3599 => tr!("{} min ago", elapsed / 60),
+        3600..=86399 => tr!("{} h ago", elapsed / 3600),
+        _ => tr!("{} d ago", elapsed / 86400),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_job_status_apply() {
+        // Given
+        let mut status = JobStatus::default();
+
+        // When
+        status.apply(StatusUpdate::Pending(2, 3));
+        status.apply(StatusUpdate::Failed);
+        status.apply(StatusUpdate::Failed);
+        status.apply(StatusUpdate::Tlm(TlmState::Disconnected));
+
+        // Then
+        assert_eq!(status.pending_local, 2);
+        assert_eq!(status.pending_remote, 3);
+        assert_eq!(status.error_count, 2);
+        assert!(status.is_disconnected());
+        assert_eq!(status.last_sync(), None);
+    }
+}
@@ -0,0 +1,306 @@
+use std::{
+    io::{self, Read},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use ini::Properties;
+
+const THROTTLED_CHUNK_SIZE: usize = 16 * 1024;
+
+/// Upload and download limits, in KiB/s
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BandwidthConfig {
+    pub max_upload_rate: Option<u64>,
+    pub max_download_rate: Option<u64>,
+}
+
+impl BandwidthConfig {
+    pub fn from_section(section: &Properties) -> Result<Self, String> {
+        Ok(Self {
+            max_upload_rate: parse_rate(section, "max_upload_rate")?,
+            max_download_rate: parse_rate(section, "max_download_rate")?,
+        })
+    }
+
+    /// True if upload or download is limited (a zero rate means unlimited)
+    pub fn is_limited(&self) -> bool {
+        [self.max_upload_rate, self.max_download_rate]
+            .iter()
+            .any(|rate| rate.unwrap_or(0) > 0)
+    }
+
+    pub fn write_section(&self, section: &mut Properties) {
+        if let Some(max_upload_rate) = self.max_upload_rate {
+            section.insert("max_upload_rate", max_upload_rate.to_string());
+        }
+        if let Some(max_download_rate) = self.max_download_rate {
+            section.insert("max_download_rate", max_download_rate.to_string());
+        }
+    }
+}
+
+fn parse_rate(section: &Properties, key: &str) -> Result<Option<u64>, String> {
+    match section.get(key) {
+        Some(value) if !value.trim().is_empty() => {
+            Ok(Some(value.trim().parse::<u64>().map_err(|_| {
+                format!("Invalid {} value '{}'", key, value)
+            })?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Shared rate limiter. Each consumer reserves transfer time, so readers
+/// sharing the same limiter share its bandwidth. A rate of zero means unlimited.
+#[derive(Debug)]
+pub struct RateLimiter {
+    bytes_per_second: AtomicU64,
+    next_free: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(kib_per_second: Option<u64>) -> Self {
+        Self {
+            bytes_per_second: AtomicU64::new(kib_per_second.unwrap_or(0) * 1024),
+            next_free: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub fn set_rate(&self, kib_per_second: Option<u64>) {
+        self.bytes_per_second
+            .store(kib_per_second.unwrap_or(0) * 1024, Ordering::Relaxed);
+    }
+
+    pub fn consume(&self, bytes: usize) {
+        let bytes_per_second = self.bytes_per_second.load(Ordering::Relaxed);
+        if bytes_per_second == 0 {
+            return;
+        }
+
+        let wait = {
+            let mut next_free = self.next_free.lock().unwrap();
+            let now = Instant::now();
+            let start = (*next_free).max(now);
+            *next_free = start + Duration::from_secs_f64(bytes as f64 / bytes_per_second as f64);
+            start - now
+        };
+
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+    }
+}
+
+pub struct ThrottledReader<R: Read> {
+    inner: R,
+    limiters: Vec<Arc<RateLimiter>>,
+}
+
+impl<R: Read> ThrottledReader<R> {
+    pub fn new(inner: R, limiters: Vec<Arc<RateLimiter>>) -> Self {
+        Self { inner, limiters }
+    }
+}
+
+impl<R: Read> Read for ThrottledReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.limiters.is_empty() {
+            return self.inner.read(buf);
+        }
+
+        let max = buf.len().min(THROTTLED_CHUNK_SIZE);
+        let read = self.inner.read(&mut buf[..max])?;
+        if read == 0 {
+            return Ok(0);
+        }
+        for limiter in &self.limiters {
+            limiter.consume(read);
+        }
+        Ok(read)
+    }
+}
+
+#[derive(Debug)]
+struct SchedulerState {
+    running: usize,
+    // (priority, ticket) of waiting transfers
+    waiting: Vec<(i32, u64)>,
+    next_ticket: u64,
+}
+
+/// Limit the number of simultaneous transfers. When a slot is released, the
+/// waiting transfer with the highest priority (then the oldest one) takes it.
+/// Zero slots means unlimited.
+#[derive(Debug)]
+pub struct TransferScheduler {
+    slots: AtomicUsize,
+    state: Mutex<SchedulerState>,
+    condvar: Condvar,
+}
+
+impl TransferScheduler {
+    pub fn new(slots: usize) -> Self {
+        Self {
+            slots: AtomicUsize::new(slots),
+            state: Mutex::new(SchedulerState {
+                running: 0,
+                waiting: vec![],
+                next_ticket: 0,
+            }),
+            condvar: Condvar::new(),
+        }
+    }
+
+    pub fn set_slots(&self, slots: usize) {
+        self.slots.store(slots, Ordering::Relaxed);
+        self.condvar.notify_all();
+    }
+
+    pub fn running(&self) -> usize {
+        self.state.lock().unwrap().running
+    }
+
+    pub fn waiting(&self) -> usize {
+        self.state.lock().unwrap().waiting.len()
+    }
+
+    pub fn acquire(self: &Arc<Self>, priority: i32) -> TransferPermit {
+        let mut state = self.state.lock().unwrap();
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        state.waiting.push((priority, ticket));
+
+        loop {
+            let slots = self.slots.load(Ordering::Relaxed);
+            let elected = state
+                .waiting
+                .iter()
+                .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))
+                .map(|(_, ticket_)| *ticket_);
+
+            if (slots == 0 || state.running < slots) && elected == Some(ticket) {
+                state.waiting.retain(|(_, ticket_)| *ticket_ != ticket);
+                state.running += 1;
+                break;
+            }
+
+            state = self.condvar.wait(state).unwrap();
+        }
+
+        // Another slot may be available for next waiting transfer
+        self.condvar.notify_all();
+        TransferPermit {
+            scheduler: self.clone(),
+        }
+    }
+
+    fn release(&self) {
+        self.state.lock().unwrap().running -= 1;
+        self.condvar.notify_all();
+    }
+}
+
+pub struct TransferPermit {
+    scheduler: Arc<TransferScheduler>,
+}
+
+impl Drop for TransferPermit {
+    fn drop(&mut self) {
+        self.scheduler.release()
+    }
+}
+
+/// Throttling and scheduling applied to file transfers of a workspace
+#[derive(Debug, Clone, Default)]
+pub struct TransferControl {
+    upload_limiters: Vec<Arc<RateLimiter>>,
+    download_limiters: Vec<Arc<RateLimiter>>,
+    scheduler: Option<Arc<TransferScheduler>>,
+    priority: i32,
+}
+
+impl TransferControl {
+    pub fn upload_limiter(mut self, value: Arc<RateLimiter>) -> Self {
+        self.upload_limiters.push(value);
+        self
+    }
+
+    pub fn download_limiter(mut self, value: Arc<RateLimiter>) -> Self {
+        self.download_limiters.push(value);
+        self
+    }
+
+    pub fn scheduler(mut self, value: Arc<TransferScheduler>, priority: i32) -> Self {
+        self.scheduler = Some(value);
+        self.priority = priority;
+        self
+    }
+
+    pub fn permit(&self) -> Option<TransferPermit> {
+        self.scheduler
+            .as_ref()
+            .map(|scheduler| scheduler.acquire(self.priority))
+    }
+
+    pub fn upload_reader<R: Read>(&self, reader: R) -> ThrottledReader<R> {
+        ThrottledReader::new(reader, self.upload_limiters.clone())
+    }
+
+    pub fn download_reader<R: Read>(&self, reader: R) -> ThrottledReader<R> {
+        ThrottledReader::new(reader, self.download_limiters.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        // Given
+        let limiter = Arc::new(RateLimiter::new(Some(64)));
+        let mut reader = ThrottledReader::new(&[0u8; 64 * 1024][..], vec![limiter]);
+
+        // When
+        let start = Instant::now();
+        io::copy(&mut reader, &mut io::sink()).unwrap();
+
+        // Then : four chunks of 16 KiB, the first one is free
+        assert!(start.elapsed() >= Duration::from_millis(700));
+    }
+
+    #[test]
+    fn test_scheduler_priority() {
+        // Given
+        let scheduler = Arc::new(TransferScheduler::new(1));
+        let order = Arc::new(Mutex::new(vec![]));
+        let permit = scheduler.acquire(0);
+
+        // When
+        let mut handles = vec![];
+        for priority in [1, 10, 5] {
+            let scheduler_ = scheduler.clone();
+            let order = order.clone();
+            handles.push(thread::spawn(move || {
+                let _permit = scheduler_.acquire(priority);
+                order.lock().unwrap().push(priority);
+            }));
+            // Ensure waiting order
+            while scheduler.waiting() != handles.len() {
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+        drop(permit);
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Then
+        assert_eq!(*order.lock().unwrap(), vec![10, 5, 1]);
+    }
+}
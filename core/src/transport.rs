@@ -0,0 +1,260 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use ini::Properties;
+use reqwest::{Certificate, Identity, Proxy};
+
+use crate::client::{TracimClientError, DEFAULT_CLIENT_TIMEOUT};
+
+pub const DEFAULT_BASE_PATH: &str = "/api";
+pub const DEFAULT_TRANSFER_TIMEOUT: u64 = 600;
+
+/// Http settings used to reach an instance (proxy, tls material, api location and timeouts)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransportConfig {
+    pub proxy: Option<String>,
+    pub ca_certificates: Vec<PathBuf>,
+    pub client_certificate: Option<PathBuf>,
+    pub client_key: Option<PathBuf>,
+    pub base_path: String,
+    pub timeout: u64,
+    pub transfer_timeout: u64,
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            ca_certificates: vec![],
+            client_certificate: None,
+            client_key: None,
+            base_path: DEFAULT_BASE_PATH.to_string(),
+            timeout: DEFAULT_CLIENT_TIMEOUT,
+            transfer_timeout: DEFAULT_TRANSFER_TIMEOUT,
+        }
+    }
+}
+
+impl TransportConfig {
+    pub fn from_section(section: &Properties) -> Result<Self, String> {
+        let default = Self::default();
+        let timeout = match section.get("timeout") {
+            Some(value) => value
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid timeout value '{}'", value))?,
+            None => default.timeout,
+        };
+        let transfer_timeout = match section.get("transfer_timeout") {
+            Some(value) => value
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid transfer_timeout value '{}'", value))?,
+            None => default.transfer_timeout,
+        };
+        let ca_certificates = section
+            .get("ca_certificates")
+            .unwrap_or("")
+            .split(',')
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| PathBuf::from(v.trim()))
+            .collect();
+
+        Ok(Self {
+            proxy: section.get("proxy").map(|v| v.to_string()),
+            ca_certificates,
+            client_certificate: section.get("client_certificate").map(PathBuf::from),
+            client_key: section.get("client_key").map(PathBuf::from),
+            base_path: normalize_base_path(section.get("base_path").unwrap_or(DEFAULT_BASE_PATH)),
+            timeout,
+            transfer_timeout,
+        })
+    }
+
+    /// Write only values which differ from defaults, to keep config files short
+    pub fn write_section(&self, section: &mut Properties) {
+        let default = Self::default();
+        if let Some(proxy) = &self.proxy {
+            section.insert("proxy", proxy.clone());
+        }
+        if !self.ca_certificates.is_empty() {
+            section.insert(
+                "ca_certificates",
+                self.ca_certificates
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+        }
+        if let Some(client_certificate) = &self.client_certificate {
+            section.insert(
+                "client_certificate",
+                client_certificate.display().to_string(),
+            );
+        }
+        if let Some(client_key) = &self.client_key {
+            section.insert("client_key", client_key.display().to_string());
+        }
+        if self.base_path != default.base_path {
+            section.insert("base_path", self.base_path.clone());
+        }
+        if self.timeout != default.timeout {
+            section.insert("timeout", self.timeout.to_string());
+        }
+        if self.transfer_timeout != default.transfer_timeout {
+            section.insert("transfer_timeout", self.transfer_timeout.to_string());
+        }
+    }
+
+    /// Api root url, without trailing slash (ex. "https://mon.tracim.fr/api")
+    pub fn api_url(&self, unsecure: bool, address: &str) -> String {
+        let scheme = if unsecure { "http" } else { "https" };
+        format!(
+            "{}://{}{}",
+            scheme,
+            address,
+            normalize_base_path(&self.base_path)
+        )
+    }
+
+    /// Client for regular api requests
+    pub fn client(&self) -> Result<reqwest::blocking::Client, TracimClientError> {
+        self.blocking_client(Duration::from_secs(self.timeout))
+    }
+
+    /// Client for file uploads and downloads, which can take much longer than regular requests
+    pub fn transfer_client(&self) -> Result<reqwest::blocking::Client, TracimClientError> {
+        self.blocking_client(Duration::from_secs(self.transfer_timeout))
+    }
+
+    /// Client for long living requests (like live messages), so without timeout
+    pub fn async_client(&self) -> Result<reqwest::Client, TracimClientError> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(proxy) = self.proxy()? {
+            builder = builder.proxy(proxy);
+        }
+        for certificate in self.certificates()? {
+            builder = builder.add_root_certificate(certificate);
+        }
+        if let Some(identity) = self.identity()? {
+            builder = builder.identity(identity);
+        }
+        Ok(builder.build()?)
+    }
+
+    fn blocking_client(
+        &self,
+        timeout: Duration,
+    ) -> Result<reqwest::blocking::Client, TracimClientError> {
+        let mut builder = reqwest::blocking::Client::builder().timeout(timeout);
+        if let Some(proxy) = self.proxy()? {
+            builder = builder.proxy(proxy);
+        }
+        for certificate in self.certificates()? {
+            builder = builder.add_root_certificate(certificate);
+        }
+        if let Some(identity) = self.identity()? {
+            builder = builder.identity(identity);
+        }
+        Ok(builder.build()?)
+    }
+
+    fn proxy(&self) -> Result<Option<Proxy>, TracimClientError> {
+        match &self.proxy {
+            Some(proxy) => Ok(Some(Proxy::all(proxy).map_err(|error| {
+                TracimClientError::PrepareError(format!("Invalid proxy '{}' : {}", proxy, error))
+            })?)),
+            None => Ok(None),
+        }
+    }
+
+    fn certificates(&self) -> Result<Vec<Certificate>, TracimClientError> {
+        let mut certificates = vec![];
+        for path in &self.ca_certificates {
+            let pem = read(path)?;
+            certificates.push(Certificate::from_pem(&pem).map_err(|error| {
+                TracimClientError::PrepareError(format!(
+                    "Invalid certificate '{}' : {}",
+                    path.display(),
+                    error
+                ))
+            })?);
+        }
+        Ok(certificates)
+    }
+
+    fn identity(&self) -> Result<Option<Identity>, TracimClientError> {
+        match (&self.client_certificate, &self.client_key) {
+            (Some(certificate_path), Some(key_path)) => {
+                let certificate = read(certificate_path)?;
+                let key = read(key_path)?;
+                Ok(Some(Identity::from_pkcs8_pem(&certificate, &key).map_err(
+                    |error| {
+                        TracimClientError::PrepareError(format!(
+                            "Invalid client certificate '{}' : {}",
+                            certificate_path.display(),
+                            error
+                        ))
+                    },
+                )?))
+            }
+            (None, None) => Ok(None),
+            _ => Err(TracimClientError::PrepareError(
+                "Client certificate require both client_certificate and client_key".to_string(),
+            )),
+        }
+    }
+}
+
+fn read(path: &PathBuf) -> Result<Vec<u8>, TracimClientError> {
+    fs::read(path).map_err(|error| TracimClientError::FileNotFound(path.clone(), error.to_string()))
+}
+
+fn normalize_base_path(base_path: &str) -> String {
+    let base_path = base_path.trim().trim_end_matches('/');
+    if base_path.is_empty() || base_path.starts_with('/') {
+        base_path.to_string()
+    } else {
+        format!("/{}", base_path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_api_url() {
+        for (base_path, expected) in [
+            ("/api", "https://mon.tracim.fr/api"),
+            ("/api/", "https://mon.tracim.fr/api"),
+            ("tracim/api", "https://mon.tracim.fr/tracim/api"),
+            ("", "https://mon.tracim.fr"),
+        ] {
+            let transport = TransportConfig {
+                base_path: base_path.to_string(),
+                ..Default::default()
+            };
+            assert_eq!(transport.api_url(false, "mon.tracim.fr"), expected);
+        }
+    }
+
+    #[test]
+    fn test_section_round_trip() {
+        // Given
+        let transport = TransportConfig {
+            proxy: Some("http://proxy:3128".to_string()),
+            ca_certificates: vec![PathBuf::from("/a.pem"), PathBuf::from("/b.pem")],
+            client_certificate: Some(PathBuf::from("/cert.pem")),
+            client_key: Some(PathBuf::from("/key.pem")),
+            base_path: "/tracim/api".to_string(),
+            timeout: 10,
+            transfer_timeout: 3600,
+        };
+        let mut section = Properties::new();
+
+        // When
+        transport.write_section(&mut section);
+
+        // Then
+        assert_eq!(TransportConfig::from_section(&section), Ok(transport));
+    }
+}
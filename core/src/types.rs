@@ -1,5 +1,6 @@
 use std::{fmt::Display, path::Path, str::FromStr};
 
+use serde_derive::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::HTML_DOCUMENT_LOCAL_EXTENSION;
@@ -11,7 +12,7 @@ pub type RevisionId = i32;
 pub type LastModifiedTimestamp = i64;
 pub type EventType = String;
 
-#[derive(Eq, PartialEq, Clone, Debug, Copy)]
+#[derive(Eq, PartialEq, Clone, Debug, Copy, Serialize, Deserialize)]
 pub enum ContentType {
     File,
     Folder,
@@ -105,24 +106,35 @@ impl Display for ParseRemoteEventTypeError {
     }
 }
 
+impl RemoteEventType {
+    /// Parse live message event types like "content.modified.file", returning the
+    /// concerned content type
+    pub fn parse_content_event(
+        value: &str,
+    ) -> Result<(RemoteEventType, ContentType), ParseRemoteEventTypeError> {
+        let error = || ParseRemoteEventTypeError(value.to_string());
+        let mut parts = value.splitn(3, '.');
+        if parts.next() != Some("content") {
+            return Err(error());
+        }
+        let event_type = match parts.next() {
+            Some("created") | Some("undeleted") => Self::Created,
+            Some("modified") => Self::Modified,
+            Some("deleted") => Self::Deleted,
+            _ => return Err(error()),
+        };
+        let content_type = parts
+            .next()
+            .and_then(|content_type| ContentType::from_str(content_type).ok())
+            .ok_or_else(error)?;
+        Ok((event_type, content_type))
+    }
+}
+
 impl FromStr for RemoteEventType {
     type Err = ParseRemoteEventTypeError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        match value {
-            "content.modified.file" => Ok(Self::Modified),
-            "content.modified.html-document" => Ok(Self::Modified),
-            "content.modified.folder" => Ok(Self::Modified),
-            "content.created.file" => Ok(Self::Created),
-            "content.created.html-document" => Ok(Self::Created),
-            "content.created.folder" => Ok(Self::Created),
-            "content.deleted.html-document" => Ok(Self::Deleted),
-            "content.deleted.file" => Ok(Self::Deleted),
-            "content.deleted.folder" => Ok(Self::Deleted),
-            "content.undeleted.html-document" => Ok(Self::Created),
-            "content.undeleted.file" => Ok(Self::Created),
-            "content.undeleted.folder" => Ok(Self::Created),
-            _ => Err(ParseRemoteEventTypeError(value.to_string())),
-        }
+        Self::parse_content_event(value).map(|(event_type, _)| event_type)
     }
 }
@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use crate::instance::WorkspaceId;
+
 pub struct UserId(pub i32);
 
 impl Display for UserId {
@@ -11,6 +13,9 @@ impl Display for UserId {
 pub enum UserRequest {
     OpenMonitorWindow(MonitorWindowPanel),
     OpenConfigurationWindow,
+    /// Workspace (instance name and workspace id) was deleted or user is not member of
+    /// it anymore, its synchronization is stopped
+    WorkspaceAccessLost(String, WorkspaceId),
     Quit,
 }
 
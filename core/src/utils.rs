@@ -1,14 +1,28 @@
 use minidom::Element;
 use std::{
     fs::File,
-    io::{BufRead, BufReader},
-    path::PathBuf,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+    process::Command,
 };
 
 pub fn strbool(value: &str) -> bool {
     ["true", "True", "t", "T", "1"].contains(&value)
 }
 
+/// Open given folder with the system file manager
+pub fn open_folder(path: &Path) -> io::Result<()> {
+    #[cfg(target_os = "windows")]
+    let program = "explorer";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let program = "xdg-open";
+
+    Command::new(program).arg(path).spawn()?;
+    Ok(())
+}
+
 // TODO : Result instead unwrap
 // From https://stackoverflow.com/a/75443325/801924
 pub fn md5_file(file_path: &PathBuf) -> String {
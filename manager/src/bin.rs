@@ -1,66 +1,233 @@
 use std::{
-    sync::{Arc, Mutex},
+    env,
+    fs::File,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::channel,
+        Arc, Mutex,
+    },
     thread,
+    time::Duration,
 };
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use daemon::Daemon;
-use env_logger::Env;
+use env_logger::{Env, Target};
 use message::DaemonMessage;
+use notify::{watcher, DebouncedEvent, RecursiveMode, Watcher};
+#[cfg(unix)]
+use signal_hook::{
+    consts::{SIGHUP, SIGINT, SIGTERM},
+    iterator::Signals,
+};
+use socket::ConfigLoader;
+use structopt::StructOpt;
 use trsync_core::{
-    activity::WrappedActivity, config::ManagerConfig, error::ErrorExchanger, sync::SyncExchanger,
+    activity::{ActivityMonitor, ActivityState, WrappedActivity},
+    config::ManagerConfig,
+    error::ErrorExchanger,
+    sync::SyncExchanger,
     user::UserRequest,
 };
+use tui::Tui;
 
+pub mod child;
 pub mod client;
 pub mod daemon;
 pub mod error;
+pub mod folder;
+pub mod membership;
 pub mod message;
+pub mod socket;
+pub mod tui;
 pub mod types;
 
 type DaemonMessageChannels = (Sender<DaemonMessage>, Receiver<DaemonMessage>);
 type ActivityChannels = (Sender<WrappedActivity>, Receiver<WrappedActivity>);
 
-fn main_() -> Result<()> {
-    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
+#[derive(StructOpt, Debug)]
+#[structopt(name = "trsync_manager")]
+struct Opt {
+    /// Reload config when config file is modified
+    #[structopt(long)]
+    watch_config: bool,
 
-    // As standalone server (no systray), we never send daemon messages
-    // TODO : listen to SIG_HUP signal to send reload daemon message
-    let (_, _main_channel_receiver): (
-        Sender<message::DaemonMessage>,
-        Receiver<message::DaemonMessage>,
-    ) = unbounded();
+    /// Display terminal user interface (logs are then written in trsync_manager.log of temporary folder)
+    #[structopt(long)]
+    tui: bool,
+}
 
-    log::info!("Read config");
+/// As standalone server (no systray), there is nobody to confirm startup syncs
+fn load_config() -> Result<ManagerConfig> {
     let mut config = ManagerConfig::from_env(true)?;
     config.confirm_startup_sync = false;
     config.popup_confirm_startup_sync = false;
+    Ok(config)
+}
+
+/// With terminal user interface, startup syncs can be confirmed (but without popup)
+fn load_tui_config() -> Result<ManagerConfig> {
+    let mut config = ManagerConfig::from_env(true)?;
+    config.popup_confirm_startup_sync = false;
+    Ok(config)
+}
+
+fn reload(main_sender: &Sender<DaemonMessage>, config_loader: ConfigLoader) {
+    match config_loader() {
+        Ok(config) => {
+            if main_sender.send(DaemonMessage::Reload(config)).is_err() {
+                log::error!("Unable to send reload message to daemon")
+            }
+        }
+        Err(error) => log::error!("Unable to reload config : {:#}", error),
+    }
+}
+
+/// Reload config on SIGHUP, stop on SIGTERM and SIGINT
+#[cfg(unix)]
+fn listen_signals(main_sender: Sender<DaemonMessage>, config_loader: ConfigLoader) -> Result<()> {
+    let mut signals =
+        Signals::new([SIGHUP, SIGTERM, SIGINT]).context("Register signals handler")?;
+
+    thread::spawn(move || {
+        for signal in signals.forever() {
+            if signal == SIGHUP {
+                log::info!("SIGHUP received, reload config");
+                reload(&main_sender, config_loader);
+                continue;
+            }
+
+            log::info!("Signal {} received, stop", signal);
+            if main_sender.send(DaemonMessage::Stop).is_err() {
+                log::error!("Unable to send stop message to daemon");
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Reload config when config file is written (by configure window for example)
+fn watch_config(main_sender: Sender<DaemonMessage>, config_loader: ConfigLoader) -> Result<()> {
+    let config_path = ManagerConfig::path()?;
+    let folder_path = config_path
+        .parent()
+        .context("Determine config file folder")?
+        .to_path_buf();
+    let (inotify_sender, inotify_receiver) = channel();
+    let mut inotify_watcher = watcher(inotify_sender, Duration::from_secs(1))?;
+    // Watch folder because config file can be replaced instead of modified
+    inotify_watcher.watch(&folder_path, RecursiveMode::NonRecursive)?;
 
+    thread::spawn(move || {
+        // Moved here to keep watcher alive
+        let _inotify_watcher = inotify_watcher;
+        while let Ok(event) = inotify_receiver.recv() {
+            match event {
+                DebouncedEvent::Create(path)
+                | DebouncedEvent::Write(path)
+                | DebouncedEvent::Rename(_, path)
+                    if path == config_path =>
+                {
+                    log::info!("Config file modified, reload config");
+                    reload(&main_sender, config_loader);
+                }
+                _ => {}
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn main_() -> Result<()> {
+    let opt = Opt::from_args();
+    let mut logger = env_logger::Builder::from_env(Env::default().default_filter_or("info"));
+    if opt.tui {
+        // Logs would be printed over terminal user interface
+        let log_file_path = env::temp_dir().join("trsync_manager.log");
+        let log_file = File::create(&log_file_path)
+            .context(format!("Create log file '{}'", log_file_path.display()))?;
+        logger.target(Target::Pipe(Box::new(log_file)));
+    }
+    logger.init();
+
+    let config_loader: ConfigLoader = if opt.tui {
+        load_tui_config
+    } else {
+        load_config
+    };
+    log::info!("Read config");
+    let config = config_loader()?;
+
+    let stop_signal = Arc::new(AtomicBool::new(false));
+    let activity_state = Arc::new(Mutex::new(ActivityState::new()));
     let sync_exchanger = Arc::new(Mutex::new(SyncExchanger::new()));
     let error_exchanger = Arc::new(Mutex::new(ErrorExchanger::new()));
-    let (_main_sender, main_receiver): DaemonMessageChannels = unbounded();
+    let (main_sender, main_receiver): DaemonMessageChannels = unbounded();
     let (activity_sender, activity_receiver): ActivityChannels = unbounded();
-    let sync_exchanger = sync_exchanger.clone();
-    let error_exchanger = error_exchanger.clone();
     let (user_request_sender, _): (Sender<UserRequest>, Receiver<UserRequest>) = unbounded();
 
-    thread::spawn(move || {
-        while let Ok(activity) = activity_receiver.recv() {
-            log::debug!("Activity: {:?}", activity)
-        }
+    #[cfg(unix)]
+    listen_signals(main_sender.clone(), config_loader)?;
+    if opt.watch_config {
+        watch_config(main_sender.clone(), config_loader)?;
+    }
+
+    ActivityMonitor::new(
+        activity_receiver,
+        activity_state.clone(),
+        stop_signal.clone(),
+    )
+    .start();
+
+    #[cfg(unix)]
+    let socket_handle = socket::server::SocketServer::new(
+        socket::socket_path(config.control_socket.as_deref())?,
+        main_sender.clone(),
+        activity_state.clone(),
+        sync_exchanger.clone(),
+        error_exchanger.clone(),
+        config_loader,
+        stop_signal.clone(),
+    )
+    .start()?;
+
+    let tui_handle = opt.tui.then(|| {
+        let mut tui = Tui::new(
+            activity_state,
+            sync_exchanger.clone(),
+            error_exchanger.clone(),
+            main_sender.clone(),
+            stop_signal.clone(),
+        );
+        thread::spawn(move || tui.run())
     });
 
     log::info!("Start daemon");
     Daemon::new(
         config,
         main_receiver,
+        main_sender,
         activity_sender,
         user_request_sender,
         sync_exchanger,
         error_exchanger,
     )
     .run()?;
+    stop_signal.store(true, Ordering::Relaxed);
+    #[cfg(unix)]
+    if socket_handle.join().is_err() {
+        log::error!("Control socket thread panicked");
+    }
+    if let Some(tui_handle) = tui_handle {
+        match tui_handle.join() {
+            Ok(Err(error)) => log::error!("Terminal user interface error : {:#}", error),
+            Err(_) => log::error!("Terminal user interface thread panicked"),
+            Ok(Ok(_)) => {}
+        }
+    }
     log::info!("Daemon finished, exit");
 
     Ok(())
@@ -0,0 +1,506 @@
+use std::{
+    env,
+    io::BufReader,
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, Command, ExitStatus, Stdio},
+    sync::atomic::Ordering,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError};
+use ini::Properties;
+use trsync::pipe::{
+    read_message, write_message, ChildError, ChildMessage, ParentMessage, PASSWORD_ENV_VAR,
+};
+use trsync_core::{
+    activity::{Activity, WrappedActivity},
+    control::RemoteControl,
+    error::{Decision, RunnerError},
+    instance::{Instance, WorkspaceId},
+    job::JobIdentifier,
+    schedule::SyncSchedule,
+    user::{MonitorWindowPanel, UserRequest},
+};
+
+use crate::types::Backoff;
+
+const POLL_DELAY: Duration = Duration::from_millis(250);
+/// Child process is killed if it is not stopped after this delay
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+/// Child process which ran at least this delay before crashing is restarted with initial delay
+const HEALTHY_RUN: Duration = Duration::from_secs(600);
+
+/// `trsync` executable next to current executable
+pub fn default_trsync_bin() -> PathBuf {
+    let name = format!("trsync{}", env::consts::EXE_SUFFIX);
+    match env::current_exe() {
+        Ok(current_exe) => current_exe.with_file_name(name),
+        Err(_) => PathBuf::from(name),
+    }
+}
+
+/// `trsync --pipe` arguments to synchronize given workspace. Transfer limits are not given :
+/// child processes can't share them, so config refuses them with isolated processes.
+pub fn child_args(
+    instance: &Instance,
+    workspace_id: WorkspaceId,
+    folder_path: &str,
+    schedule: &SyncSchedule,
+    confirm_startup_sync: bool,
+) -> Vec<String> {
+    let mut args = vec![
+        folder_path.to_string(),
+        instance.address.clone(),
+        workspace_id.to_string(),
+        instance.username.clone(),
+        "--pipe".to_string(),
+        "--env-var-pass".to_string(),
+        PASSWORD_ENV_VAR.to_string(),
+        "--base-path".to_string(),
+        instance.transport.base_path.clone(),
+        "--timeout".to_string(),
+        instance.transport.timeout.to_string(),
+        "--transfer-timeout".to_string(),
+        instance.transport.transfer_timeout.to_string(),
+    ];
+    if instance.unsecure {
+        args.push("--no-ssl".to_string());
+    }
+    if confirm_startup_sync {
+        args.push("--confirm-startup-sync".to_string());
+    }
+    if let Some(proxy) = &instance.transport.proxy {
+        args.extend(["--proxy".to_string(), proxy.clone()]);
+    }
+    for ca_certificate in &instance.transport.ca_certificates {
+        args.extend(["--ca-certificate".to_string(), path_arg(ca_certificate)]);
+    }
+    if let Some(client_certificate) = &instance.transport.client_certificate {
+        args.extend([
+            "--client-certificate".to_string(),
+            path_arg(client_certificate),
+        ]);
+    }
+    if let Some(client_key) = &instance.transport.client_key {
+        args.extend(["--client-key".to_string(), path_arg(client_key)]);
+    }
+
+    let mut schedule_section = Properties::new();
+    schedule.write_section(&mut schedule_section);
+    for key in ["sync_hours", "sync_days", "sync_networks"] {
+        if let Some(value) = schedule_section.get(key) {
+            args.extend([format!("--{}", key.replace('_', "-")), value.to_string()]);
+        }
+    }
+
+    args
+}
+
+fn path_arg(path: &Path) -> String {
+    path.display().to_string()
+}
+
+/// Run a workspace synchronization in a `trsync --pipe` child process, relay its messages
+/// to manager channels and restart it when it crashes
+pub struct ChildSupervisor {
+    bin: PathBuf,
+    args: Vec<String>,
+    password: String,
+    job_identifier: JobIdentifier,
+    remote: RemoteControl,
+    /// Restart delays, cloned to restart from initial delay
+    backoff: Backoff,
+}
+
+impl ChildSupervisor {
+    pub fn new(
+        bin: PathBuf,
+        args: Vec<String>,
+        password: String,
+        job_identifier: JobIdentifier,
+        remote: RemoteControl,
+    ) -> Self {
+        Self {
+            bin,
+            args,
+            password,
+            job_identifier,
+            remote,
+            backoff: Backoff::new(),
+        }
+    }
+
+    pub fn backoff(mut self, value: Backoff) -> Self {
+        self.backoff = value;
+        self
+    }
+
+    pub fn run(&self) -> Result<()> {
+        let mut backoff = self.backoff.clone();
+
+        loop {
+            let started = Instant::now();
+            let result = self.run_child();
+            self.forget_pending();
+
+            if self.remote.stop_signal().load(Ordering::Relaxed) {
+                log::info!("Process of '{}' stopped", self.job_identifier);
+                break;
+            }
+            let failure = match result {
+                Ok(status) if status.success() => {
+                    log::info!("Process of '{}' finished", self.job_identifier);
+                    break;
+                }
+                Ok(status) => format!("crashed ({})", status),
+                Err(error) => format!("failed ({:#})", error),
+            };
+
+            if started.elapsed() >= HEALTHY_RUN {
+                backoff = self.backoff.clone();
+            }
+            let delay = backoff.failed();
+            log::error!(
+                "Process of '{}' {}, restart it in {}s",
+                self.job_identifier,
+                failure,
+                delay.as_secs()
+            );
+            self.set_activity(Activity::Error);
+            while !backoff.is_due() {
+                if self.remote.stop_signal().load(Ordering::Relaxed) {
+                    return Ok(());
+                }
+                thread::sleep(POLL_DELAY);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn spawn(&self) -> Result<Child> {
+        log::info!(
+            "Start process '{}' for '{}'",
+            self.bin.display(),
+            self.job_identifier
+        );
+        Command::new(&self.bin)
+            .args(&self.args)
+            .env(PASSWORD_ENV_VAR, &self.password)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .context(format!("Start process '{}'", self.bin.display()))
+    }
+
+    /// Relay messages and signals until child process exit
+    fn run_child(&self) -> Result<ExitStatus> {
+        let mut child = self.spawn()?;
+        let mut stdin = child.stdin.take().context("Get child process stdin")?;
+        let messages = self.listen(&mut child)?;
+
+        let mut paused = false;
+        let mut stop_requested_at: Option<Instant> = None;
+        let mut waiting_confirmation = false;
+        let mut waiting_decision = false;
+
+        loop {
+            match messages.recv_timeout(POLL_DELAY) {
+                Ok(message) => {
+                    waiting_confirmation |= matches!(message, ChildMessage::StartupSync(_, _));
+                    waiting_decision |= matches!(message, ChildMessage::Error(_));
+                    self.relay(message);
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => thread::sleep(POLL_DELAY),
+            }
+
+            // Confirmation and decision given by user through manager interfaces
+            if waiting_confirmation {
+                if let Some(Ok(confirm)) = self
+                    .remote
+                    .sync_channels()
+                    .map(|channels| channels.confirm_sync_receiver().try_recv())
+                {
+                    waiting_confirmation = false;
+                    self.send(&mut stdin, ParentMessage::ConfirmStartupSync(confirm));
+                }
+            }
+            if waiting_decision {
+                if let Some(Ok(decision)) = self
+                    .remote
+                    .error_channels()
+                    .map(|channels| channels.decision_receiver().try_recv())
+                {
+                    waiting_decision = false;
+                    // Child process pauses itself on this decision
+                    if let Decision::PauseSpaceSync = decision {
+                        self.remote.pause_signal().store(true, Ordering::Relaxed);
+                        paused = true;
+                    }
+                    self.send(&mut stdin, ParentMessage::Decision(decision));
+                }
+            }
+
+            // Signals set by daemon
+            let pause = self.remote.pause_signal().load(Ordering::Relaxed);
+            if pause != paused {
+                paused = pause;
+                let message = match pause {
+                    true => ParentMessage::Pause,
+                    false => ParentMessage::Resume,
+                };
+                self.send(&mut stdin, message);
+            }
+            if self.remote.resync_signal().swap(false, Ordering::Relaxed) {
+                self.send(&mut stdin, ParentMessage::Resync);
+            }
+            if self.remote.stop_signal().load(Ordering::Relaxed) {
+                match stop_requested_at {
+                    None => {
+                        self.send(&mut stdin, ParentMessage::Stop);
+                        stop_requested_at = Some(Instant::now());
+                    }
+                    Some(requested_at) if requested_at.elapsed() >= STOP_TIMEOUT => {
+                        log::warn!(
+                            "Process of '{}' is not stopped after {}s, kill it",
+                            self.job_identifier,
+                            STOP_TIMEOUT.as_secs()
+                        );
+                        if let Err(error) = child.kill() {
+                            log::error!("Unable to kill process : {}", error);
+                        }
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            if let Some(status) = child.try_wait()? {
+                return Ok(status);
+            }
+        }
+    }
+
+    /// Give child process message to manager channels, as an in-process runner would do
+    fn relay(&self, message: ChildMessage) {
+        match message {
+            ChildMessage::Activity(activity) => self.set_activity(activity),
+            ChildMessage::Status(update) => {
+                if let Some(activity_sender) = self.remote.activity_sender() {
+                    if let Err(error) = activity_sender
+                        .send(WrappedActivity::status(self.job_identifier.clone(), update))
+                    {
+                        log::error!("Unable to send status : {}", error);
+                    }
+                }
+            }
+            ChildMessage::StartupSync(remote_changes, local_changes) => {
+                // TODO : no unwrap
+                if let Some(sync_channels) = self.remote.sync_channels() {
+                    *sync_channels.changes().lock().unwrap() =
+                        Some((remote_changes, local_changes));
+                }
+                if self.remote.popup_confirm_startup_sync() {
+                    self.notify_user(UserRequest::OpenMonitorWindow(
+                        MonitorWindowPanel::StartupConfirmations,
+                    ));
+                }
+            }
+            ChildMessage::Error(ChildError {
+                message,
+                class,
+                content_id,
+                subject,
+            }) => {
+                if let Some(error_channels) = self.remote.error_channels() {
+                    *error_channels.subject().lock().unwrap() = subject;
+                    *error_channels.error().lock().unwrap() = Some(RunnerError::Isolated {
+                        message,
+                        class,
+                        content_id,
+                    });
+                }
+            }
+            ChildMessage::AccessLost => self.notify_user(UserRequest::WorkspaceAccessLost(
+                self.job_identifier.instance_name.clone(),
+                WorkspaceId(self.job_identifier.workspace_id),
+            )),
+        }
+    }
+
+    fn notify_user(&self, request: UserRequest) {
+        if let Some(user_request_sender) = self.remote.user_request_sender() {
+            if user_request_sender.send(request).is_err() {
+                log::debug!("No user to notify for '{}'", self.job_identifier);
+            }
+        }
+    }
+
+    /// Read child process messages in a dedicated thread
+    fn listen(&self, child: &mut Child) -> Result<Receiver<ChildMessage>> {
+        let stdout = child.stdout.take().context("Get child process stdout")?;
+        let job_identifier = self.job_identifier.clone();
+        let (sender, receiver) = unbounded();
+
+        thread::Builder::new()
+            .name("child_listener".to_string())
+            .spawn(move || {
+                let mut reader = BufReader::new(stdout);
+                loop {
+                    match read_message::<_, ChildMessage>(&mut reader) {
+                        Ok(Some(message)) => {
+                            if sender.send(message).is_err() {
+                                break;
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(error) => {
+                            log::error!("Invalid message from '{}' : {}", job_identifier, error)
+                        }
+                    }
+                }
+            })
+            .context("Start child listener")?;
+
+        Ok(receiver)
+    }
+
+    fn send(&self, stdin: &mut ChildStdin, message: ParentMessage) {
+        if let Err(error) = write_message(stdin, &message) {
+            log::error!(
+                "Unable to send {:?} to process of '{}' : {}",
+                message,
+                self.job_identifier,
+                error
+            );
+        }
+    }
+
+    fn set_activity(&self, activity: Activity) {
+        if let Some(activity_sender) = self.remote.activity_sender() {
+            if let Err(error) =
+                activity_sender.send(WrappedActivity::new(self.job_identifier.clone(), activity))
+            {
+                log::error!("Unable to send activity : {}", error);
+            }
+        }
+    }
+
+    /// Forget confirmation or decision waited by an exited child process
+    fn forget_pending(&self) {
+        // TODO : no unwrap
+        if let Some(sync_channels) = self.remote.sync_channels() {
+            *sync_channels.changes().lock().unwrap() = None;
+            while sync_channels.confirm_sync_receiver().try_recv().is_ok() {}
+        }
+        if let Some(error_channels) = self.remote.error_channels() {
+            *error_channels.error().lock().unwrap() = None;
+            *error_channels.subject().lock().unwrap() = None;
+            while error_channels.decision_receiver().try_recv().is_ok() {}
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod test {
+    use std::{
+        fs,
+        sync::{atomic::AtomicBool, Arc},
+    };
+
+    use testdir::testdir;
+    use trsync_core::{activity::JobUpdate, control::RemoteControlBuilder};
+
+    use super::*;
+
+    const WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Supervisor of a shell script counting its starts in a "starts" file
+    fn supervisor(
+        folder: &Path,
+        script: &str,
+        stop_signal: Arc<AtomicBool>,
+    ) -> (ChildSupervisor, Receiver<WrappedActivity>) {
+        // Test folder is shared by lib and bin tests
+        fs::remove_file(folder.join("starts")).unwrap_or(());
+        let (activity_sender, activity_receiver) = unbounded();
+        let remote = RemoteControlBuilder::default()
+            .stop_signal(stop_signal)
+            .activity_sender(Some(activity_sender))
+            .build();
+        let supervisor = ChildSupervisor::new(
+            PathBuf::from("/bin/sh"),
+            vec![
+                "-c".to_string(),
+                format!("echo start >> {}/starts; {}", folder.display(), script),
+            ],
+            "password".to_string(),
+            JobIdentifier::new("a".to_string(), 1, "W".to_string(), "/a".to_string()),
+            remote,
+        )
+        .backoff(Backoff::with_delays(
+            Duration::from_millis(50),
+            Duration::from_millis(100),
+        ));
+        (supervisor, activity_receiver)
+    }
+
+    fn starts(folder: &Path) -> usize {
+        fs::read_to_string(folder.join("starts"))
+            .unwrap_or_default()
+            .lines()
+            .count()
+    }
+
+    fn wait_until(condition: impl Fn() -> bool) {
+        let start = Instant::now();
+        while !condition() {
+            assert!(start.elapsed() < WAIT_TIMEOUT, "Condition not reached");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_restart_crashed_process() {
+        // Given
+        let folder = testdir!();
+        let stop_signal = Arc::new(AtomicBool::new(false));
+        let (supervisor, activity_receiver) = supervisor(&folder, "exit 1", stop_signal.clone());
+
+        // When
+        let handle = thread::spawn(move || supervisor.run());
+        wait_until(|| starts(&folder) >= 3);
+        stop_signal.store(true, Ordering::Relaxed);
+
+        // Then
+        assert!(handle.join().unwrap().is_ok());
+        assert!(activity_receiver
+            .try_iter()
+            .any(|activity| matches!(activity.update(), JobUpdate::Activity(Activity::Error))));
+    }
+
+    #[test]
+    fn test_clean_shutdown() {
+        // Given
+        let folder = testdir!();
+        let stop_signal = Arc::new(AtomicBool::new(false));
+        // Process exits successfully on first parent message (stop one)
+        let (supervisor, _activity_receiver) =
+            supervisor(&folder, "read message; exit 0", stop_signal.clone());
+
+        // When
+        let handle = thread::spawn(move || supervisor.run());
+        wait_until(|| starts(&folder) == 1);
+        let stop_requested_at = Instant::now();
+        stop_signal.store(true, Ordering::Relaxed);
+
+        // Then
+        assert!(handle.join().unwrap().is_ok());
+        assert!(stop_requested_at.elapsed() < STOP_TIMEOUT);
+        assert_eq!(starts(&folder), 1);
+    }
+}
@@ -3,8 +3,6 @@ use trsync_core::instance::{Instance, Workspace, WorkspaceId};
 
 use crate::error::{ClientError, Error};
 
-const DEFAULT_CLIENT_TIMEOUT: u64 = 30;
-
 pub struct Client {
     instance: Instance,
     client: reqwest::blocking::Client,
@@ -12,12 +10,13 @@ pub struct Client {
 
 impl Client {
     pub fn new(instance: Instance) -> Result<Self, Error> {
-        Ok(Self {
-            instance,
-            client: reqwest::blocking::Client::builder()
-                .timeout(std::time::Duration::from_secs(DEFAULT_CLIENT_TIMEOUT))
-                .build()?,
-        })
+        let client = instance.transport.client().map_err(|error| {
+            Error::UnexpectedError(format!(
+                "Unable to build http client for instance '{}' : {}",
+                instance.name, error
+            ))
+        })?;
+        Ok(Self { instance, client })
     }
 
     pub fn get_workspace(&self, workspace_id: WorkspaceId) -> Result<Workspace, ClientError> {
@@ -35,6 +34,8 @@ impl Client {
         match status_code {
             200 => Ok(response.json::<Workspace>()?),
             401 => Err(ClientError::Unauthorized),
+            // Tracim answer 400 with "workspace not found" code when user is not member
+            400 | 403 | 404 => Err(ClientError::NotAccessible),
             _ => {
                 let text = response.text()?;
                 Err(ClientError::UnexpectedResponse(format!(
@@ -0,0 +1,332 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use ini::Ini;
+use structopt::StructOpt;
+use trsync_core::{config::ManagerConfig, error::Decision, instance::ContentId};
+#[cfg(unix)]
+use trsync_manager::socket::client::SocketClient;
+use trsync_manager::socket::{socket_path, JobState, Request, Response, Workspace};
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "trsync-ctl", about = "Control a running trsync manager")]
+struct Opt {
+    /// Control socket path (default is the one of manager config)
+    #[structopt(long, parse(from_os_str))]
+    socket: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(StructOpt, Debug)]
+enum Command {
+    /// Display workspaces and their activity
+    Status,
+    /// Suspend synchronization of a workspace
+    Pause { workspace: String },
+    /// Resume synchronization of a workspace
+    Resume { workspace: String },
+    /// Force a full synchronization of a workspace
+    Resync { workspace: String },
+    /// Display workspaces errors waiting for a decision
+    Errors,
+    /// Answer to a workspace error
+    Decide {
+        workspace: String,
+        #[structopt(subcommand)]
+        decision: DecisionCommand,
+    },
+    /// Accept pending startup synchronization of a workspace
+    Confirm { workspace: String },
+    /// Reload manager config
+    Reload,
+}
+
+#[derive(StructOpt, Debug)]
+enum DecisionCommand {
+    /// Restart workspace synchronization
+    Restart,
+    /// Ignore given content and restart workspace synchronization
+    Ignore { content_id: i32 },
+    /// Don't operate the failed event again and restart workspace synchronization
+    Skip,
+    /// Pause workspace synchronization
+    Pause,
+    /// Forget local state and restart workspace synchronization like the first one
+    Reset,
+    /// Remove local copy of given content and restart workspace synchronization
+    Redownload { content_id: i32 },
+    /// Ignore given path (relative to workspace folder) and restart workspace synchronization
+    IgnorePath {
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+    },
+}
+
+impl From<DecisionCommand> for Decision {
+    fn from(value: DecisionCommand) -> Self {
+        match value {
+            DecisionCommand::Restart => Decision::RestartSpaceSync,
+            DecisionCommand::Ignore { content_id } => {
+                Decision::IgnoreAndRestartSpaceSync(ContentId(content_id))
+            }
+            DecisionCommand::Skip => Decision::SkipEventAndRestartSpaceSync,
+            DecisionCommand::Pause => Decision::PauseSpaceSync,
+            DecisionCommand::Reset => Decision::ResetAndRestartSpaceSync,
+            DecisionCommand::Redownload { content_id } => {
+                Decision::RedownloadAndRestartSpaceSync(ContentId(content_id))
+            }
+            DecisionCommand::IgnorePath { path } => Decision::IgnorePathAndRestartSpaceSync(path),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Command {
+    /// Workspace argument of the command, if any
+    fn workspace(&self) -> Option<&str> {
+        match self {
+            Command::Pause { workspace }
+            | Command::Resume { workspace }
+            | Command::Resync { workspace }
+            | Command::Decide { workspace, .. }
+            | Command::Confirm { workspace } => Some(workspace),
+            Command::Status | Command::Errors | Command::Reload => None,
+        }
+    }
+
+    /// Socket request of the command, with its workspace argument found
+    fn into_request(self, workspace: Option<Workspace>) -> Result<Request> {
+        let workspace = || workspace.context("Missing workspace");
+        Ok(match self {
+            Command::Status => Request::ListJobs,
+            Command::Pause { .. } => Request::Pause {
+                workspace: workspace()?,
+            },
+            Command::Resume { .. } => Request::Resume {
+                workspace: workspace()?,
+            },
+            Command::Resync { .. } => Request::Resync {
+                workspace: workspace()?,
+            },
+            Command::Errors => Request::Errors,
+            Command::Decide { decision, .. } => Request::Decide {
+                workspace: workspace()?,
+                decision: decision.into(),
+            },
+            Command::Confirm { .. } => Request::Confirm {
+                workspace: workspace()?,
+            },
+            Command::Reload => Request::Reload,
+        })
+    }
+}
+
+/// Socket path from manager config file, without reading passwords
+fn configured_socket_path() -> Result<PathBuf> {
+    let control_socket = Ini::load_from_file(ManagerConfig::path()?)
+        .ok()
+        .and_then(|config| {
+            config
+                .get_from(Some("server"), "control_socket")
+                .map(|v| v.to_string())
+        });
+    Ok(socket_path(control_socket.as_deref())?)
+}
+
+#[cfg(unix)]
+fn request(client: &mut SocketClient, request: Request) -> Result<Response> {
+    match client.request(&request)? {
+        Response::Error(message) => bail!(message),
+        response => Ok(response),
+    }
+}
+
+#[cfg(unix)]
+fn jobs(client: &mut SocketClient) -> Result<Vec<JobState>> {
+    match request(client, Request::ListJobs)? {
+        Response::Jobs(jobs) => Ok(jobs),
+        response => bail!("Unexpected response : {:?}", response),
+    }
+}
+
+/// Find workspace from "<instance>::<workspace>" or "<workspace>" where workspace is
+/// its name or id
+#[cfg(unix)]
+fn workspace(client: &mut SocketClient, raw: &str) -> Result<Workspace> {
+    let (instance_name, workspace) = match raw.split_once("::") {
+        Some((instance_name, workspace)) => (Some(instance_name), workspace),
+        None => (None, raw),
+    };
+
+    let matches: Vec<Workspace> = jobs(client)?
+        .into_iter()
+        .map(|job| job.job)
+        .filter(|job| instance_name.is_none_or(|name| name == job.instance_name))
+        .filter(|job| job.workspace_name == workspace || job.workspace_id.to_string() == workspace)
+        .map(|job| Workspace::new(job.instance_name, job.workspace_id))
+        .collect();
+
+    match matches.as_slice() {
+        [workspace] => Ok(workspace.clone()),
+        [] => bail!("No workspace matching '{}'", raw),
+        _ => bail!(
+            "Several workspaces match '{}', use '<instance>::<workspace>' form",
+            raw
+        ),
+    }
+}
+
+#[cfg(unix)]
+fn main_() -> Result<()> {
+    let opt = Opt::from_args();
+    let socket_path = match opt.socket {
+        Some(socket) => socket,
+        None => configured_socket_path()?,
+    };
+    let mut client = SocketClient::connect(&socket_path)?;
+
+    let workspace = match opt.command.workspace() {
+        Some(raw) => Some(self::workspace(&mut client, raw)?),
+        None => None,
+    };
+    match request(&mut client, opt.command.into_request(workspace)?)? {
+        Response::Jobs(jobs) => {
+            for job in jobs {
+                println!(
+                    "{} ({}) : {} ({})",
+                    job.job, job.job.workspace_id, job.activity, job.status
+                );
+            }
+        }
+        Response::Errors(errors) => {
+            for error in errors {
+                println!("{} ({}) : {}", error.job, error.class, error.message);
+                if let Some(subject) = &error.subject {
+                    if let Some(event) = &subject.event {
+                        println!(
+                            "  event : {} (can be skipped with 'decide {} skip')",
+                            event, error.job
+                        );
+                    }
+                    if let Some(path) = &subject.path {
+                        println!(
+                            "  path : {} (can be ignored with 'decide {} ignore-path {}')",
+                            path.display(),
+                            error.job,
+                            path.display()
+                        );
+                    }
+                }
+                if let Some(content_id) = error.content_id {
+                    println!(
+                        "  (can be ignored with 'decide {} ignore {}')",
+                        error.job, content_id
+                    );
+                }
+            }
+        }
+        Response::Done | Response::Error(_) => {}
+    }
+
+    Ok(())
+}
+
+fn main() {
+    #[cfg(unix)]
+    if let Err(error) = main_() {
+        eprintln!("{:#}", error);
+        std::process::exit(1);
+    }
+
+    #[cfg(not(unix))]
+    {
+        eprintln!("Control socket is only available on unix");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod test {
+    use std::path::Path;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Opt, structopt::clap::Error> {
+        Opt::from_iter_safe([&["trsync-ctl"], args].concat())
+    }
+
+    fn request_of(args: &[&str]) -> Request {
+        let command = parse(args).unwrap().command;
+        let workspace = command
+            .workspace()
+            .map(|raw| Workspace::new(raw.to_string(), 4));
+        command.into_request(workspace).unwrap()
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        let opt = parse(&["--socket", "/tmp/trsync.sock", "status"]).unwrap();
+        assert_eq!(opt.socket, Some(PathBuf::from("/tmp/trsync.sock")));
+        assert!(matches!(opt.command, Command::Status));
+
+        let opt = parse(&["pause", "tracim::4"]).unwrap();
+        assert_eq!(opt.socket, None);
+        assert_eq!(opt.command.workspace(), Some("tracim::4"));
+
+        let opt = parse(&["decide", "W", "ignore-path", "a/b.txt"]).unwrap();
+        assert!(matches!(
+            opt.command,
+            Command::Decide {
+                decision: DecisionCommand::IgnorePath { path },
+                ..
+            } if path == Path::new("a/b.txt")
+        ));
+
+        assert!(parse(&["pause"]).is_err());
+        assert!(parse(&["decide", "W", "ignore", "not_an_id"]).is_err());
+        assert!(parse(&["unknown"]).is_err());
+    }
+
+    #[test]
+    fn test_command_requests() {
+        let workspace = Workspace::new("W".to_string(), 4);
+
+        assert!(matches!(request_of(&["status"]), Request::ListJobs));
+        assert!(matches!(request_of(&["errors"]), Request::Errors));
+        assert!(matches!(request_of(&["reload"]), Request::Reload));
+        assert!(
+            matches!(request_of(&["pause", "W"]), Request::Pause { workspace: w } if w == workspace)
+        );
+        assert!(
+            matches!(request_of(&["resume", "W"]), Request::Resume { workspace: w } if w == workspace)
+        );
+        assert!(
+            matches!(request_of(&["resync", "W"]), Request::Resync { workspace: w } if w == workspace)
+        );
+        assert!(
+            matches!(request_of(&["confirm", "W"]), Request::Confirm { workspace: w } if w == workspace)
+        );
+        assert!(matches!(
+            request_of(&["decide", "W", "ignore", "42"]),
+            Request::Decide {
+                workspace: w,
+                decision: Decision::IgnoreAndRestartSpaceSync(ContentId(42)),
+            } if w == workspace
+        ));
+        assert!(matches!(
+            request_of(&["decide", "W", "skip"]),
+            Request::Decide {
+                decision: Decision::SkipEventAndRestartSpaceSync,
+                ..
+            }
+        ));
+        assert!(Command::Confirm {
+            workspace: "W".to_string()
+        }
+        .into_request(None)
+        .is_err());
+    }
+}
@@ -1,73 +1,208 @@
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, RecvError, RecvTimeoutError, Sender};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use std::{collections::HashMap, path::Path};
+use std::time::{Duration, Instant};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
 use std::{fs, thread};
-use trsync_core::activity::WrappedActivity;
+use trsync_core::activity::{Activity, WrappedActivity};
+use trsync_core::client::Client as TracimClient;
 use trsync_core::control::RemoteControlBuilder;
 use trsync_core::error::ErrorExchanger;
+use trsync_core::instance::{Instance, InstanceId, WorkspaceId};
 use trsync_core::job::JobIdentifier;
 use trsync_core::sync::SyncExchanger;
+use trsync_core::transfer::{RateLimiter, TransferControl, TransferScheduler};
 use trsync_core::user::UserRequest;
 
-use trsync_core::config::ManagerConfig;
+use trsync::database::mark_stale;
+use trsync_core::config::{ManagerConfig, RemovedWorkspacePolicy};
 
-use crate::{client::Client, error::Error, message::DaemonMessage, types::*};
+use crate::{
+    child::{child_args, default_trsync_bin, ChildSupervisor},
+    client::Client,
+    error::{ClientError, Error},
+    folder::{archive_folder, FolderRegistry},
+    membership::MembershipListener,
+    message::DaemonMessage,
+    types::*,
+};
+
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+const CLEAN_RETRY_DELAY: Duration = Duration::from_secs(1);
 
 pub struct Daemon {
     config: ManagerConfig,
-    processes: HashMap<TrsyncUid, Arc<AtomicBool>>,
+    processes: HashMap<TrsyncUid, Process>,
     main_receiver: Receiver<DaemonMessage>,
+    main_sender: Sender<DaemonMessage>,
+    /// Stop signals of membership listeners, for instances synchronizing all workspaces
+    membership_listeners: HashMap<InstanceId, Arc<AtomicBool>>,
+    /// Stopped processes of workspaces removed from config, waiting for removed workspace
+    /// policy. Their folders are cleaned only once the process is finished.
+    removed_workspaces: HashMap<TrsyncUid, Process>,
+    /// Removed workspaces to clean when their processes will be finished, and next attempt
+    pending_cleans: Option<(Instant, Vec<TrsyncUid>)>,
+    /// Retry schedule of instances which were unavailable
+    instances_backoffs: HashMap<InstanceId, Backoff>,
+    /// Workspaces which access lost was already notified to user (until next config reload)
+    access_lost_notified: HashSet<TrsyncUid>,
     activity_sender: Sender<WrappedActivity>,
     user_request_sender: Sender<UserRequest>,
     sync_exchanger: Arc<Mutex<SyncExchanger>>,
     error_exchanger: Arc<Mutex<ErrorExchanger>>,
+    transfer_scheduler: Arc<TransferScheduler>,
+    upload_limiter: Arc<RateLimiter>,
+    download_limiter: Arc<RateLimiter>,
+    instances_limiters: HashMap<InstanceId, (Arc<RateLimiter>, Arc<RateLimiter>)>,
 }
 
 impl Daemon {
     pub fn new(
         config: ManagerConfig,
         main_receiver: Receiver<DaemonMessage>,
+        main_sender: Sender<DaemonMessage>,
         activity_sender: Sender<WrappedActivity>,
         user_request_sender: Sender<UserRequest>,
         sync_exchanger: Arc<Mutex<SyncExchanger>>,
         error_exchanger: Arc<Mutex<ErrorExchanger>>,
     ) -> Self {
-        Self {
+        let transfer_scheduler = Arc::new(TransferScheduler::new(config.max_parallel_transfers));
+        let upload_limiter = Arc::new(RateLimiter::new(config.bandwidth.max_upload_rate));
+        let download_limiter = Arc::new(RateLimiter::new(config.bandwidth.max_download_rate));
+        let mut self_ = Self {
             config,
             processes: HashMap::new(),
             main_receiver,
+            main_sender,
+            membership_listeners: HashMap::new(),
+            removed_workspaces: HashMap::new(),
+            pending_cleans: None,
+            instances_backoffs: HashMap::new(),
+            access_lost_notified: HashSet::new(),
             activity_sender,
             user_request_sender,
             sync_exchanger,
             error_exchanger,
+            transfer_scheduler,
+            upload_limiter,
+            download_limiter,
+            instances_limiters: HashMap::new(),
+        };
+        self_.apply_transfer_config();
+        self_
+    }
+
+    /// Update limits shared by running processes according to current config
+    fn apply_transfer_config(&mut self) {
+        self.transfer_scheduler
+            .set_slots(self.config.max_parallel_transfers);
+        self.upload_limiter
+            .set_rate(self.config.bandwidth.max_upload_rate);
+        self.download_limiter
+            .set_rate(self.config.bandwidth.max_download_rate);
+
+        for instance in &self.config.instances {
+            let (upload_limiter, download_limiter) = self
+                .instances_limiters
+                .entry(instance.name.clone())
+                .or_insert_with(|| {
+                    (
+                        Arc::new(RateLimiter::new(None)),
+                        Arc::new(RateLimiter::new(None)),
+                    )
+                });
+            upload_limiter.set_rate(instance.bandwidth.max_upload_rate);
+            download_limiter.set_rate(instance.bandwidth.max_download_rate);
         }
     }
 
+    fn transfer_control(&self, instance_id: &InstanceId, priority: i32) -> TransferControl {
+        let mut transfer = TransferControl::default()
+            .upload_limiter(self.upload_limiter.clone())
+            .download_limiter(self.download_limiter.clone())
+            .scheduler(self.transfer_scheduler.clone(), priority);
+        if let Some((upload_limiter, download_limiter)) = self.instances_limiters.get(instance_id) {
+            transfer = transfer
+                .upload_limiter(upload_limiter.clone())
+                .download_limiter(download_limiter.clone());
+        }
+        transfer
+    }
+
     pub fn run(&mut self) -> Result<(), Error> {
-        // TODO : this is a too much generic reason to restart every 30s !
-        while let Err(error) = self.ensure_processes() {
-            // TODO : do not log in error when error is UnavailableNetwork
-            log::error!("Startup error : '{}', retry in 30s", error);
-            std::thread::sleep(Duration::from_secs(30))
+        self.start_membership_listeners();
+        if let Err(error) = self.ensure_processes() {
+            log::error!("Startup error : '{}'", error);
         }
 
         loop {
-            // Block until new message received
-            match self.main_receiver.recv() {
+            // Block until new message received, or until next unavailable instance retry or
+            // removed workspaces clean attempt
+            let next_clean = self.pending_cleans.as_ref().map(|(deadline, _)| *deadline);
+            let deadline = match (self.next_retry(), next_clean) {
+                (Some(retry), Some(clean)) => Some(retry.min(clean)),
+                (retry, clean) => retry.or(clean),
+            };
+            let message = match deadline {
+                Some(deadline) => match self.main_receiver.recv_deadline(deadline) {
+                    Err(RecvTimeoutError::Timeout) => {
+                        if next_clean.is_some_and(|clean| clean <= Instant::now()) {
+                            if let Some((_, trsync_uids)) = self.pending_cleans.take() {
+                                self.clean_removed_workspaces(trsync_uids);
+                            }
+                        }
+                        let due_instances = self.due_instances();
+                        if !due_instances.is_empty() {
+                            log::info!("Retry to start processes of unavailable instances");
+                            if let Err(error) =
+                                self.ensure_instances_processes(Some(&due_instances))
+                            {
+                                log::error!("Unable to update processes : '{}'", error);
+                            }
+                        }
+                        continue;
+                    }
+                    message => message.map_err(|_| RecvError),
+                },
+                None => self.main_receiver.recv(),
+            };
+
+            match message {
                 Ok(DaemonMessage::Reload(new_config)) => {
                     self.config = new_config;
-                    while let Err(error) = self.ensure_processes() {
-                        if !self.main_receiver.is_empty() {
-                            log::error!("Startup error : '{}', main message received, skip", error);
-                            continue;
-                        }
-                        log::error!("Startup error : '{}', retry in 30s", error);
-                        std::thread::sleep(Duration::from_secs(30))
+                    self.forget_finished_processes();
+                    // Config changed, retry unavailable instances now
+                    self.instances_backoffs.clear();
+                    self.access_lost_notified.clear();
+                    self.apply_transfer_config();
+                    self.stop_membership_listeners();
+                    self.start_membership_listeners();
+                    if let Err(error) = self.ensure_processes() {
+                        log::error!("Unable to update processes : '{}'", error);
+                    }
+                }
+                Ok(DaemonMessage::Pause(job_identifier)) => self.set_paused(&job_identifier, true),
+                Ok(DaemonMessage::Resume(job_identifier)) => {
+                    self.set_paused(&job_identifier, false)
+                }
+                Ok(DaemonMessage::Resync(job_identifier)) => self.resync(&job_identifier),
+                Ok(DaemonMessage::MembershipChanged(instance_id)) => {
+                    log::info!("Workspaces of '{}' changed", instance_id);
+                    if let Err(error) = self.ensure_instances_processes(Some(&[instance_id])) {
+                        log::error!("Unable to update processes : '{}'", error);
                     }
                 }
-                Ok(DaemonMessage::Stop) => break,
+                Ok(DaemonMessage::CleanRemovedWorkspaces(trsync_uids)) => {
+                    self.clean_removed_workspaces(trsync_uids)
+                }
+                Ok(DaemonMessage::Stop) => {
+                    self.stop_membership_listeners();
+                    self.stop_processes();
+                    break;
+                }
                 Err(error) => return Err(Error::from(error)),
             }
         }
@@ -75,6 +210,133 @@ impl Daemon {
         Ok(())
     }
 
+    /// Listen memberships of instances synchronizing all workspaces, to start or stop
+    /// processes when user joins or leaves a workspace
+    fn start_membership_listeners(&mut self) {
+        for instance in self.config.instances.iter().filter(|i| i.all_workspaces) {
+            let stop_signal = Arc::new(AtomicBool::new(false));
+            MembershipListener::new(
+                instance.clone(),
+                self.main_sender.clone(),
+                stop_signal.clone(),
+            )
+            .start();
+            self.membership_listeners
+                .insert(instance.name.clone(), stop_signal);
+        }
+    }
+
+    fn stop_membership_listeners(&mut self) {
+        for (instance_id, stop_signal) in self.membership_listeners.drain() {
+            log::info!("Signal '{}' membership listener to stop", instance_id);
+            stop_signal.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Signal all processes to stop, then wait (a limited time) for them to finish
+    fn stop_processes(&mut self) {
+        for (trsync_uid, process) in &self.processes {
+            log::info!("Signal '{}' to stop", trsync_uid);
+            process.stop_signal().store(true, Ordering::Relaxed);
+        }
+
+        let deadline = Instant::now() + STOP_TIMEOUT;
+        while self
+            .processes
+            .values()
+            .any(|process| !process.is_finished())
+        {
+            if Instant::now() > deadline {
+                for (trsync_uid, _) in self
+                    .processes
+                    .iter()
+                    .filter(|(_, process)| !process.is_finished())
+                {
+                    log::error!("Process '{}' did not stop in time", trsync_uid);
+                }
+                break;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        self.processes.clear();
+    }
+
+    /// Forget processes which ended by themselves (ex. workspace access lost), so they are
+    /// started again
+    fn forget_finished_processes(&mut self) {
+        self.processes.retain(|trsync_uid, process| {
+            if process.is_finished() {
+                log::info!("Process '{}' is finished, forget it", trsync_uid);
+            }
+            !process.is_finished()
+        });
+    }
+
+    fn notify_user(&self, request: UserRequest) {
+        if self.user_request_sender.send(request).is_err() {
+            log::debug!("No user to notify")
+        }
+    }
+
+    /// Display workspace access as lost and notify user, once until next config reload
+    fn workspace_access_lost(&mut self, trsync_uid: TrsyncUid) {
+        if !self.access_lost_notified.insert(trsync_uid.clone()) {
+            return;
+        }
+
+        // Workspace name is unknown if its process never started
+        let job_identifier = match self.processes.get(&trsync_uid) {
+            Some(process) => process.job_identifier().clone(),
+            None => JobIdentifier::new(
+                trsync_uid.instance_id().to_string(),
+                trsync_uid.workspace_id().0,
+                trsync_uid.workspace_id().to_string(),
+                String::new(),
+            ),
+        };
+        if self
+            .activity_sender
+            .send(WrappedActivity::new(job_identifier, Activity::AccessLost))
+            .is_err()
+        {
+            log::error!("Unable to send access lost activity of {}", trsync_uid);
+        }
+        self.notify_user(UserRequest::WorkspaceAccessLost(
+            trsync_uid.instance_id().to_string(),
+            *trsync_uid.workspace_id(),
+        ));
+    }
+
+    fn process(&self, job_identifier: &JobIdentifier) -> Option<&Process> {
+        let process = self
+            .processes
+            .values()
+            .find(|process| process.job_identifier() == job_identifier);
+        if process.is_none() {
+            log::error!("No running process for '{}'", job_identifier)
+        }
+        process
+    }
+
+    fn set_paused(&self, job_identifier: &JobIdentifier, value: bool) {
+        if let Some(process) = self.process(job_identifier) {
+            log::info!(
+                "Signal '{}' to {}",
+                job_identifier,
+                if value { "pause" } else { "resume" }
+            );
+            process.pause_signal().store(value, Ordering::Relaxed);
+        }
+    }
+
+    fn resync(&self, job_identifier: &JobIdentifier) {
+        if let Some(process) = self.process(job_identifier) {
+            log::info!("Signal '{}' to resync", job_identifier);
+            process.resync_signal().store(true, Ordering::Relaxed);
+        }
+    }
+
     pub fn start(mut self) -> Result<(), Error> {
         std::thread::spawn(move || {
             if let Err(error) = self.run() {
@@ -85,70 +347,332 @@ impl Daemon {
     }
 
     pub fn ensure_processes(&mut self) -> Result<(), Error> {
-        let processes_to_start = self.get_processes_to_start()?;
-        let processes_to_stop = self.get_processes_to_stop();
+        self.ensure_instances_processes(None)
+    }
 
-        log::info!("'{}' process to start", processes_to_start.len());
+    /// Start and stop processes of given instances (all if None), other instances are not
+    /// queried and keep their processes
+    fn ensure_instances_processes(
+        &mut self,
+        instances_ids: Option<&[InstanceId]>,
+    ) -> Result<(), Error> {
+        let expected_processes = self.get_expected_processes(instances_ids);
+        let processes_to_stop = self.get_processes_to_stop(&expected_processes);
         log::info!("'{}' process to stop", processes_to_stop.len());
-
         for process_to_stop in processes_to_stop {
-            self.stop_process(process_to_stop)?;
+            let process = self.stop_process(process_to_stop.clone())?;
+            // Not a restart, and access lost stays displayed
+            if !expected_processes.contains(&process_to_stop)
+                && !self.access_lost_notified.contains(&process_to_stop)
+            {
+                process.forget_signal().store(true, Ordering::Relaxed);
+            }
+            if self.is_removed_from_config(&process_to_stop) {
+                self.workspace_removed(process_to_stop, process);
+            }
         }
 
+        // Computed after stops to restart processes stopped because of folder change
+        let processes_to_start = self.get_processes_to_start(&expected_processes);
+        log::info!("'{}' process to start", processes_to_start.len());
+
         for process_to_start in processes_to_start {
-            if let Err(error) = self.start_process(process_to_start) {
-                log::error!("Failed to spawn new process : '{:?}'", error)
+            let instance_id = process_to_start.instance_id().clone();
+            // Instance failed previously in this round, or is waiting its next retry
+            if !self.is_instance_due(&instance_id) {
+                continue;
+            }
+            match self.start_process(process_to_start) {
+                Ok(_) => {}
+                Err(error @ Error::UnavailableNetwork(_)) => {
+                    self.instance_unavailable(&instance_id, &error)
+                }
+                Err(error) => log::error!("Failed to spawn new process : '{:?}'", error),
             };
         }
 
+        // Concerned instances which were due and did not fail again are available
+        for (instance_id, backoff) in self.instances_backoffs.iter_mut() {
+            let is_concerned = instances_ids
+                .map(|instances_ids| instances_ids.contains(instance_id))
+                .unwrap_or(true);
+            if is_concerned && backoff.is_due() {
+                backoff.succeeded();
+            }
+        }
+        self.instances_backoffs
+            .retain(|_, backoff| backoff.is_failing());
+
         Ok(())
     }
 
-    fn get_processes_to_start(&self) -> Result<Vec<TrsyncUid>, Error> {
-        let mut processes_to_start = vec![];
+    fn is_instance_due(&self, instance_id: &InstanceId) -> bool {
+        self.instances_backoffs
+            .get(instance_id)
+            .map(|backoff| backoff.is_due())
+            .unwrap_or(true)
+    }
+
+    /// Delay next start attempts of this instance, independently of other instances
+    fn instance_unavailable(&mut self, instance_id: &InstanceId, error: &Error) {
+        let delay = self
+            .instances_backoffs
+            .entry(instance_id.clone())
+            .or_default()
+            .failed();
+        log::warn!(
+            "Instance '{}' is unavailable ({}), retry in {}s",
+            instance_id,
+            error,
+            delay.as_secs()
+        );
+    }
+
+    /// Unavailable instances which must be retried now
+    fn due_instances(&self) -> Vec<InstanceId> {
+        self.instances_backoffs
+            .iter()
+            .filter(|(_, backoff)| backoff.is_due())
+            .map(|(instance_id, _)| instance_id.clone())
+            .collect()
+    }
+
+    /// Earliest retry of unavailable instances, if any
+    fn next_retry(&self) -> Option<Instant> {
+        self.instances_backoffs
+            .values()
+            .map(|backoff| backoff.next_attempt())
+            .min()
+    }
+
+    /// Processes which must run according to config and workspaces memberships. When
+    /// workspaces of an instance can't be determined, its running processes are kept and
+    /// it is retried later. Instances out of given ones (all if None) keep their processes.
+    fn get_expected_processes(&mut self, instances_ids: Option<&[InstanceId]>) -> Vec<TrsyncUid> {
+        let mut expected_processes = vec![];
+        let mut unavailable_instances = vec![];
+        let mut lost_workspaces = vec![];
 
         for instance in self.config.instances.iter() {
-            let client = Client::new(instance.clone())?;
-            for workspace_id in &instance.workspaces_ids {
-                match client.get_workspace(*workspace_id) {
-                    Ok(workspace) => {
-                        let process_uid =
-                            TrsyncUid::new(instance.address.clone(), workspace.workspace_id);
-                        if !self.processes.contains_key(&process_uid) {
-                            processes_to_start.push(process_uid);
-                        }
-                    }
-                    Err(error) => {
-                        return Err(Error::UnavailableNetwork(format!(
-                            "Unable to get workspace infos : '{}'",
-                            error
-                        )))
+            let is_concerned = instances_ids
+                .map(|instances_ids| instances_ids.contains(&instance.name))
+                .unwrap_or(true);
+            if !is_concerned || !self.is_instance_due(&instance.name) {
+                expected_processes.extend(self.instance_processes(&instance.name));
+                continue;
+            }
+
+            match self.instance_workspaces_ids(instance, &mut lost_workspaces) {
+                Ok(workspaces_ids) => {
+                    for workspace_id in workspaces_ids {
+                        expected_processes
+                            .push(TrsyncUid::new(instance.name.clone(), workspace_id));
                     }
                 }
+                Err(error) => {
+                    expected_processes.extend(self.instance_processes(&instance.name));
+                    unavailable_instances.push((instance.name.clone(), error));
+                }
             }
         }
 
-        Ok(processes_to_start)
+        for (instance_id, error) in unavailable_instances {
+            self.instance_unavailable(&instance_id, &error);
+        }
+        for trsync_uid in lost_workspaces {
+            self.workspace_access_lost(trsync_uid);
+        }
+
+        expected_processes
     }
 
-    fn get_processes_to_stop(&self) -> Vec<TrsyncUid> {
-        let mut processes_to_stop: Vec<TrsyncUid> = vec![];
-        let mut expected_processes: Vec<TrsyncUid> = vec![];
+    fn instance_processes(&self, instance_id: &InstanceId) -> Vec<TrsyncUid> {
+        self.processes
+            .keys()
+            .filter(|uid| uid.instance_id() == instance_id)
+            .cloned()
+            .collect()
+    }
 
-        for instance in self.config.instances.iter() {
-            for workspace_id in &instance.workspaces_ids {
-                let process_uid = TrsyncUid::new(instance.address.clone(), *workspace_id);
-                expected_processes.push(process_uid);
+    /// Workspaces to synchronize : listed ones, or all user workspaces except excluded ones.
+    /// Listed workspaces which are not accessible anymore are pushed in `lost_workspaces`.
+    fn instance_workspaces_ids(
+        &self,
+        instance: &Instance,
+        lost_workspaces: &mut Vec<TrsyncUid>,
+    ) -> Result<Vec<WorkspaceId>, Error> {
+        if instance.all_workspaces {
+            let workspaces = TracimClient::new(
+                instance.url(None),
+                instance.username.clone(),
+                instance.password.clone(),
+                &instance.transport,
+            )
+            .and_then(|client| client.workspaces())
+            .map_err(|error| {
+                Error::UnavailableNetwork(format!(
+                    "Unable to get '{}' workspaces : '{}'",
+                    instance.name, error
+                ))
+            })?;
+            return Ok(workspaces
+                .into_iter()
+                .map(|workspace| workspace.workspace_id)
+                .filter(|workspace_id| instance.is_workspace_selected(workspace_id))
+                .collect());
+        }
+
+        // Inaccessible workspaces are skipped, to not block other ones
+        let client = Client::new(instance.clone())?;
+        let mut workspaces_ids = vec![];
+        for workspace_id in &instance.workspaces_ids {
+            match client.get_workspace(*workspace_id) {
+                Ok(_) => workspaces_ids.push(*workspace_id),
+                Err(ClientError::NotAccessible) => {
+                    log::warn!(
+                        "Workspace {} of '{}' is not accessible (deleted or membership revoked)",
+                        workspace_id,
+                        instance.name
+                    );
+                    lost_workspaces.push(TrsyncUid::new(instance.name.clone(), *workspace_id));
+                }
+                Err(error) => {
+                    return Err(Error::UnavailableNetwork(format!(
+                        "Unable to get workspace infos : '{}'",
+                        error
+                    )))
+                }
+            }
+        }
+        Ok(workspaces_ids)
+    }
+
+    fn get_processes_to_start(&self, expected_processes: &[TrsyncUid]) -> Vec<TrsyncUid> {
+        expected_processes
+            .iter()
+            .filter(|process_uid| !self.processes.contains_key(process_uid))
+            .cloned()
+            .collect()
+    }
+
+    /// Processes not expected anymore, or which workspace folder override changed
+    fn get_processes_to_stop(&self, expected_processes: &[TrsyncUid]) -> Vec<TrsyncUid> {
+        self.processes
+            .iter()
+            .filter(|(process_uid, process)| {
+                !expected_processes.contains(process_uid)
+                    || self.folder_path_override(process_uid) != *process.folder_path_override()
+            })
+            .map(|(process_uid, _)| process_uid.clone())
+            .collect()
+    }
+
+    /// True if workspace is not selected anymore in config (membership loss is not a removal)
+    fn is_removed_from_config(&self, trsync_uid: &TrsyncUid) -> bool {
+        match self
+            .config
+            .instances
+            .iter()
+            .find(|instance| &instance.name == trsync_uid.instance_id())
+        {
+            Some(instance) => !instance.is_workspace_selected(trsync_uid.workspace_id()),
+            None => true,
+        }
+    }
+
+    /// Mark local state of removed workspace as outdated (it is indexed again if workspace
+    /// is added again) and keep its folder for removed workspace policy. Folders chosen by
+    /// user (`folder_path`) are never archived nor deleted.
+    fn workspace_removed(&mut self, trsync_uid: TrsyncUid, process: Process) {
+        log::info!("Workspace {} removed from config", trsync_uid);
+        let folder = process.folder();
+        if let Err(error) = mark_stale(folder) {
+            log::error!(
+                "Unable to mark '{}' as stale : {:#}",
+                folder.display(),
+                error
+            );
+        }
+        if process.folder_path_override().is_some() {
+            log::info!(
+                "Folder '{}' of {} is chosen by user, keep it",
+                folder.display(),
+                trsync_uid
+            );
+            return;
+        }
+        self.removed_workspaces.insert(trsync_uid, process);
+    }
+
+    /// Keep, archive or delete folders of given removed workspaces, according to config.
+    /// Workspaces which process is still running are cleaned later.
+    fn clean_removed_workspaces(&mut self, trsync_uids: Vec<TrsyncUid>) {
+        let policy = self.config.removed_workspace_policy;
+        let local_folder = PathBuf::from(&self.config.local_folder);
+        let mut deferred = vec![];
+
+        for trsync_uid in trsync_uids {
+            // Workspace could have been added again in the meantime
+            if !self.is_removed_from_config(&trsync_uid) {
+                continue;
+            }
+            let folder = match self.removed_workspaces.get(&trsync_uid) {
+                Some(process) if !process.is_finished() => {
+                    log::info!("Process of {} still running, clean it later", trsync_uid);
+                    deferred.push(trsync_uid);
+                    continue;
+                }
+                Some(process) => process.folder().clone(),
+                None => {
+                    log::info!("No removed folder known for {}", trsync_uid);
+                    continue;
+                }
+            };
+            self.removed_workspaces.remove(&trsync_uid);
+
+            let result = match policy {
+                RemovedWorkspacePolicy::Keep => continue,
+                RemovedWorkspacePolicy::Archive => archive_folder(&local_folder, &folder)
+                    .map(|archive| log::info!("Archived '{}'", archive.display())),
+                RemovedWorkspacePolicy::Delete => fs::remove_dir_all(&folder)
+                    .map(|_| log::info!("Deleted '{}'", folder.display()))
+                    .map_err(Error::from),
+            };
+            if let Err(error) = result.and_then(|_| {
+                FolderRegistry::load(&local_folder)?
+                    .forget(trsync_uid.instance_id(), *trsync_uid.workspace_id())
+            }) {
+                log::error!(
+                    "Unable to clean '{}' of {} : '{}'",
+                    folder.display(),
+                    trsync_uid,
+                    error
+                );
             }
         }
 
-        for process_uid in self.processes.keys() {
-            if !expected_processes.contains(process_uid) {
-                processes_to_stop.push(process_uid.clone())
+        if !deferred.is_empty() {
+            let (_, pending) = self
+                .pending_cleans
+                .get_or_insert_with(|| (Instant::now() + CLEAN_RETRY_DELAY, vec![]));
+            for trsync_uid in deferred {
+                if !pending.contains(&trsync_uid) {
+                    pending.push(trsync_uid);
+                }
             }
         }
+    }
 
-        processes_to_stop
+    fn folder_path_override(&self, trsync_uid: &TrsyncUid) -> Option<String> {
+        self.config
+            .instances
+            .iter()
+            .find(|instance| &instance.name == trsync_uid.instance_id())
+            .and_then(|instance| {
+                instance
+                    .workspace_config(trsync_uid.workspace_id())
+                    .folder_path
+            })
     }
 
     fn start_process(&mut self, trsync_uid: TrsyncUid) -> Result<(), Error> {
@@ -157,11 +681,23 @@ impl Daemon {
             .config
             .instances
             .iter()
-            .find(|instance| instance.address == trsync_uid.instance_address())
+            .find(|instance| &instance.name == trsync_uid.instance_id())
             .expect("Start process imply its instance exists");
         let workspace =
             match Client::new(instance.clone())?.get_workspace(*trsync_uid.workspace_id()) {
                 Ok(workspace) => workspace,
+                Err(ClientError::NotAccessible) => {
+                    self.workspace_access_lost(trsync_uid.clone());
+                    return Err(Error::UnexpectedError(format!(
+                        "Workspace {} is not accessible (deleted or membership revoked)",
+                        trsync_uid
+                    )));
+                }
+                Err(error @ ClientError::RequestError(_)) => {
+                    return Err(Error::UnavailableNetwork(format!(
+                        "Error during workspace fetching : '{error}'"
+                    )));
+                }
                 Err(error) => {
                     return Err(Error::UnexpectedError(format!(
                         "Error during workspace fetching : '{error}'"
@@ -169,9 +705,82 @@ impl Daemon {
                 }
             };
 
-        let folder_path = Path::new(&local_folder)
-            .join(&instance.address)
-            .join(workspace.label);
+        let local_folder = Path::new(&local_folder);
+        if let Err(error) = fs::create_dir_all(local_folder) {
+            return Err(Error::UnexpectedError(format!(
+                "Error during folder '{:?}' creation : '{}'",
+                local_folder, error
+            )));
+        };
+        // Canonical, to be comparable with running processes folders
+        let local_folder = match fs::canonicalize(local_folder) {
+            Ok(local_folder_) => local_folder_,
+            Err(error) => {
+                return Err(Error::UnexpectedError(format!(
+                    "Error during folder path '{:?}' canonicalization : '{}'",
+                    local_folder, error
+                )))
+            }
+        };
+        let folder_path_override = instance
+            .workspace_config(&workspace.workspace_id)
+            .folder_path;
+        let relative_path = match &folder_path_override {
+            Some(folder_path) => PathBuf::from(folder_path),
+            None => {
+                let shared_address =
+                    self.config.instances.iter().any(|other| {
+                        other.name != instance.name && other.address == instance.address
+                    });
+                let path = self
+                    .config
+                    .folder_layout
+                    .path(instance, &workspace, shared_address);
+                // Folder of an installation older than folder registry, with unsanitized name
+                match self.config.folder_layout.legacy_path(instance, &workspace) {
+                    Some(legacy_path)
+                        if !shared_address
+                            && legacy_path != path
+                            && local_folder.join(&legacy_path).is_dir()
+                            && !FolderRegistry::load(&local_folder)?
+                                .is_known(&instance.name, workspace.workspace_id) =>
+                    {
+                        log::info!(
+                            "Keep previously used folder '{}' for {}",
+                            legacy_path.display(),
+                            trsync_uid
+                        );
+                        legacy_path
+                    }
+                    _ => path,
+                }
+            }
+        };
+        // Absolute override path replace local folder when joined
+        let folder_path = local_folder.join(&relative_path);
+        let folder_path = fs::canonicalize(&folder_path).unwrap_or(folder_path);
+        if let Some((other_uid, other_process)) = self
+            .processes
+            .iter()
+            .find(|(_, process)| folders_overlap(process.folder(), &folder_path))
+        {
+            return Err(Error::UnexpectedError(format!(
+                "Folder '{}' of {} is already used by {} ('{}')",
+                folder_path.display(),
+                trsync_uid,
+                other_uid,
+                other_process.folder().display()
+            )));
+        }
+        // Overridden folders are chosen by user, they are never moved
+        let folder_path = match &folder_path_override {
+            Some(_) => folder_path,
+            None => FolderRegistry::load(&local_folder)?.workspace_folder(
+                instance,
+                workspace.workspace_id,
+                &relative_path,
+            )?,
+        };
         log::debug!("Prepare process for '{:?}'", &folder_path);
         if let Err(error) = fs::create_dir_all(&folder_path) {
             return Err(Error::UnexpectedError(format!(
@@ -205,6 +814,10 @@ impl Daemon {
             }
         };
 
+        let transfer = self.transfer_control(
+            &instance.name,
+            instance.workspace_config(&workspace.workspace_id).priority,
+        );
         let trsync_context = match trsync::context::Context::new(
             !instance.unsecure,
             instance.address.clone(),
@@ -214,8 +827,15 @@ impl Daemon {
             workspace.workspace_id,
             workspace_name,
             false,
+            instance.transport.clone(),
         ) {
-            Ok(context_) => context_,
+            Ok(context_) => context_
+                .instance_name(instance.name.to_string())
+                .transfer(transfer)
+                .schedule(
+                    self.config
+                        .workspace_schedule(instance, &workspace.workspace_id),
+                ),
             Err(error) => {
                 return Err(Error::UnexpectedError(format!(
                     "Unable to build trsync context : {:?}",
@@ -225,11 +845,7 @@ impl Daemon {
         };
 
         //
-        let job_identifier = JobIdentifier::new(
-            trsync_context.instance_name.clone(),
-            trsync_context.workspace_id.0,
-            trsync_context.workspace_name.clone(),
-        );
+        let job_identifier = trsync_context.job_identifier();
         let sync_channels = self
             .sync_exchanger
             .lock()
@@ -239,11 +855,16 @@ impl Daemon {
             .error_exchanger
             .lock()
             .unwrap() // TODO unwrap ...
-            .insert(job_identifier);
+            .insert(job_identifier.clone());
 
         let stop_signal = Arc::new(AtomicBool::new(false));
+        let pause_signal = Arc::new(AtomicBool::new(false));
+        let resync_signal = Arc::new(AtomicBool::new(false));
+        let forget_signal = Arc::new(AtomicBool::new(false));
         let remote = RemoteControlBuilder::default()
             .stop_signal(stop_signal.clone())
+            .pause_signal(pause_signal.clone())
+            .resync_signal(resync_signal.clone())
             .activity_sender(Some(self.activity_sender.clone()))
             .confirm_startup_sync(self.config.confirm_startup_sync)
             .popup_confirm_startup_sync(self.config.popup_confirm_startup_sync)
@@ -252,21 +873,82 @@ impl Daemon {
             .sync_channels(Some(sync_channels))
             .build();
 
-        thread::spawn(move || trsync::run2::run(trsync_context, remote));
-        self.processes.insert(trsync_uid, stop_signal);
+        let run: Box<dyn FnOnce() -> anyhow::Result<()> + Send> = if self.config.isolated_processes
+        {
+            let bin = self
+                .config
+                .trsync_bin
+                .as_ref()
+                .map(PathBuf::from)
+                .unwrap_or_else(default_trsync_bin);
+            let args = child_args(
+                instance,
+                workspace.workspace_id,
+                folder_path,
+                &self
+                    .config
+                    .workspace_schedule(instance, &workspace.workspace_id),
+                self.config.confirm_startup_sync,
+            );
+            let supervisor = ChildSupervisor::new(
+                bin,
+                args,
+                instance.password.clone(),
+                job_identifier.clone(),
+                remote,
+            );
+            Box::new(move || supervisor.run())
+        } else {
+            Box::new(move || trsync::run2::run(trsync_context, remote))
+        };
+        // Removal is sent from job thread to be received after its last activity
+        let activity_sender = self.activity_sender.clone();
+        let forget_signal_ = forget_signal.clone();
+        let job_identifier_ = job_identifier.clone();
+        let handle = thread::spawn(move || {
+            let result = run();
+            if forget_signal_.load(Ordering::Relaxed)
+                && activity_sender
+                    .send(WrappedActivity::removed(job_identifier_))
+                    .is_err()
+            {
+                log::error!("Unable to send job removal");
+            }
+            result
+        });
+        self.removed_workspaces.remove(&trsync_uid);
+        self.access_lost_notified.remove(&trsync_uid);
+        self.processes.insert(
+            trsync_uid,
+            Process::new(
+                job_identifier,
+                PathBuf::from(folder_path),
+                folder_path_override,
+                stop_signal,
+                pause_signal,
+                resync_signal,
+                forget_signal,
+                handle,
+            ),
+        );
         Ok(())
     }
 
-    fn stop_process(&mut self, trsync_uid: TrsyncUid) -> Result<(), Error> {
-        let stop_signal = self
+    /// Signal process to stop and return it, so caller can wait for its end
+    fn stop_process(&mut self, trsync_uid: TrsyncUid) -> Result<Process, Error> {
+        let process = self
             .processes
-            .get_mut(&trsync_uid)
+            .remove(&trsync_uid)
             .expect("Stop process imply that process exists");
 
         log::info!("Signal '{}' to stop", trsync_uid);
-        stop_signal.swap(true, Ordering::Relaxed);
+        process.stop_signal().swap(true, Ordering::Relaxed);
 
-        self.processes.remove(&trsync_uid);
-        Ok(())
+        Ok(process)
     }
 }
+
+/// True if given folders are the same or one contains the other
+fn folders_overlap(a: &Path, b: &Path) -> bool {
+    a.starts_with(b) || b.starts_with(a)
+}
@@ -20,6 +20,8 @@ impl From<RecvError> for Error {
 pub enum ClientError {
     RequestError(String),
     Unauthorized,
+    /// Workspace deleted or user not member of it
+    NotAccessible,
     UnexpectedResponse(String),
 }
 
@@ -48,6 +50,7 @@ impl Display for ClientError {
                 write!(f, "Error during http request: '{}'", message)
             }
             ClientError::Unauthorized => write!(f, "Error during http request: Unauthorized"),
+            ClientError::NotAccessible => write!(f, "Workspace is not accessible"),
             ClientError::UnexpectedResponse(message) => {
                 write!(f, "Unexpected http response: '{}'", message)
             }
@@ -0,0 +1,295 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use ini::Ini;
+use trsync_core::instance::{Instance, InstanceId, WorkspaceId};
+
+use crate::error::Error;
+
+/// Registry file, stored in local folder
+pub const REGISTRY_FILE_NAME: &str = ".trsync_workspaces";
+/// Folder of archived workspaces folders, in local folder
+pub const ARCHIVES_FOLDER_NAME: &str = ".trsync_archives";
+
+/// Folder of each synchronized workspace (relative to local folder), by instance.
+/// Allow to retrieve the existing folder (and its database) of a workspace after its
+/// folder path changed (workspace renamed, folder layout changed).
+pub struct FolderRegistry {
+    local_folder: PathBuf,
+    folders: HashMap<(InstanceId, WorkspaceId), String>,
+}
+
+impl FolderRegistry {
+    pub fn load(local_folder: &Path) -> Result<Self, Error> {
+        let mut folders = HashMap::new();
+        let path = local_folder.join(REGISTRY_FILE_NAME);
+
+        if path.exists() {
+            let registry = Ini::load_from_file(&path).map_err(|error| {
+                Error::UnexpectedError(format!(
+                    "Unable to read folder registry '{}' : '{}'",
+                    path.display(),
+                    error
+                ))
+            })?;
+            for (instance_id, section) in registry.iter() {
+                let instance_id = match instance_id {
+                    Some(instance_id) => instance_id,
+                    None => continue,
+                };
+                for (raw_workspace_id, folder) in section.iter() {
+                    match raw_workspace_id.parse::<i32>() {
+                        Ok(workspace_id) => {
+                            folders.insert(
+                                (
+                                    InstanceId(instance_id.to_string()),
+                                    WorkspaceId(workspace_id),
+                                ),
+                                folder.to_string(),
+                            );
+                        }
+                        Err(_) => log::error!(
+                            "Ignore invalid workspace id '{}' in '{}'",
+                            raw_workspace_id,
+                            path.display()
+                        ),
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            local_folder: local_folder.to_path_buf(),
+            folders,
+        })
+    }
+
+    fn write(&self) -> Result<(), Error> {
+        let mut registry = Ini::new();
+        let mut folders: Vec<_> = self.folders.iter().collect();
+        folders
+            .sort_by_key(|((instance_id, workspace_id), _)| (instance_id.clone(), workspace_id.0));
+        for ((instance_id, workspace_id), folder) in folders {
+            registry
+                .with_section(Some(instance_id.to_string()))
+                .set(workspace_id.to_string(), folder.clone());
+        }
+
+        let path = self.local_folder.join(REGISTRY_FILE_NAME);
+        registry.write_to_file(&path).map_err(|error| {
+            Error::UnexpectedError(format!(
+                "Unable to write folder registry '{}' : '{}'",
+                path.display(),
+                error
+            ))
+        })
+    }
+
+    /// True if a folder is recorded for given workspace
+    pub fn is_known(&self, instance_id: &InstanceId, workspace_id: WorkspaceId) -> bool {
+        self.folders
+            .contains_key(&(instance_id.clone(), workspace_id))
+    }
+
+    /// Absolute path of given workspace folder. If workspace was previously synchronized in
+    /// another folder, this folder is moved to the new path.
+    pub fn workspace_folder(
+        &mut self,
+        instance: &Instance,
+        workspace_id: WorkspaceId,
+        folder: &Path,
+    ) -> Result<PathBuf, Error> {
+        let key = (instance.name.clone(), workspace_id);
+        let folder = folder.to_string_lossy().replace('\\', "/");
+        let folder_path = self.local_folder.join(&folder);
+
+        if let Some(previous_folder) = self.folders.get(&key) {
+            let previous_path = self.local_folder.join(previous_folder);
+            if previous_folder != &folder && previous_path.exists() {
+                // Keep previous folder (and its database) rather than synchronize from scratch
+                if folder_path.exists() {
+                    return Err(Error::UnexpectedError(format!(
+                        "Folder of workspace {} changed from '{}' to '{}' but '{}' already \
+                        exists, move or remove one of them",
+                        workspace_id,
+                        previous_folder,
+                        folder,
+                        folder_path.display(),
+                    )));
+                }
+                log::info!(
+                    "Folder of workspace {} changed from '{}' to '{}', move it",
+                    workspace_id,
+                    previous_folder,
+                    folder
+                );
+                if let Some(parent) = folder_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::rename(&previous_path, &folder_path).map_err(|error| {
+                    Error::UnexpectedError(format!(
+                        "Unable to move '{}' to '{}' : '{}'",
+                        previous_path.display(),
+                        folder_path.display(),
+                        error
+                    ))
+                })?;
+            }
+        }
+
+        if self.folders.get(&key) != Some(&folder) {
+            self.folders.insert(key, folder);
+            self.write()?;
+        }
+
+        Ok(folder_path)
+    }
+
+    /// Forget folder of given workspace (its folder has been archived or deleted)
+    pub fn forget(
+        &mut self,
+        instance_id: &InstanceId,
+        workspace_id: WorkspaceId,
+    ) -> Result<(), Error> {
+        if self
+            .folders
+            .remove(&(instance_id.clone(), workspace_id))
+            .is_some()
+        {
+            self.write()?;
+        }
+        Ok(())
+    }
+}
+
+/// Move given workspace folder into archives folder, with archiving date in its name
+pub fn archive_folder(local_folder: &Path, folder: &Path) -> Result<PathBuf, Error> {
+    let archives_folder = local_folder.join(ARCHIVES_FOLDER_NAME);
+    fs::create_dir_all(&archives_folder)?;
+
+    let folder_name = folder
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let archive_path = archives_folder.join(format!(
+        "{} {}",
+        folder_name,
+        Local::now().format("%Y-%m-%d %H-%M-%S")
+    ));
+    fs::rename(folder, &archive_path).map_err(|error| {
+        Error::UnexpectedError(format!(
+            "Unable to move '{}' to '{}' : '{}'",
+            folder.display(),
+            archive_path.display(),
+            error
+        ))
+    })?;
+
+    Ok(archive_path)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use testdir::testdir;
+    use trsync_core::{transfer::BandwidthConfig, transport::TransportConfig};
+
+    use super::*;
+
+    fn instance() -> Instance {
+        Instance {
+            name: InstanceId("algoo".to_string()),
+            address: "algoo.tracim.fr".to_string(),
+            unsecure: false,
+            username: "bux".to_string(),
+            password: "".to_string(),
+            workspaces_ids: vec![WorkspaceId(1)],
+            all_workspaces: false,
+            excluded_workspaces_ids: vec![],
+            transport: TransportConfig::default(),
+            bandwidth: BandwidthConfig::default(),
+            workspaces_configs: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_workspace_folder_moved() {
+        // Given
+        let local_folder = testdir!();
+        let mut registry = FolderRegistry::load(&local_folder).unwrap();
+        let previous = registry
+            .workspace_folder(&instance(), WorkspaceId(1), Path::new("algoo/Projects"))
+            .unwrap();
+        fs::create_dir_all(&previous).unwrap();
+        fs::write(previous.join(".trsync.db"), "").unwrap();
+
+        // When
+        let folder = registry
+            .workspace_folder(&instance(), WorkspaceId(1), Path::new("algoo/Archives"))
+            .unwrap();
+
+        // Then
+        assert_eq!(folder, local_folder.join("algoo/Archives"));
+        assert!(!previous.exists());
+        assert!(folder.join(".trsync.db").exists());
+    }
+
+    #[test]
+    fn test_workspace_folder_conflict() {
+        // Given
+        let local_folder = testdir!();
+        let mut registry = FolderRegistry::load(&local_folder).unwrap();
+        let previous = registry
+            .workspace_folder(&instance(), WorkspaceId(1), Path::new("algoo/Projects"))
+            .unwrap();
+        fs::create_dir_all(&previous).unwrap();
+        fs::create_dir_all(local_folder.join("algoo/Archives")).unwrap();
+
+        // When
+        let result =
+            registry.workspace_folder(&instance(), WorkspaceId(1), Path::new("algoo/Archives"));
+
+        // Then
+        assert!(result.is_err());
+        assert!(previous.exists());
+        let registry = FolderRegistry::load(&local_folder).unwrap();
+        assert_eq!(
+            registry
+                .folders
+                .get(&(instance().name, WorkspaceId(1)))
+                .map(String::as_str),
+            Some("algoo/Projects")
+        );
+    }
+
+    #[test]
+    fn test_registry_persistence() {
+        // Given
+        let local_folder = testdir!();
+        let mut registry = FolderRegistry::load(&local_folder).unwrap();
+        registry
+            .workspace_folder(&instance(), WorkspaceId(1), Path::new("algoo/Projects"))
+            .unwrap();
+        registry
+            .workspace_folder(&instance(), WorkspaceId(2), Path::new("algoo/Archives"))
+            .unwrap();
+        registry.forget(&instance().name, WorkspaceId(2)).unwrap();
+
+        // When
+        let registry = FolderRegistry::load(&local_folder).unwrap();
+
+        // Then
+        assert_eq!(
+            registry.folders,
+            HashMap::from([(
+                (instance().name, WorkspaceId(1)),
+                "algoo/Projects".to_string()
+            )])
+        );
+    }
+}
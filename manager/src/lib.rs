@@ -1,5 +1,10 @@
+pub mod child;
 pub mod client;
 pub mod daemon;
 pub mod error;
+pub mod folder;
+pub mod membership;
 pub mod message;
+pub mod socket;
+pub mod tui;
 pub mod types;
@@ -0,0 +1,178 @@
+use std::{
+    str,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use async_std::task;
+use crossbeam_channel::Sender;
+use futures_util::StreamExt;
+use tokio::time::timeout;
+use trsync::remote::watcher::TracimLiveEvent;
+use trsync_core::{
+    client::{Client, TracimClientError},
+    instance::Instance,
+    user::UserId,
+};
+
+use crate::{error::Error, message::DaemonMessage};
+
+const MEMBERSHIP_EVENT_TYPES: [&str; 2] = ["workspace_member.created", "workspace_member.deleted"];
+const LAST_ACTIVITY_TIMEOUT: Duration = Duration::from_secs(60);
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// How a listening ended
+enum ListenEnd {
+    /// Stream closed, inactive or stop signal received
+    Disconnected,
+    /// Credentials refused, listening again is useless until configuration changes
+    Unauthorized,
+}
+
+/// Listen live messages of an instance to detect when user joins or leaves a workspace
+pub struct MembershipListener {
+    instance: Instance,
+    main_sender: Sender<DaemonMessage>,
+    stop_signal: Arc<AtomicBool>,
+}
+
+impl MembershipListener {
+    pub fn new(
+        instance: Instance,
+        main_sender: Sender<DaemonMessage>,
+        stop_signal: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            instance,
+            main_sender,
+            stop_signal,
+        }
+    }
+
+    pub fn start(self) -> JoinHandle<()> {
+        thread::spawn(move || {
+            while !self.stop_signal.load(Ordering::Relaxed) {
+                match self.listen() {
+                    Ok(ListenEnd::Disconnected) => self.sleep(RECONNECT_DELAY),
+                    Ok(ListenEnd::Unauthorized) => {
+                        log::error!(
+                            "Credentials refused for '{}', stop membership listening",
+                            self.instance.name,
+                        );
+                        break;
+                    }
+                    Err(error) => {
+                        log::error!(
+                            "Membership listening error for '{}' : '{}', retry in {}s",
+                            self.instance.name,
+                            error,
+                            RETRY_INTERVAL.as_secs()
+                        );
+                        self.sleep(RETRY_INTERVAL);
+                    }
+                }
+            }
+            log::info!("Finished membership listening for '{}'", self.instance.name);
+        })
+    }
+
+    /// Sleep given duration, or less if stop signal is received
+    fn sleep(&self, duration: Duration) {
+        let start = Instant::now();
+        while start.elapsed() < duration && !self.stop_signal.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(250));
+        }
+    }
+
+    /// Listen until stop signal or connection inactivity
+    fn listen(&self) -> Result<ListenEnd, Error> {
+        let client = Client::new(
+            self.instance.url(None),
+            self.instance.username.clone(),
+            self.instance.password.clone(),
+            &self.instance.transport,
+        )
+        .map_err(|error| Error::UnexpectedError(format!("Unable to build client : {}", error)))?;
+        let user_id = match client
+            .check_credentials()
+            .map_err(|error| Error::UnavailableNetwork(format!("{}", error)))?
+        {
+            Some(user_id) => user_id,
+            None => return Ok(ListenEnd::Unauthorized),
+        };
+
+        task::block_on(async {
+            let response = match client.live_messages_response(&user_id).await {
+                Ok(response) => response,
+                Err(TracimClientError::AuthenticationError) => return Ok(ListenEnd::Unauthorized),
+                Err(error) => return Err(Error::UnavailableNetwork(format!("{}", error))),
+            };
+            let mut stream = response.bytes_stream();
+            let mut buffer = String::new();
+            let mut last_activity = Instant::now();
+            log::info!("Listen memberships of '{}'", self.instance.name);
+
+            while !self.stop_signal.load(Ordering::Relaxed) {
+                match timeout(Duration::from_millis(250), stream.next()).await {
+                    Ok(Some(Ok(bytes))) => {
+                        last_activity = Instant::now();
+                        buffer.push_str(&String::from_utf8_lossy(&bytes));
+                        // Lines can be split over several chunks
+                        while let Some(index) = buffer.find('\n') {
+                            let line: String = buffer.drain(..=index).collect();
+                            self.proceed_line(line.trim_end(), &user_id);
+                        }
+                    }
+                    Ok(Some(Err(error))) => {
+                        return Err(Error::UnavailableNetwork(format!("{}", error)))
+                    }
+                    Ok(None) => return Ok(ListenEnd::Disconnected),
+                    Err(_) => {
+                        if last_activity.elapsed() > LAST_ACTIVITY_TIMEOUT {
+                            log::info!(
+                                "No live messages activity for '{}', reconnect",
+                                self.instance.name
+                            );
+                            return Ok(ListenEnd::Disconnected);
+                        }
+                    }
+                }
+            }
+
+            Ok(ListenEnd::Disconnected)
+        })
+    }
+
+    fn proceed_line(&self, line: &str, user_id: &UserId) {
+        let event = match TracimLiveEvent::from_line(line) {
+            Some(Ok(event)) => event,
+            Some(Err(error)) => {
+                log::error!("Error when decoding event : '{}'", error);
+                return;
+            }
+            None => return,
+        };
+
+        let event_type = event.event_type.as_str();
+        let member_id = event.fields["user"]["user_id"].as_i64();
+        if MEMBERSHIP_EVENT_TYPES.contains(&event_type) && member_id == Some(user_id.0 as i64) {
+            log::info!(
+                "Membership changed on '{}' ({}), update workspaces",
+                self.instance.name,
+                event_type
+            );
+            if self
+                .main_sender
+                .send(DaemonMessage::MembershipChanged(self.instance.name.clone()))
+                .is_err()
+            {
+                log::error!("Unable to send membership change to daemon")
+            }
+        }
+    }
+}
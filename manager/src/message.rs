@@ -1,7 +1,16 @@
-use trsync_core::config::ManagerConfig;
+use trsync_core::{config::ManagerConfig, instance::InstanceId, job::JobIdentifier};
+
+use crate::types::TrsyncUid;
 
 #[derive(Debug)]
 pub enum DaemonMessage {
     Reload(ManagerConfig),
+    Pause(JobIdentifier),
+    Resume(JobIdentifier),
+    Resync(JobIdentifier),
+    /// User joined or left a workspace of this instance
+    MembershipChanged(InstanceId),
+    /// Apply removed workspace policy to these workspaces, confirmed by user
+    CleanRemovedWorkspaces(Vec<TrsyncUid>),
     Stop,
 }
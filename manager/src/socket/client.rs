@@ -0,0 +1,48 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    os::unix::net::UnixStream,
+    path::Path,
+};
+
+use crate::error::Error;
+
+use super::{Request, Response};
+
+/// Client of a running daemon control socket
+pub struct SocketClient {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl SocketClient {
+    pub fn connect(path: &Path) -> Result<Self, Error> {
+        let stream = UnixStream::connect(path).map_err(|error| {
+            Error::UnexpectedError(format!(
+                "Unable to connect to '{}' (is manager running ?) : {}",
+                path.display(),
+                error
+            ))
+        })?;
+        Ok(Self {
+            writer: stream.try_clone()?,
+            reader: BufReader::new(stream),
+        })
+    }
+
+    pub fn request(&mut self, request: &Request) -> Result<Response, Error> {
+        let request = serde_json::to_string(request).map_err(|error| {
+            Error::UnexpectedError(format!("Unable to serialize request : {}", error))
+        })?;
+        self.writer.write_all(format!("{}\n", request).as_bytes())?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(Error::UnexpectedError(
+                "Connection closed by manager".to_string(),
+            ));
+        }
+        serde_json::from_str(&line).map_err(|error| {
+            Error::UnexpectedError(format!("Invalid response '{}' : {}", line.trim(), error))
+        })
+    }
+}
@@ -0,0 +1,208 @@
+use std::path::PathBuf;
+
+use serde_derive::{Deserialize, Serialize};
+use trsync_core::{
+    activity::Activity,
+    config::ManagerConfig,
+    error::{Decision, ErrorClass, ErrorSubject},
+    instance::ContentId,
+    job::JobIdentifier,
+    status::JobStatus,
+};
+
+use crate::error::Error;
+
+#[cfg(unix)]
+pub mod client;
+#[cfg(unix)]
+pub mod server;
+
+pub const SOCKET_FILE_NAME: &str = "trsync.sock";
+
+/// Produce the config to use when a reload is requested
+pub type ConfigLoader = fn() -> anyhow::Result<ManagerConfig>;
+
+/// Workspace targeted by a request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workspace {
+    pub instance_name: String,
+    pub workspace_id: i32,
+}
+
+impl Workspace {
+    pub fn new(instance_name: String, workspace_id: i32) -> Self {
+        Self {
+            instance_name,
+            workspace_id,
+        }
+    }
+
+    pub fn matches(&self, job_identifier: &JobIdentifier) -> bool {
+        self.instance_name == job_identifier.instance_name
+            && self.workspace_id == job_identifier.workspace_id
+    }
+}
+
+impl std::fmt::Display for Workspace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}::{}", self.instance_name, self.workspace_id)
+    }
+}
+
+/// Requests accepted by the control socket, one json document per line
+/// (ex. `{"request":"pause","workspace":{"instance_name":"tracim","workspace_id":4}}`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum Request {
+    ListJobs,
+    Reload,
+    Pause {
+        workspace: Workspace,
+    },
+    Resume {
+        workspace: Workspace,
+    },
+    Resync {
+        workspace: Workspace,
+    },
+    Errors,
+    Decide {
+        workspace: Workspace,
+        decision: Decision,
+    },
+    Confirm {
+        workspace: Workspace,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobState {
+    pub job: JobIdentifier,
+    pub activity: Activity,
+    pub status: JobStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobError {
+    pub job: JobIdentifier,
+    pub message: String,
+    pub class: ErrorClass,
+    /// Content which can be ignored to get rid of the error
+    pub content_id: Option<ContentId>,
+    pub subject: Option<ErrorSubject>,
+}
+
+/// Responses of the control socket, one json document per line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "response", content = "data", rename_all = "snake_case")]
+pub enum Response {
+    Done,
+    Jobs(Vec<JobState>),
+    Errors(Vec<JobError>),
+    Error(String),
+}
+
+/// Configured socket path, or default one (in user runtime folder if any, home folder else)
+pub fn socket_path(configured: Option<&str>) -> Result<PathBuf, Error> {
+    if let Some(configured) = configured {
+        return Ok(PathBuf::from(configured));
+    }
+
+    if let Some(runtime_dir) = dirs::runtime_dir() {
+        return Ok(runtime_dir.join(SOCKET_FILE_NAME));
+    }
+
+    match dirs::home_dir() {
+        Some(home_dir) => Ok(home_dir.join(format!(".{}", SOCKET_FILE_NAME))),
+        None => Err(Error::UnexpectedError(
+            "Unable to determine home folder".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{de::DeserializeOwned, Serialize};
+    use trsync_core::error::ErrorSubject;
+
+    use super::*;
+
+    /// Json line of given value, after a round trip through its json line
+    fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> (String, String) {
+        let line = serde_json::to_string(value).unwrap();
+        assert!(!line.contains('\n'));
+        let read: T = serde_json::from_str(&line).unwrap();
+        (line, serde_json::to_string(&read).unwrap())
+    }
+
+    #[test]
+    fn test_requests_round_trip() {
+        let workspace = Workspace::new("tracim".to_string(), 4);
+        let requests = vec![
+            Request::ListJobs,
+            Request::Reload,
+            Request::Pause {
+                workspace: workspace.clone(),
+            },
+            Request::Resume {
+                workspace: workspace.clone(),
+            },
+            Request::Resync {
+                workspace: workspace.clone(),
+            },
+            Request::Errors,
+            Request::Decide {
+                workspace: workspace.clone(),
+                decision: Decision::IgnoreAndRestartSpaceSync(ContentId(42)),
+            },
+            Request::Confirm { workspace },
+        ];
+
+        for request in requests {
+            let (line, read_line) = round_trip(&request);
+            assert_eq!(line, read_line);
+        }
+    }
+
+    #[test]
+    fn test_request_documented_format() {
+        let line = r#"{"request":"pause","workspace":{"instance_name":"tracim","workspace_id":4}}"#;
+
+        let request: Request = serde_json::from_str(line).unwrap();
+
+        assert!(matches!(
+            request,
+            Request::Pause { workspace } if workspace == Workspace::new("tracim".to_string(), 4)
+        ));
+    }
+
+    #[test]
+    fn test_responses_round_trip() {
+        let job = JobIdentifier::new("tracim".to_string(), 4, "W".to_string(), "/W".to_string());
+        let responses = vec![
+            Response::Done,
+            Response::Jobs(vec![JobState {
+                job: job.clone(),
+                activity: Activity::Job("Upload a.txt".to_string()),
+                status: JobStatus::default(),
+            }]),
+            Response::Errors(vec![JobError {
+                job,
+                message: "Quota exceeded".to_string(),
+                class: ErrorClass::Network,
+                content_id: Some(ContentId(42)),
+                subject: Some(ErrorSubject {
+                    event: Some("Created a.txt".to_string()),
+                    content_id: None,
+                    path: Some(PathBuf::from("a.txt")),
+                }),
+            }]),
+            Response::Error("Unknown workspace".to_string()),
+        ];
+
+        for response in responses {
+            let (line, read_line) = round_trip(&response);
+            assert_eq!(line, read_line);
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, ErrorKind, Write},
+    os::unix::{
+        fs::PermissionsExt,
+        net::{UnixListener, UnixStream},
+    },
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crossbeam_channel::Sender;
+use trsync_core::{
+    activity::ActivityState, error::ErrorExchanger, job::JobIdentifier, sync::SyncExchanger,
+};
+
+use crate::{error::Error, message::DaemonMessage};
+
+use super::{ConfigLoader, JobError, JobState, Request, Response, Workspace};
+
+/// Local unix socket allowing other processes (scripts, cli, etc.) to drive the daemon
+pub struct SocketServer {
+    path: PathBuf,
+    main_sender: Sender<DaemonMessage>,
+    activity_state: Arc<Mutex<ActivityState>>,
+    sync_exchanger: Arc<Mutex<SyncExchanger>>,
+    error_exchanger: Arc<Mutex<ErrorExchanger>>,
+    config_loader: ConfigLoader,
+    stop_signal: Arc<AtomicBool>,
+}
+
+impl SocketServer {
+    pub fn new(
+        path: PathBuf,
+        main_sender: Sender<DaemonMessage>,
+        activity_state: Arc<Mutex<ActivityState>>,
+        sync_exchanger: Arc<Mutex<SyncExchanger>>,
+        error_exchanger: Arc<Mutex<ErrorExchanger>>,
+        config_loader: ConfigLoader,
+        stop_signal: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            path,
+            main_sender,
+            activity_state,
+            sync_exchanger,
+            error_exchanger,
+            config_loader,
+            stop_signal,
+        }
+    }
+
+    fn bind(&self) -> Result<UnixListener, Error> {
+        if self.path.exists() {
+            if UnixStream::connect(&self.path).is_ok() {
+                return Err(Error::UnexpectedError(format!(
+                    "Socket '{}' is already used by another process",
+                    self.path.display()
+                )));
+            }
+            // Left by a previous process which did not terminate properly
+            fs::remove_file(&self.path)?;
+        }
+
+        let listener = UnixListener::bind(&self.path)?;
+        fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600))?;
+        listener.set_nonblocking(true)?;
+        Ok(listener)
+    }
+
+    pub fn start(self) -> Result<JoinHandle<()>, Error> {
+        let listener = self.bind()?;
+        log::info!("Listen control socket '{}'", self.path.display());
+
+        let server = Arc::new(self);
+        Ok(thread::spawn(move || {
+            loop {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let server_ = server.clone();
+                        thread::spawn(move || {
+                            if let Err(error) = server_.serve(stream) {
+                                log::error!("Control socket connection error : '{}'", error)
+                            }
+                        });
+                    }
+                    Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                        if server.stop_signal.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        thread::sleep(Duration::from_millis(250));
+                    }
+                    Err(error) => {
+                        log::error!("Control socket error : '{}'", error);
+                        break;
+                    }
+                }
+            }
+
+            if let Err(error) = fs::remove_file(&server.path) {
+                log::error!(
+                    "Unable to remove socket '{}' : '{}'",
+                    server.path.display(),
+                    error
+                )
+            }
+            log::info!("Finished control socket");
+        }))
+    }
+
+    fn serve(&self, stream: UnixStream) -> Result<(), Error> {
+        stream.set_nonblocking(false)?;
+        let mut writer = stream.try_clone()?;
+
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match serde_json::from_str::<Request>(&line) {
+                Ok(request) => {
+                    log::debug!("Control socket request : {:?}", request);
+                    self.respond(request)
+                }
+                Err(error) => Response::Error(format!("Invalid request : {}", error)),
+            };
+            let response = serde_json::to_string(&response).map_err(|error| {
+                Error::UnexpectedError(format!("Unable to serialize response : {}", error))
+            })?;
+            writer.write_all(format!("{}\n", response).as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    fn respond(&self, request: Request) -> Response {
+        match request {
+            Request::ListJobs => self.jobs(),
+            Request::Reload => match (self.config_loader)() {
+                Ok(config) => self.send(DaemonMessage::Reload(config)),
+                Err(error) => Response::Error(format!("Unable to read config : {:#}", error)),
+            },
+            Request::Pause { workspace } => self.send_to(&workspace, DaemonMessage::Pause),
+            Request::Resume { workspace } => self.send_to(&workspace, DaemonMessage::Resume),
+            Request::Resync { workspace } => self.send_to(&workspace, DaemonMessage::Resync),
+            Request::Errors => self.errors(),
+            Request::Decide {
+                workspace,
+                decision,
+            } => {
+                let error_exchanger = self.error_exchanger.lock().unwrap();
+                let channels = match error_exchanger
+                    .channels()
+                    .iter()
+                    .find(|(job_identifier, _)| workspace.matches(job_identifier))
+                {
+                    Some((_, channels)) => channels,
+                    None => return Response::Error(format!("Unknown workspace '{}'", workspace)),
+                };
+
+                let mut error = channels.error().lock().unwrap();
+                if error.is_none() {
+                    return Response::Error(format!("No pending error for '{}'", workspace));
+                }
+                if channels.decision_sender().send(decision).is_err() {
+                    return Response::Error(format!("Unable to send decision to '{}'", workspace));
+                }
+                *error = None;
+                Response::Done
+            }
+            Request::Confirm { workspace } => {
+                let sync_exchanger = self.sync_exchanger.lock().unwrap();
+                let channels = match sync_exchanger
+                    .channels()
+                    .iter()
+                    .find(|(job_identifier, _)| workspace.matches(job_identifier))
+                {
+                    Some((_, channels)) => channels,
+                    None => return Response::Error(format!("Unknown workspace '{}'", workspace)),
+                };
+
+                let mut changes = channels.changes().lock().unwrap();
+                if changes.is_none() {
+                    return Response::Error(format!("No pending startup sync for '{}'", workspace));
+                }
+                if channels.confirm_sync_sender().send(true).is_err() {
+                    return Response::Error(format!(
+                        "Unable to send confirmation to '{}'",
+                        workspace
+                    ));
+                }
+                *changes = None;
+                Response::Done
+            }
+        }
+    }
+
+    fn jobs(&self) -> Response {
+        let activity_state = self.activity_state.lock().unwrap();
+        let mut jobs: Vec<JobState> = activity_state
+            .activities()
+            .iter()
+            .map(|(job_identifier, activity)| JobState {
+                job: job_identifier.clone(),
+                activity: activity.clone(),
+                status: activity_state.status(job_identifier),
+            })
+            .collect();
+        jobs.sort_by_key(|job| job.job.to_string());
+        Response::Jobs(jobs)
+    }
+
+    fn errors(&self) -> Response {
+        let mut errors = vec![];
+        for (job_identifier, channels) in self.error_exchanger.lock().unwrap().channels() {
+            if let Some(error) = channels.error().lock().unwrap().as_ref() {
+                errors.push(JobError {
+                    job: job_identifier.clone(),
+                    message: error.to_string(),
+                    class: error.class(),
+                    content_id: error.ignorable_content_id(),
+                    subject: channels.subject().lock().unwrap().clone(),
+                })
+            }
+        }
+        errors.sort_by_key(|error| error.job.to_string());
+        Response::Errors(errors)
+    }
+
+    fn job_identifier(&self, workspace: &Workspace) -> Option<JobIdentifier> {
+        self.activity_state
+            .lock()
+            .unwrap()
+            .activities()
+            .keys()
+            .find(|job_identifier| workspace.matches(job_identifier))
+            .cloned()
+    }
+
+    fn send_to(
+        &self,
+        workspace: &Workspace,
+        message: fn(JobIdentifier) -> DaemonMessage,
+    ) -> Response {
+        match self.job_identifier(workspace) {
+            Some(job_identifier) => self.send(message(job_identifier)),
+            None => Response::Error(format!("Unknown workspace '{}'", workspace)),
+        }
+    }
+
+    fn send(&self, message: DaemonMessage) -> Response {
+        match self.main_sender.send(message) {
+            Ok(_) => Response::Done,
+            Err(error) => Response::Error(format!("Unable to communicate with daemon : {}", error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossbeam_channel::unbounded;
+    use testdir::testdir;
+
+    use super::*;
+
+    #[test]
+    fn test_malformed_line() {
+        // Given
+        let path = testdir!().join("trsync.sock");
+        let stop_signal = Arc::new(AtomicBool::new(false));
+        let (main_sender, _main_receiver) = unbounded();
+        let handle = SocketServer::new(
+            path.clone(),
+            main_sender,
+            Arc::new(Mutex::new(ActivityState::new())),
+            Arc::new(Mutex::new(SyncExchanger::new())),
+            Arc::new(Mutex::new(ErrorExchanger::new())),
+            || Err(anyhow::anyhow!("No config in tests")),
+            stop_signal.clone(),
+        )
+        .start()
+        .unwrap();
+        let mut stream = UnixStream::connect(&path).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut read_response = || {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            serde_json::from_str::<Response>(&line).unwrap()
+        };
+
+        // When
+        stream.write_all(b"not json\n").unwrap();
+        let malformed_response = read_response();
+        stream.write_all(b"{\"request\":\"list_jobs\"}\n").unwrap();
+        let next_response = read_response();
+
+        // Then (connection is still usable after a malformed line)
+        assert!(
+            matches!(malformed_response, Response::Error(message) if message.starts_with("Invalid request"))
+        );
+        assert!(matches!(next_response, Response::Jobs(jobs) if jobs.is_empty()));
+
+        stop_signal.store(true, Ordering::Relaxed);
+        handle.join().unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,385 @@
+use std::{
+    io,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use crossbeam_channel::Sender;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+use trsync_core::{
+    activity::{Activity, ActivityState},
+    error::{Decision, ErrorClass, ErrorExchanger, ErrorSubject},
+    instance::ContentId,
+    job::JobIdentifier,
+    status::JobStatus,
+    sync::SyncExchanger,
+    tr,
+    utils::open_folder,
+};
+
+use crate::message::DaemonMessage;
+
+mod view;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    Jobs,
+    Confirmations,
+    Errors,
+}
+
+impl Panel {
+    pub const ALL: [Panel; 3] = [Panel::Jobs, Panel::Confirmations, Panel::Errors];
+
+    fn next(&self) -> Self {
+        match self {
+            Panel::Jobs => Panel::Confirmations,
+            Panel::Confirmations => Panel::Errors,
+            Panel::Errors => Panel::Jobs,
+        }
+    }
+}
+
+/// Terminal frontend of the daemon, for headless environments (like over ssh)
+pub struct Tui {
+    activity_state: Arc<Mutex<ActivityState>>,
+    sync_exchanger: Arc<Mutex<SyncExchanger>>,
+    error_exchanger: Arc<Mutex<ErrorExchanger>>,
+    main_sender: Sender<DaemonMessage>,
+    stop_signal: Arc<AtomicBool>,
+    panel: Panel,
+    selected: usize,
+    message: Option<String>,
+}
+
+impl Tui {
+    pub fn new(
+        activity_state: Arc<Mutex<ActivityState>>,
+        sync_exchanger: Arc<Mutex<SyncExchanger>>,
+        error_exchanger: Arc<Mutex<ErrorExchanger>>,
+        main_sender: Sender<DaemonMessage>,
+        stop_signal: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            activity_state,
+            sync_exchanger,
+            error_exchanger,
+            main_sender,
+            stop_signal,
+            panel: Panel::Jobs,
+            selected: 0,
+            message: None,
+        }
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        enable_raw_mode().context("Enable terminal raw mode")?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen).context("Enter terminal alternate screen")?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+        let result = self.run_(&mut terminal);
+
+        disable_raw_mode().context("Disable terminal raw mode")?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)
+            .context("Leave terminal alternate screen")?;
+        terminal.show_cursor()?;
+        result
+    }
+
+    fn run_(&mut self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+        loop {
+            if self.stop_signal.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+
+            terminal.draw(|frame| view::draw(frame, self))?;
+
+            if !event::poll(Duration::from_millis(250))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                if is_quit(&key) {
+                    self.send(DaemonMessage::Stop);
+                    return Ok(());
+                }
+                self.on_key(key.code);
+            }
+        }
+    }
+
+    fn on_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Tab => self.set_panel(self.panel.next()),
+            KeyCode::Char('1') => self.set_panel(Panel::Jobs),
+            KeyCode::Char('2') => self.set_panel(Panel::Confirmations),
+            KeyCode::Char('3') => self.set_panel(Panel::Errors),
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected += 1,
+            _ => {
+                let job_identifier = match self.selected_job() {
+                    Some(job_identifier) => job_identifier,
+                    None => return,
+                };
+                match (self.panel, code) {
+                    (Panel::Jobs, KeyCode::Char('p')) => self.toggle_pause(job_identifier),
+                    (Panel::Jobs, KeyCode::Char('s')) => {
+                        self.send(DaemonMessage::Resync(job_identifier))
+                    }
+                    (Panel::Confirmations, KeyCode::Char('a')) => {
+                        self.confirm(&job_identifier, true)
+                    }
+                    (Panel::Confirmations, KeyCode::Char('r')) => {
+                        self.confirm(&job_identifier, false)
+                    }
+                    (Panel::Errors, KeyCode::Char('r')) => {
+                        self.decide(&job_identifier, Decision::RestartSpaceSync)
+                    }
+                    (Panel::Errors, KeyCode::Char('i')) => {
+                        match self
+                            .error(&job_identifier)
+                            .and_then(|(_, _, content_id)| content_id)
+                        {
+                            Some(content_id) => self.decide(
+                                &job_identifier,
+                                Decision::IgnoreAndRestartSpaceSync(content_id),
+                            ),
+                            None => {
+                                self.message =
+                                    Some(tr!("This error is not about a content").to_string())
+                            }
+                        }
+                    }
+                    (Panel::Errors, KeyCode::Char('s')) => {
+                        match self
+                            .subject(&job_identifier)
+                            .and_then(|subject| subject.event)
+                        {
+                            Some(_) => {
+                                self.decide(&job_identifier, Decision::SkipEventAndRestartSpaceSync)
+                            }
+                            None => {
+                                self.message =
+                                    Some(tr!("This error is not about an event").to_string())
+                            }
+                        }
+                    }
+                    (Panel::Errors, KeyCode::Char('p')) => {
+                        self.decide(&job_identifier, Decision::PauseSpaceSync)
+                    }
+                    (Panel::Errors, KeyCode::Char('x')) => {
+                        self.decide(&job_identifier, Decision::ResetAndRestartSpaceSync)
+                    }
+                    (Panel::Errors, KeyCode::Char('d')) => {
+                        match self
+                            .subject(&job_identifier)
+                            .and_then(|subject| subject.content_id)
+                        {
+                            Some(content_id) => self.decide(
+                                &job_identifier,
+                                Decision::RedownloadAndRestartSpaceSync(content_id),
+                            ),
+                            None => {
+                                self.message =
+                                    Some(tr!("This error is not about a content").to_string())
+                            }
+                        }
+                    }
+                    (Panel::Errors, KeyCode::Char('g')) => {
+                        match self
+                            .subject(&job_identifier)
+                            .and_then(|subject| subject.path)
+                        {
+                            Some(path) => self.decide(
+                                &job_identifier,
+                                Decision::IgnorePathAndRestartSpaceSync(path),
+                            ),
+                            None => {
+                                self.message =
+                                    Some(tr!("This error is not about a path").to_string())
+                            }
+                        }
+                    }
+                    (Panel::Errors, KeyCode::Char('o')) => self.open_folder(&job_identifier),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn set_panel(&mut self, panel: Panel) {
+        self.panel = panel;
+        self.selected = 0;
+        self.message = None;
+    }
+
+    pub fn panel(&self) -> Panel {
+        self.panel
+    }
+
+    pub fn message(&self) -> Option<&String> {
+        self.message.as_ref()
+    }
+
+    /// Jobs of current panel, with their activity
+    pub fn jobs(&self) -> Vec<(JobIdentifier, Activity)> {
+        let mut jobs: Vec<(JobIdentifier, Activity)> = self
+            .activity_state
+            .lock()
+            .unwrap()
+            .activities()
+            .iter()
+            .map(|(job_identifier, activity)| (job_identifier.clone(), activity.clone()))
+            .filter(|(job_identifier, _)| match self.panel {
+                Panel::Jobs => true,
+                Panel::Confirmations => self.changes(job_identifier).is_some(),
+                Panel::Errors => self.error(job_identifier).is_some(),
+            })
+            .collect();
+        jobs.sort_by_key(|(job_identifier, _)| job_identifier.to_string());
+        jobs
+    }
+
+    pub fn status(&self, job_identifier: &JobIdentifier) -> JobStatus {
+        self.activity_state.lock().unwrap().status(job_identifier)
+    }
+
+    /// Selected index, bounded by current panel jobs count
+    pub fn selected(&self, jobs_count: usize) -> Option<usize> {
+        match jobs_count {
+            0 => None,
+            _ => Some(self.selected.min(jobs_count - 1)),
+        }
+    }
+
+    pub fn selected_job(&self) -> Option<JobIdentifier> {
+        let jobs = self.jobs();
+        self.selected(jobs.len()).map(|index| jobs[index].0.clone())
+    }
+
+    /// Pending startup changes (as displayable lines) of given job
+    pub fn changes(&self, job_identifier: &JobIdentifier) -> Option<Vec<String>> {
+        let sync_exchanger = self.sync_exchanger.lock().unwrap();
+        let channels = sync_exchanger.channels().get(job_identifier)?;
+        let changes = channels.changes().lock().unwrap();
+        let (remote_changes, local_changes) = changes.as_ref()?;
+        Some(
+            remote_changes
+                .iter()
+                .map(|change| change.to_string())
+                .chain(local_changes.iter().map(|change| change.to_string()))
+                .collect(),
+        )
+    }
+
+    /// Pending error message of given job, with its class and content which can be
+    /// ignored, if any
+    pub fn error(
+        &self,
+        job_identifier: &JobIdentifier,
+    ) -> Option<(String, ErrorClass, Option<ContentId>)> {
+        let error_exchanger = self.error_exchanger.lock().unwrap();
+        let channels = error_exchanger.channels().get(job_identifier)?;
+        let error = channels.error().lock().unwrap();
+        error.as_ref().map(|error| {
+            (
+                error.to_string(),
+                error.class(),
+                error.ignorable_content_id(),
+            )
+        })
+    }
+
+    /// What the pending error of given job is about, if known
+    pub fn subject(&self, job_identifier: &JobIdentifier) -> Option<ErrorSubject> {
+        let error_exchanger = self.error_exchanger.lock().unwrap();
+        let channels = error_exchanger.channels().get(job_identifier)?;
+        let subject = channels.subject().lock().unwrap();
+        subject.clone()
+    }
+
+    /// Open folder containing the error subject, or workspace folder
+    fn open_folder(&mut self, job_identifier: &JobIdentifier) {
+        let workspace_folder = Path::new(&job_identifier.folder_path);
+        let folder = self
+            .subject(job_identifier)
+            .and_then(|subject| subject.path)
+            .and_then(|path| workspace_folder.join(path).parent().map(Path::to_path_buf))
+            .unwrap_or(workspace_folder.to_path_buf());
+        if let Err(error) = open_folder(&folder) {
+            self.message = Some(tr!("Unable to open {} : {}", folder.display(), error));
+        }
+    }
+
+    fn toggle_pause(&mut self, job_identifier: JobIdentifier) {
+        let paused = self
+            .activity_state
+            .lock()
+            .unwrap()
+            .activities()
+            .get(&job_identifier)
+            .map(|activity| activity.is_paused())
+            .unwrap_or(false);
+        if paused {
+            self.send(DaemonMessage::Resume(job_identifier))
+        } else {
+            self.send(DaemonMessage::Pause(job_identifier))
+        }
+    }
+
+    fn confirm(&mut self, job_identifier: &JobIdentifier, accept: bool) {
+        let sent = {
+            let sync_exchanger = self.sync_exchanger.lock().unwrap();
+            match sync_exchanger.channels().get(job_identifier) {
+                Some(channels) => {
+                    *channels.changes().lock().unwrap() = None;
+                    channels.confirm_sync_sender().send(accept).is_ok()
+                }
+                None => false,
+            }
+        };
+        if !sent {
+            self.message = Some(tr!("Unable to communicate with {}", job_identifier));
+        }
+    }
+
+    fn decide(&mut self, job_identifier: &JobIdentifier, decision: Decision) {
+        let sent = {
+            let error_exchanger = self.error_exchanger.lock().unwrap();
+            match error_exchanger.channels().get(job_identifier) {
+                Some(channels) => {
+                    *channels.error().lock().unwrap() = None;
+                    channels.decision_sender().send(decision).is_ok()
+                }
+                None => false,
+            }
+        };
+        if !sent {
+            self.message = Some(tr!("Unable to communicate with {}", job_identifier));
+        }
+    }
+
+    fn send(&mut self, message: DaemonMessage) {
+        if self.main_sender.send(message).is_err() {
+            self.message = Some(tr!("Unable to communicate with manager").to_string())
+        }
+    }
+}
+
+fn is_quit(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('q')
+        || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+}
@@ -0,0 +1,154 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap},
+    Frame,
+};
+
+use trsync_core::{status::ago, tr};
+
+use super::{Panel, Tui};
+
+pub fn draw(frame: &mut Frame, tui: &Tui) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .split(frame.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(rows[1]);
+
+    let titles: Vec<Line> = Panel::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, panel)| Line::from(format!("{} {}", i + 1, panel_title(panel))))
+        .collect();
+    let tabs = Tabs::new(titles)
+        .block(Block::default().borders(Borders::ALL).title("Trsync"))
+        .select(
+            Panel::ALL
+                .iter()
+                .position(|panel| panel == &tui.panel())
+                .unwrap_or(0),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED));
+    frame.render_widget(tabs, rows[0]);
+
+    let jobs = tui.jobs();
+    let items: Vec<ListItem> = jobs
+        .iter()
+        .map(|(job_identifier, activity)| match tui.panel() {
+            Panel::Jobs => ListItem::new(format!("{} : {}", job_identifier, activity)),
+            _ => ListItem::new(job_identifier.to_string()),
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(panel_title(&tui.panel())),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut list_state = ListState::default();
+    list_state.select(tui.selected(jobs.len()));
+    frame.render_stateful_widget(list, columns[0], &mut list_state);
+
+    let detail = match (tui.panel(), tui.selected_job()) {
+        (Panel::Jobs, Some(job_identifier)) => {
+            let activity = jobs
+                .iter()
+                .find(|(job_identifier_, _)| job_identifier_ == &job_identifier)
+                .map(|(_, activity)| activity.to_string())
+                .unwrap_or_default();
+            let status = tui.status(&job_identifier);
+            let never = || tr!("never").to_string();
+            vec![
+                Line::from(tr!("Instance : {}", job_identifier.instance_name)),
+                Line::from(tr!(
+                    "Workspace : {} ({})",
+                    job_identifier.workspace_name,
+                    job_identifier.workspace_id
+                )),
+                Line::from(tr!("Activity : {}", activity)),
+                Line::from(tr!(
+                    "Last full synchronization : {}",
+                    status.last_full_sync.map(ago).unwrap_or_else(never)
+                )),
+                Line::from(tr!(
+                    "Last event : {}",
+                    status.last_event.map(ago).unwrap_or_else(never)
+                )),
+                Line::from(tr!(
+                    "Pending : {} local, {} remote",
+                    status.pending_local,
+                    status.pending_remote
+                )),
+                Line::from(tr!("Errors : {}", status.error_count)),
+                Line::from(tr!("Connection : {}", status.connection)),
+                Line::from(tr!("Live messages : {}", status.tlm)),
+            ]
+        }
+        (Panel::Confirmations, Some(job_identifier)) => tui
+            .changes(&job_identifier)
+            .unwrap_or_default()
+            .into_iter()
+            .map(Line::from)
+            .collect(),
+        (Panel::Errors, Some(job_identifier)) => match tui.error(&job_identifier) {
+            Some((message, class, _)) => {
+                let mut lines = vec![
+                    Line::from(tr!("This workspace encountered an error ({}) :", class)),
+                    Line::styled(message, Style::default().fg(Color::Red)),
+                ];
+                if let Some(hint) = class.hint() {
+                    lines.push(Line::from(hint));
+                }
+                if let Some(subject) = tui.subject(&job_identifier) {
+                    if let Some(event) = subject.event {
+                        lines.push(Line::from(tr!("Event : {}", event)));
+                    }
+                    if let Some(path) = subject.path {
+                        lines.push(Line::from(tr!("Path : {}", path.display())));
+                    }
+                }
+                lines
+            }
+            None => vec![],
+        },
+        (_, None) => vec![],
+    };
+    let detail = Paragraph::new(detail)
+        .block(Block::default().borders(Borders::ALL).title(tr!("Detail")))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(detail, columns[1]);
+
+    let footer = match tui.message() {
+        Some(message) => Line::styled(message.clone(), Style::default().fg(Color::Yellow)),
+        None => Line::from(help(&tui.panel())),
+    };
+    frame.render_widget(Paragraph::new(footer), rows[2]);
+}
+
+fn panel_title(panel: &Panel) -> &'static str {
+    match panel {
+        Panel::Jobs => tr!("Workspaces"),
+        Panel::Confirmations => tr!("Confirmations"),
+        Panel::Errors => tr!("Errors"),
+    }
+}
+
+fn help(panel: &Panel) -> &'static str {
+    match panel {
+        Panel::Jobs => tr!("q: quit  tab: panel  ↑↓: select  p: pause/resume  s: resynchronize"),
+        Panel::Confirmations => tr!("q: quit  tab: panel  ↑↓: select  a: accept  r: refuse"),
+        Panel::Errors => tr!(
+            "q: quit  tab: panel  ↑↓: select  r: restart  i: ignore content  s: skip event  p: pause  x: reset  d: redownload  g: ignore path  o: open folder"
+        ),
+    }
+}
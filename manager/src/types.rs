@@ -1,21 +1,31 @@
-use trsync_core::instance::WorkspaceId;
+use std::{
+    path::PathBuf,
+    sync::{atomic::AtomicBool, Arc},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use trsync_core::{
+    instance::{InstanceId, WorkspaceId},
+    job::JobIdentifier,
+};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct TrsyncUid {
-    instance_address: String,
+    instance_id: InstanceId,
     workspace_id: WorkspaceId,
 }
 
 impl TrsyncUid {
-    pub fn new(instance_address: String, workspace_id: WorkspaceId) -> Self {
+    pub fn new(instance_id: InstanceId, workspace_id: WorkspaceId) -> Self {
         Self {
-            instance_address,
+            instance_id,
             workspace_id,
         }
     }
 
-    pub fn instance_address(&self) -> &str {
-        &self.instance_address
+    pub fn instance_id(&self) -> &InstanceId {
+        &self.instance_id
     }
 
     pub fn workspace_id(&self) -> &WorkspaceId {
@@ -25,6 +35,179 @@ impl TrsyncUid {
 
 impl std::fmt::Display for TrsyncUid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}::{}", self.instance_address, self.workspace_id)
+        write!(f, "{}::{}", self.instance_id, self.workspace_id)
+    }
+}
+
+/// Controls of a running trsync process
+pub struct Process {
+    job_identifier: JobIdentifier,
+    folder: PathBuf,
+    folder_path_override: Option<String>,
+    stop_signal: Arc<AtomicBool>,
+    pause_signal: Arc<AtomicBool>,
+    resync_signal: Arc<AtomicBool>,
+    /// Remove job from activities when it is finished (workspace not synchronized anymore)
+    forget_signal: Arc<AtomicBool>,
+    handle: JoinHandle<anyhow::Result<()>>,
+}
+
+impl Process {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        job_identifier: JobIdentifier,
+        folder: PathBuf,
+        folder_path_override: Option<String>,
+        stop_signal: Arc<AtomicBool>,
+        pause_signal: Arc<AtomicBool>,
+        resync_signal: Arc<AtomicBool>,
+        forget_signal: Arc<AtomicBool>,
+        handle: JoinHandle<anyhow::Result<()>>,
+    ) -> Self {
+        Self {
+            job_identifier,
+            folder,
+            folder_path_override,
+            stop_signal,
+            pause_signal,
+            resync_signal,
+            forget_signal,
+            handle,
+        }
+    }
+
+    pub fn job_identifier(&self) -> &JobIdentifier {
+        &self.job_identifier
+    }
+
+    pub fn folder(&self) -> &PathBuf {
+        &self.folder
+    }
+
+    pub fn folder_path_override(&self) -> &Option<String> {
+        &self.folder_path_override
+    }
+
+    pub fn stop_signal(&self) -> &Arc<AtomicBool> {
+        &self.stop_signal
+    }
+
+    pub fn pause_signal(&self) -> &Arc<AtomicBool> {
+        &self.pause_signal
+    }
+
+    pub fn resync_signal(&self) -> &Arc<AtomicBool> {
+        &self.resync_signal
+    }
+
+    pub fn forget_signal(&self) -> &Arc<AtomicBool> {
+        &self.forget_signal
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
+
+const RETRY_MIN_DELAY: Duration = Duration::from_secs(10);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(600);
+
+/// Retries with exponentially growing delay (unreachable instance, crashed process)
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    failures: u32,
+    next_attempt: Instant,
+    min_delay: Duration,
+    max_delay: Duration,
+}
+
+impl Backoff {
+    pub fn new() -> Self {
+        Self::with_delays(RETRY_MIN_DELAY, RETRY_MAX_DELAY)
+    }
+
+    pub fn with_delays(min_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            failures: 0,
+            next_attempt: Instant::now(),
+            min_delay,
+            max_delay,
+        }
+    }
+
+    /// Record a failed attempt and schedule the next one, return delay before it
+    pub fn failed(&mut self) -> Duration {
+        let delay = self
+            .min_delay
+            .saturating_mul(2u32.saturating_pow(self.failures))
+            .min(self.max_delay);
+        self.failures = self.failures.saturating_add(1);
+        self.next_attempt = Instant::now() + delay;
+        delay
+    }
+
+    /// Forget previous failures after a successful attempt
+    pub fn succeeded(&mut self) {
+        self.failures = 0;
+        self.next_attempt = Instant::now();
+    }
+
+    pub fn is_failing(&self) -> bool {
+        self.failures > 0
+    }
+
+    pub fn is_due(&self) -> bool {
+        Instant::now() >= self.next_attempt
+    }
+
+    pub fn next_attempt(&self) -> Instant {
+        self.next_attempt
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_until_max() {
+        // Given
+        let mut backoff = Backoff::new();
+        assert!(backoff.is_due());
+
+        // When
+        let delays: Vec<Duration> = (0..10).map(|_| backoff.failed()).collect();
+
+        // Then
+        assert_eq!(delays[0], RETRY_MIN_DELAY);
+        assert_eq!(delays[1], RETRY_MIN_DELAY * 2);
+        assert_eq!(delays[2], RETRY_MIN_DELAY * 4);
+        assert_eq!(delays[9], RETRY_MAX_DELAY);
+        assert!(delays.iter().all(|delay| delay <= &RETRY_MAX_DELAY));
+        assert!(!backoff.is_due());
+        assert!(backoff.next_attempt() > Instant::now() + RETRY_MAX_DELAY / 2);
+    }
+
+    #[test]
+    fn test_backoff_reset() {
+        // Given
+        let mut backoff = Backoff::new();
+        backoff.failed();
+        backoff.failed();
+
+        // When
+        backoff.succeeded();
+
+        // Then
+        assert!(backoff.is_due());
+        assert!(!backoff.is_failing());
+        assert_eq!(backoff.failed(), RETRY_MIN_DELAY);
+        assert!(backoff.is_failing());
     }
 }
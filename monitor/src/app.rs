@@ -1,42 +1,55 @@
 use std::{
     fmt::{Display, Write},
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
+    thread,
     time::{Duration, Instant},
 };
 
 use anyhow::Result;
-use crossbeam_channel::Receiver;
+use crossbeam_channel::{bounded, Receiver, Sender, TryRecvError};
 use eframe::{
     egui::{self, CentralPanel, Context as EguiContext, RichText, Ui},
     epaint::Color32,
 };
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
+use trsync::{
+    database::connection,
+    history::{self, HistoryEntry},
+};
 use trsync_core::{
     activity::ActivityState,
     error::{Decision, ErrorExchanger, OperatorError, RunnerError, StateError},
     job::JobIdentifier,
     sync::SyncExchanger,
+    tr,
     user::{MonitorWindowPanel, UserRequest},
+    utils::open_folder,
 };
+use trsync_manager::message::DaemonMessage;
 
 use crate::event::Event;
 
 const PIXELS_PER_POINT: f32 = 1.25;
+const HISTORY_LIMIT: usize = 200;
+const HISTORY_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
 
 #[derive(EnumIter, Eq, PartialEq)]
 pub enum Panel {
     Root,
     StartupSynchronizations,
     Errors,
+    History,
 }
 
 impl Display for Panel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Panel::Root => f.write_str("Moniteur"),
-            Panel::StartupSynchronizations => f.write_str("Synchronizations"),
-            Panel::Errors => f.write_str("Erreurs"),
+            Panel::Root => f.write_str(tr!("Monitor")),
+            Panel::StartupSynchronizations => f.write_str(tr!("Synchronizations")),
+            Panel::Errors => f.write_str(tr!("Errors")),
+            Panel::History => f.write_str(tr!("History")),
         }
     }
 }
@@ -77,9 +90,15 @@ pub struct App {
     user_request_receiver: Receiver<UserRequest>,
     sync_exchanger: Arc<Mutex<SyncExchanger>>,
     error_exchanger: Arc<Mutex<ErrorExchanger>>,
+    main_sender: Sender<DaemonMessage>,
     current_panel: Panel,
     current_sync_space: Option<JobIdentifier>,
     current_error_space: Option<JobIdentifier>,
+    current_history_space: Option<JobIdentifier>,
+    history: Vec<HistoryEntry>,
+    history_read: Option<(JobIdentifier, Instant)>,
+    /// History being read in background, to not block the window
+    history_receiver: Option<Receiver<(JobIdentifier, Vec<HistoryEntry>)>>,
     blinking_char: BlinkingChar,
     last_blinking: Instant,
 }
@@ -94,6 +113,8 @@ impl eframe::App for App {
 
         self.update_synchronizations_combo_box_default_value();
         self.update_error_combo_box_default_value();
+        self.update_history_combo_box_default_value();
+        self.update_history();
         self.update_blinking_char();
         self.update_error_space_seen();
 
@@ -118,6 +139,7 @@ impl App {
         user_request_receiver: Receiver<UserRequest>,
         sync_exchanger: Arc<Mutex<SyncExchanger>>,
         error_exchanger: Arc<Mutex<ErrorExchanger>>,
+        main_sender: Sender<DaemonMessage>,
         panel: MonitorWindowPanel,
     ) -> Self {
         Self {
@@ -125,9 +147,14 @@ impl App {
             user_request_receiver,
             sync_exchanger,
             error_exchanger,
+            main_sender,
             current_panel: panel.into(),
             current_sync_space: None,
             current_error_space: None,
+            current_history_space: None,
+            history: vec![],
+            history_read: None,
+            history_receiver: None,
             blinking_char: BlinkingChar::Off,
             last_blinking: Instant::now(),
         }
@@ -153,6 +180,61 @@ impl App {
         }
     }
 
+    fn update_history_combo_box_default_value(&mut self) {
+        if self.current_history_space.is_none() {
+            if let Some(space) = self.spaces().first() {
+                self.current_history_space = Some(space.clone())
+            }
+        }
+    }
+
+    fn update_history(&mut self) {
+        if let Some(history_receiver) = &self.history_receiver {
+            match history_receiver.try_recv() {
+                Ok((space, entries)) => {
+                    if self.current_history_space.as_ref() == Some(&space) {
+                        self.history = entries;
+                    }
+                    self.history_receiver = None;
+                }
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => self.history_receiver = None,
+            }
+        }
+
+        if self.current_panel != Panel::History {
+            return;
+        }
+
+        let space = match &self.current_history_space {
+            Some(space) => space.clone(),
+            None => return,
+        };
+        if let Some((read_space, read_at)) = &self.history_read {
+            if read_space == &space && read_at.elapsed() < HISTORY_REFRESH_INTERVAL {
+                return;
+            }
+        }
+
+        let (history_sender, history_receiver) = bounded(1);
+        let space_ = space.clone();
+        thread::spawn(move || {
+            let entries = match connection(&PathBuf::from(&space_.folder_path))
+                .and_then(|connection| history::entries(&connection, None, Some(HISTORY_LIMIT)))
+            {
+                Ok(entries) => entries,
+                Err(error) => {
+                    log::error!("Unable to read history of {} : {:#}", space_, error);
+                    vec![]
+                }
+            };
+            // Window can be closed meanwhile
+            history_sender.send((space_, entries)).unwrap_or(());
+        });
+        self.history_receiver = Some(history_receiver);
+        self.history_read = Some((space, Instant::now()));
+    }
+
     fn header(&mut self, ui: &mut Ui) -> Vec<Event> {
         ui.horizontal_wrapped(|ui| {
             for panel in Panel::iter() {
@@ -185,6 +267,7 @@ impl App {
             Panel::Root => self.root_body(ui),
             Panel::StartupSynchronizations => self.synchronizations_body(ui),
             Panel::Errors => self.errors_body(ui),
+            Panel::History => self.history_body(ui),
         }
 
         vec![]
@@ -193,9 +276,9 @@ impl App {
     fn root_body(&self, ui: &mut Ui) {
         let activity_state = self.activity_state.lock().unwrap();
 
-        ui.label("État par espaces");
+        ui.label(tr!("State by workspace"));
         egui::Grid::new("instances_states")
-            .num_columns(3)
+            .num_columns(5)
             .spacing([40.0, 4.0])
             .striped(true)
             .show(ui, |ui| {
@@ -203,11 +286,44 @@ impl App {
                     ui.label(&job_identifier.instance_name);
                     ui.label(&job_identifier.workspace_name);
                     ui.label(activity.to_string());
+                    let status = activity_state.status(job_identifier);
+                    if status.error_count > 0 || status.is_disconnected() {
+                        ui.label(RichText::new(status.to_string()).color(Color32::RED));
+                    } else {
+                        ui.label(status.to_string());
+                    }
+                    let message = if activity.is_paused() {
+                        ui.button(tr!("Resume"))
+                            .clicked()
+                            .then(|| DaemonMessage::Resume(job_identifier.clone()))
+                    } else {
+                        ui.button(tr!("Pause"))
+                            .clicked()
+                            .then(|| DaemonMessage::Pause(job_identifier.clone()))
+                    };
+                    if let Some(message) = message {
+                        if self.main_sender.send(message).is_err() {
+                            log::error!("Unable to send pause message for {}", job_identifier)
+                        }
+                    }
                     ui.end_row();
                 }
             });
     }
 
+    fn spaces(&self) -> Vec<JobIdentifier> {
+        let mut spaces: Vec<JobIdentifier> = self
+            .activity_state
+            .lock()
+            .unwrap()
+            .activities()
+            .keys()
+            .cloned()
+            .collect();
+        spaces.sort_by_key(|space| space.to_string());
+        spaces
+    }
+
     fn waiting_spaces(&self) -> Vec<JobIdentifier> {
         let binding = self.sync_exchanger.lock().unwrap();
         let channels = binding.channels();
@@ -258,7 +374,7 @@ impl App {
     fn synchronizations_combo_box(&mut self, ui: &mut Ui) {
         let waiting_spaces = self.waiting_spaces();
 
-        egui::ComboBox::from_label("Espaces en attente de confirmation")
+        egui::ComboBox::from_label(tr!("Workspaces waiting confirmation"))
             .selected_text(
                 self.current_sync_space
                     .as_ref()
@@ -287,9 +403,9 @@ impl App {
             if let Some(sync_channels) = channels.get(waiting_space) {
                 let mut changes = sync_channels.changes().lock().unwrap();
 
-                ui.label(
-                    "La synchronisation de départ de cet espace inclura les changements suivants :",
-                );
+                ui.label(tr!(
+                    "Startup synchronization of this workspace will include following changes :"
+                ));
 
                 egui::ScrollArea::both().show(ui, |ui| {
                     egui::Grid::new("instances_states")
@@ -312,7 +428,7 @@ impl App {
                     });
 
                     ui.horizontal_wrapped(|ui| {
-                        if changes.is_some() && ui.button("Refuser").clicked() {
+                        if changes.is_some() && ui.button(tr!("Refuse")).clicked() {
                             *changes = None;
                             answered = true;
                             if sync_channels.confirm_sync_sender().send(false).is_err() {
@@ -324,7 +440,7 @@ impl App {
                             };
                         };
 
-                        if changes.is_some() && ui.button("Accepter").clicked() {
+                        if changes.is_some() && ui.button(tr!("Accept")).clicked() {
                             *changes = None;
                             answered = true;
                             if sync_channels.confirm_sync_sender().send(true).is_err() {
@@ -354,7 +470,7 @@ impl App {
     fn errors_combo_box(&mut self, ui: &mut Ui) {
         let error_spaces = self.error_spaces();
 
-        egui::ComboBox::from_label("Espaces en erreur")
+        egui::ComboBox::from_label(tr!("Workspaces in error"))
             .selected_text(
                 self.current_error_space
                     .as_ref()
@@ -380,56 +496,181 @@ impl App {
 
         if let Some(error_space) = &self.current_error_space {
             if let Some(sync_channels) = channels.get(error_space) {
-                let mut answered = false;
+                let mut decision = None;
                 let mut error_guard = sync_channels.error().lock().unwrap();
+                let subject = sync_channels
+                    .subject()
+                    .lock()
+                    .unwrap()
+                    .clone()
+                    .unwrap_or_default();
 
                 if let Some(error) = (*error_guard).as_ref() {
                     let message = match error {
                         RunnerError::OperatorError(OperatorError::StateError(
                             StateError::PathAlreadyExist(path, _),
-                        )) => format!("Un contenu semble dupliqué : '{}'", path.display()),
+                        )) => tr!("A content seems duplicated : '{}'", path.display()),
                         _ => error.to_string(),
                     };
 
-                    ui.label("Cet espace de travail à rencontré une erreur :");
+                    ui.label(tr!(
+                        "This workspace encountered an error ({}) :",
+                        error.class()
+                    ));
                     ui.label(RichText::new(message).color(Color32::RED));
+                    if let Some(hint) = error.class().hint() {
+                        ui.label(hint);
+                    }
+                    if let Some(event) = &subject.event {
+                        ui.label(tr!("Event : {}", event));
+                    }
+                    if let Some(path) = &subject.path {
+                        ui.label(tr!("Path : {}", path.display()));
+                    }
                     ui.horizontal_wrapped(|ui| {
                         if let RunnerError::OperatorError(OperatorError::StateError(
                             StateError::PathAlreadyExist(_, content_id),
                         )) = error
                         {
                             if ui
-                                .button("Ignorer à l'avenir & Redémarrer la synchronisation")
+                                .button(tr!("Ignore in the future & Restart synchronization"))
                                 .clicked()
                             {
-                                answered = true;
-                                if sync_channels
-                                    .decision_sender()
-                                    .send(Decision::IgnoreAndRestartSpaceSync(*content_id))
-                                    .is_err()
-                                {
-                                    print!("Unable to send decision to {}", error_space)
-                                }
+                                decision = Some(Decision::IgnoreAndRestartSpaceSync(*content_id));
                             }
                         }
 
-                        if ui.button("Redémarrer la synchronisation").clicked() {
-                            answered = true;
-                            if sync_channels
-                                .decision_sender()
-                                .send(Decision::RestartSpaceSync)
-                                .is_err()
-                            {
-                                print!("Unable to send decision to {}", error_space)
+                        if subject.event.is_some()
+                            && ui
+                                .button(tr!("Skip this event & Restart synchronization"))
+                                .clicked()
+                        {
+                            decision = Some(Decision::SkipEventAndRestartSpaceSync);
+                        }
+
+                        if let Some(content_id) = subject.content_id {
+                            if ui.button(tr!("Redownload this content")).clicked() {
+                                decision =
+                                    Some(Decision::RedownloadAndRestartSpaceSync(content_id));
+                            }
+                        }
+
+                        if let Some(path) = &subject.path {
+                            if ui.button(tr!("Ignore this path in the future")).clicked() {
+                                decision =
+                                    Some(Decision::IgnorePathAndRestartSpaceSync(path.clone()));
+                            }
+                        }
+
+                        if ui.button(tr!("Reset local database & Reindex")).clicked() {
+                            decision = Some(Decision::ResetAndRestartSpaceSync);
+                        }
+
+                        if ui.button(tr!("Pause synchronization")).clicked() {
+                            decision = Some(Decision::PauseSpaceSync);
+                        }
+
+                        if ui.button(tr!("Open folder")).clicked() {
+                            let workspace_folder = Path::new(&error_space.folder_path);
+                            let folder = subject
+                                .path
+                                .as_ref()
+                                .and_then(|path| {
+                                    workspace_folder.join(path).parent().map(Path::to_path_buf)
+                                })
+                                .unwrap_or(workspace_folder.to_path_buf());
+                            if let Err(error) = open_folder(&folder) {
+                                log::error!(
+                                    "Unable to open folder {} : {}",
+                                    folder.display(),
+                                    error
+                                )
                             }
                         }
+
+                        if ui.button(tr!("Restart synchronization")).clicked() {
+                            decision = Some(Decision::RestartSpaceSync);
+                        }
                     });
                 }
-                if answered {
+                if let Some(decision) = decision {
+                    if sync_channels.decision_sender().send(decision).is_err() {
+                        log::error!("Unable to send decision to {}", error_space)
+                    }
                     self.current_error_space = None;
                     *error_guard = None;
                 }
             }
         }
     }
+
+    fn history_body(&mut self, ui: &mut Ui) {
+        self.history_combo_box(ui);
+        self.history_display(ui);
+    }
+
+    fn history_combo_box(&mut self, ui: &mut Ui) {
+        let spaces = self.spaces();
+
+        egui::ComboBox::from_label(tr!("Workspace"))
+            .selected_text(
+                self.current_history_space
+                    .as_ref()
+                    .map(|x| x.to_string())
+                    .unwrap_or("".to_string()),
+            )
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap = Some(false);
+                ui.set_min_width(120.0);
+                for space in spaces {
+                    ui.selectable_value(
+                        &mut self.current_history_space,
+                        Some(space.clone()),
+                        space.to_string(),
+                    );
+                }
+            });
+    }
+
+    fn history_display(&self, ui: &mut Ui) {
+        if self.history.is_empty() {
+            ui.label(tr!("No operation recorded for this workspace"));
+            return;
+        }
+
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("history")
+                .num_columns(5)
+                .spacing([20.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for entry in &self.history {
+                        ui.label(entry.datetime());
+                        ui.label(entry.direction.to_string());
+                        ui.label(
+                            entry
+                                .path
+                                .as_ref()
+                                .map(|path| path.display().to_string())
+                                .unwrap_or("?".to_string()),
+                        );
+                        ui.label(&entry.executor);
+                        match &entry.error {
+                            Some(error) => {
+                                ui.label(RichText::new(error).color(Color32::RED));
+                            }
+                            None => {
+                                ui.label(
+                                    entry
+                                        .revision_id
+                                        .map(|revision_id| tr!("revision {}", revision_id))
+                                        .unwrap_or_default(),
+                                );
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+    }
 }
@@ -2,7 +2,7 @@ use std::sync::{Arc, Mutex};
 
 use anyhow::{bail, Result};
 
-use crossbeam_channel::Receiver;
+use crossbeam_channel::{Receiver, Sender};
 use eframe::epaint::vec2;
 use trsync_core::{
     activity::ActivityState,
@@ -11,6 +11,8 @@ use trsync_core::{
     user::{MonitorWindowPanel, UserRequest},
 };
 
+use trsync_manager::message::DaemonMessage;
+
 use crate::app::App;
 
 pub fn run(
@@ -18,6 +20,7 @@ pub fn run(
     user_request_receiver: Receiver<UserRequest>,
     sync_exchanger: Arc<Mutex<SyncExchanger>>,
     error_exchanger: Arc<Mutex<ErrorExchanger>>,
+    main_sender: Sender<DaemonMessage>,
     panel: MonitorWindowPanel,
 ) -> Result<()> {
     let options = eframe::NativeOptions {
@@ -30,6 +33,7 @@ pub fn run(
         user_request_receiver,
         sync_exchanger,
         error_exchanger,
+        main_sender,
         panel,
     );
     app.start()?;
@@ -7,17 +7,22 @@ use std::{
 };
 
 use crossbeam_channel::Sender;
+use gtk::prelude::*;
+use libappindicator::{AppIndicator, AppIndicatorStatus};
 use trsync_core::{
     activity::ActivityState,
     error::ErrorExchanger,
     sync::SyncExchanger,
+    tr,
     user::{MonitorWindowPanel, UserRequest},
 };
 use trsync_manager::message::DaemonMessage;
 
-use tray_item::TrayItem;
-
-use crate::{config::Config, icon::Icon};
+use crate::{
+    config::Config,
+    icon::Icon,
+    workspace::{self, WorkspaceItems},
+};
 
 pub fn run_tray(
     config: Config,
@@ -37,16 +42,15 @@ pub fn run_tray(
     // Icon
     let mut current_icon = Icon::Idle;
     let mut tray = match current_icon.value(&config).to_str() {
-        Some(icon_value) => match TrayItem::new("Tracim", icon_value) {
-            Ok(tray_) => tray_,
-            Err(error) => return Err(format!("Unable to create tray item : '{}'", error)),
-        },
+        Some(icon_value) => AppIndicator::new("Tracim", icon_value),
         None => return Err("Unable to get icon value".to_string()),
     };
+    tray.set_status(AppIndicatorStatus::Active);
+    let mut menu = gtk::Menu::new();
 
     // Monitor item
     let window_sender_ = user_request_sender.clone();
-    if let Err(error) = tray.add_menu_item("Moniteur", move || {
+    add_menu_item(&menu, tr!("Monitor"), move || {
         log::info!("Request monitor window open");
         if window_sender_
             .send(UserRequest::OpenMonitorWindow(MonitorWindowPanel::Root))
@@ -54,13 +58,11 @@ pub fn run_tray(
         {
             log::error!("Unable to send monitor window open request")
         }
-    }) {
-        return Err(format!("Unable to add menu item : '{:?}'", error));
-    };
+    });
 
     // Configure item
     let window_sender_ = user_request_sender.clone();
-    if let Err(error) = tray.add_menu_item("Configurer", move || {
+    add_menu_item(&menu, tr!("Configure"), move || {
         log::info!("Request configure window open");
         if window_sender_
             .send(UserRequest::OpenConfigurationWindow)
@@ -68,32 +70,75 @@ pub fn run_tray(
         {
             log::error!("Unable to send configure window open request")
         }
-    }) {
-        return Err(format!("Unable to add menu item : '{:?}'", error));
-    };
+    });
+
+    // Pause and resume items
+    for (label, pause) in [(tr!("Pause all"), true), (tr!("Resume all"), false)] {
+        let main_sender_ = main_sender.clone();
+        let activity_state_ = activity_state.clone();
+        add_menu_item(&menu, label, move || {
+            let job_identifiers: Vec<_> = activity_state_
+                .lock()
+                .unwrap()
+                .activities()
+                .keys()
+                .cloned()
+                .collect();
+            for job_identifier in job_identifiers {
+                workspace::send_pause(&main_sender_, job_identifier, pause)
+            }
+        });
+    }
+
+    // Pause and resume items of each workspace, filled when workspaces are known
+    let workspaces_menu = gtk::Menu::new();
+    let workspaces_item = gtk::MenuItem::with_label(tr!("Workspaces"));
+    workspaces_item.set_submenu(Some(&workspaces_menu));
+    menu.append(&workspaces_item);
 
     // Quit item
     let menu_stop_signal = stop_signal.clone();
     let main_sender_ = main_sender_quit.clone();
     let window_sender_ = user_request_sender.clone();
-    if let Err(error) = tray.add_menu_item("Quitter", move || {
+    add_menu_item(&menu, tr!("Quit"), move || {
         main_sender_.send(DaemonMessage::Stop).unwrap_or(());
         menu_stop_signal.store(true, Ordering::Relaxed);
         if window_sender_.send(UserRequest::Quit).is_err() {
             log::error!("Unable to send exit request")
         }
         gtk::main_quit();
-    }) {
-        return Err(format!("Unable to add menu item : '{:?}'", error));
-    };
+    });
+
+    menu.show_all();
+    workspaces_item.hide();
+    tray.set_menu(&mut menu);
 
     let glib_stop_signal = stop_signal.clone();
     let activity_state_ = activity_state.clone();
+    let mut workspace_items = WorkspaceItems::default();
     glib::timeout_add_local(Duration::from_millis(250), move || {
         if glib_stop_signal.load(Ordering::Relaxed) {
             return glib::Continue(false);
         }
 
+        // Pause and resume items of each workspace
+        if let Some(job_identifiers) = workspace_items.changed(&activity_state_) {
+            for item in workspaces_menu.children() {
+                workspaces_menu.remove(&item);
+            }
+            for job_identifier in &job_identifiers {
+                for (label, pause) in workspace::items(job_identifier) {
+                    let main_sender_ = main_sender.clone();
+                    let job_identifier_ = job_identifier.clone();
+                    add_menu_item(&workspaces_menu, &label, move || {
+                        workspace::send_pause(&main_sender_, job_identifier_.clone(), pause)
+                    });
+                }
+            }
+            workspaces_menu.show_all();
+            workspaces_item.set_visible(!job_identifiers.is_empty());
+        }
+
         let activity_icon = {
             let is_waiting_spaces = sync_exchanger
                 .lock()
@@ -132,6 +177,8 @@ pub fn run_tray(
                     Icon::Working8 => Icon::Working1,
                     _ => Icon::Working1,
                 }
+            } else if activity_state_.lock().unwrap().is_disconnected() {
+                Icon::Error
             } else {
                 Icon::Idle
             }
@@ -142,9 +189,7 @@ pub fn run_tray(
             match current_icon.value(&config).to_str() {
                 Some(icon_value) => {
                     log::debug!("Set icon to {}", icon_value);
-                    if let Err(error) = tray.set_icon(icon_value) {
-                        log::error!("Unable to set icon : '{:?}'", error);
-                    };
+                    tray.set_icon(icon_value);
                 }
                 None => {
                     log::error!("Unable to get icon value");
@@ -158,3 +203,9 @@ pub fn run_tray(
     gtk::main();
     Ok(())
 }
+
+fn add_menu_item<F: Fn() + 'static>(menu: &gtk::Menu, label: &str, callback: F) {
+    let item = gtk::MenuItem::with_label(label);
+    item.connect_activate(move |_| callback());
+    menu.append(&item);
+}
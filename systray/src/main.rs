@@ -19,8 +19,10 @@ use trsync_core::{
     config::ManagerConfig,
     error::ErrorExchanger,
     sync::SyncExchanger,
-    user::UserRequest,
+    user::{MonitorWindowPanel, UserRequest},
 };
+#[cfg(unix)]
+use trsync_manager::socket::{server::SocketServer, socket_path};
 use trsync_manager::{self, daemon::Daemon, message::DaemonMessage};
 use trsync_manager_configure::run::run as run_configure;
 use trsync_manager_monitor::run::run as run_monitor;
@@ -34,6 +36,7 @@ mod windows;
 mod config;
 mod error;
 mod icon;
+mod workspace;
 
 type DaemonMessageChannels = (Sender<DaemonMessage>, Receiver<DaemonMessage>);
 type ActivityChannels = (Sender<WrappedActivity>, Receiver<WrappedActivity>);
@@ -54,11 +57,14 @@ fn run() -> Result<()> {
     log::info!("Start manager");
     let (main_sender, main_receiver): DaemonMessageChannels = unbounded();
     let (activity_sender, activity_receiver): ActivityChannels = unbounded();
+    #[cfg(unix)]
+    let control_socket = manager_config.control_socket.clone();
     let sync_exchanger_ = sync_exchanger.clone();
     let error_exchanger_ = error_exchanger.clone();
     Daemon::new(
         manager_config,
         main_receiver,
+        main_sender.clone(),
         activity_sender,
         user_request_sender.clone(),
         sync_exchanger_,
@@ -72,6 +78,19 @@ fn run() -> Result<()> {
     let stop_signal_ = stop_signal.clone();
     ActivityMonitor::new(activity_receiver_, activity_state_, stop_signal_).start();
 
+    // Start control socket
+    #[cfg(unix)]
+    SocketServer::new(
+        socket_path(control_socket.as_deref())?,
+        main_sender.clone(),
+        activity_state.clone(),
+        sync_exchanger.clone(),
+        error_exchanger.clone(),
+        || ManagerConfig::from_env(false),
+        stop_signal.clone(),
+    )
+    .start()?;
+
     // Systray
     let activity_state_ = activity_state.clone();
     let stop_signal_ = stop_signal.clone();
@@ -134,6 +153,7 @@ fn run() -> Result<()> {
                         user_request_receiver.clone(),
                         sync_exchanger.clone(),
                         error_exchanger.clone(),
+                        main_sender_.clone(),
                         panel,
                     ) {
                         log::error!("Unable to run configure window : '{}'", error)
@@ -146,6 +166,23 @@ fn run() -> Result<()> {
                         log::error!("Unable to run configure window : '{}'", error)
                     }
                 }
+                UserRequest::WorkspaceAccessLost(instance_name, workspace_id) => {
+                    log::warn!(
+                        "Workspace {} of '{}' is not accessible anymore",
+                        workspace_id,
+                        instance_name
+                    );
+                    if let Err(error) = run_monitor(
+                        activity_state_.clone(),
+                        user_request_receiver.clone(),
+                        sync_exchanger.clone(),
+                        error_exchanger.clone(),
+                        main_sender_.clone(),
+                        MonitorWindowPanel::Root,
+                    ) {
+                        log::error!("Unable to run monitor window : '{}'", error)
+                    }
+                }
                 UserRequest::Quit => break,
             },
         }
@@ -1,17 +1,20 @@
+use crossbeam_channel::Sender;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::RecvTimeoutError;
 use std::sync::{mpsc, Arc, Mutex};
 use std::time::Duration;
-use crossbeam_channel::Sender;
 use tray_item::TrayItem;
 
 use trsync_core::activity::ActivityState;
 use trsync_core::error::ErrorExchanger;
+use trsync_core::job::JobIdentifier;
 use trsync_core::sync::SyncExchanger;
+use trsync_core::tr;
 use trsync_core::user::{MonitorWindowPanel, UserRequest};
 use trsync_manager::message::DaemonMessage;
 
 use crate::icon::Icon;
+use crate::workspace::{self, WorkspaceItems};
 
 enum Message {
     Quit,
@@ -27,55 +30,17 @@ pub fn run_tray(
 ) -> Result<(), String> {
     let mut current_icon = Icon::Idle;
     let activity_state_ = activity_state.clone();
-    let main_sender_quit = main_sender.clone();
-
-    // Icon
-    let mut tray = match TrayItem::new("Tracim", "trsync_idle") {
-        Ok(tray_) => tray_,
-        Err(error) => return Err(format!("Unable to create tray item : '{}'", error)),
-    };
-
-    // Monitor item
-    let window_sender_ = user_request_sender.clone();
-    if let Err(error) = tray.add_menu_item("Moniteur", move || {
-        log::info!("Request monitor window open");
-        if window_sender_
-            .send(UserRequest::OpenMonitorWindow(MonitorWindowPanel::Root))
-            .is_err()
-        {
-            log::error!("Unable to send monitor window open request")
-        }
-    }) {
-        return Err(format!("Unable to add menu item : '{:?}'", error));
-    };
-
-    // Configure item
-    let window_sender_ = user_request_sender.clone();
-    if let Err(error) = tray.add_menu_item("Configurer", move || {
-        log::info!("Request configure window open");
-        if window_sender_
-            .send(UserRequest::OpenConfigurationWindow)
-            .is_err()
-        {
-            log::error!("Unable to send configure window open request")
-        }
-    }) {
-        return Err(format!("Unable to add menu item : '{:?}'", error));
-    };
-
-    // Quit item
     let (tx, rx) = mpsc::channel();
-    let menu_stop_signal = stop_signal.clone();
-    let main_sender_ = main_sender_quit.clone();
-    let window_sender_ = user_request_sender.clone();
-    if let Err(error) = tray.add_menu_item("Quitter", move || {
-        main_sender_.send(DaemonMessage::Stop).unwrap_or(());
-        tx.send(Message::Quit)
-            .expect("This channel must not been closed");
-    }) {
-        return Err(format!("Unable to add menu item : '{:?}'", error));
-    };
+    let mut tray = build_tray(
+        &current_icon,
+        &[],
+        &main_sender,
+        &activity_state,
+        &user_request_sender,
+        &tx,
+    )?;
 
+    let mut workspace_items = WorkspaceItems::default();
     loop {
         match rx.recv_timeout(Duration::from_millis(250)) {
             Err(RecvTimeoutError::Disconnected) => {
@@ -87,6 +52,22 @@ pub fn run_tray(
                     break;
                 }
 
+                // Menu can't be modified, so it is rebuilt with pause and resume items of
+                // each workspace
+                if let Some(job_identifiers) = workspace_items.changed(&activity_state_) {
+                    match build_tray(
+                        &current_icon,
+                        &job_identifiers,
+                        &main_sender,
+                        &activity_state,
+                        &user_request_sender,
+                        &tx,
+                    ) {
+                        Ok(tray_) => tray = tray_,
+                        Err(error) => log::error!("Unable to rebuild tray : {}", error),
+                    }
+                }
+
                 let activity_icon = {
                     let is_waiting_spaces = sync_exchanger
                         .lock()
@@ -128,6 +109,8 @@ pub fn run_tray(
                             Icon::Working8 => Icon::Working1,
                             _ => Icon::Working1,
                         }
+                    } else if activity_state_.lock().unwrap().is_disconnected() {
+                        Icon::Error
                     } else {
                         Icon::Idle
                     }
@@ -148,3 +131,93 @@ pub fn run_tray(
 
     Ok(())
 }
+
+/// Tray icon with its menu, pause and resume items of given workspaces are before quit item
+fn build_tray(
+    icon: &Icon,
+    job_identifiers: &[JobIdentifier],
+    main_sender: &Sender<DaemonMessage>,
+    activity_state: &Arc<Mutex<ActivityState>>,
+    user_request_sender: &Sender<UserRequest>,
+    tx: &mpsc::Sender<Message>,
+) -> Result<TrayItem, String> {
+    // Icon
+    let mut tray = match TrayItem::new("Tracim", &icon.value()) {
+        Ok(tray_) => tray_,
+        Err(error) => return Err(format!("Unable to create tray item : '{}'", error)),
+    };
+
+    // Monitor item
+    let window_sender_ = user_request_sender.clone();
+    if let Err(error) = tray.add_menu_item(tr!("Monitor"), move || {
+        log::info!("Request monitor window open");
+        if window_sender_
+            .send(UserRequest::OpenMonitorWindow(MonitorWindowPanel::Root))
+            .is_err()
+        {
+            log::error!("Unable to send monitor window open request")
+        }
+    }) {
+        return Err(format!("Unable to add menu item : '{:?}'", error));
+    };
+
+    // Configure item
+    let window_sender_ = user_request_sender.clone();
+    if let Err(error) = tray.add_menu_item(tr!("Configure"), move || {
+        log::info!("Request configure window open");
+        if window_sender_
+            .send(UserRequest::OpenConfigurationWindow)
+            .is_err()
+        {
+            log::error!("Unable to send configure window open request")
+        }
+    }) {
+        return Err(format!("Unable to add menu item : '{:?}'", error));
+    };
+
+    // Pause and resume items
+    for (label, pause) in [(tr!("Pause all"), true), (tr!("Resume all"), false)] {
+        let main_sender_ = main_sender.clone();
+        let activity_state_ = activity_state.clone();
+        if let Err(error) = tray.add_menu_item(label, move || {
+            let job_identifiers: Vec<_> = activity_state_
+                .lock()
+                .unwrap()
+                .activities()
+                .keys()
+                .cloned()
+                .collect();
+            for job_identifier in job_identifiers {
+                workspace::send_pause(&main_sender_, job_identifier, pause)
+            }
+        }) {
+            return Err(format!("Unable to add menu item : '{:?}'", error));
+        };
+    }
+
+    // Pause and resume items of each workspace
+    for job_identifier in job_identifiers {
+        for (label, pause) in workspace::items(job_identifier) {
+            let main_sender_ = main_sender.clone();
+            let job_identifier_ = job_identifier.clone();
+            if let Err(error) = tray.add_menu_item(&label, move || {
+                workspace::send_pause(&main_sender_, job_identifier_.clone(), pause)
+            }) {
+                return Err(format!("Unable to add menu item : '{:?}'", error));
+            };
+        }
+    }
+
+    // Quit item
+    let main_sender_ = main_sender.clone();
+    let tx = tx.clone();
+    if let Err(error) = tray.add_menu_item(tr!("Quit"), move || {
+        main_sender_.send(DaemonMessage::Stop).unwrap_or(());
+        tx.send(Message::Quit)
+            .expect("This channel must not been closed");
+    }) {
+        return Err(format!("Unable to add menu item : '{:?}'", error));
+    };
+
+    Ok(tray)
+}
@@ -0,0 +1,61 @@
+use std::sync::Mutex;
+
+use crossbeam_channel::Sender;
+use trsync_core::{activity::ActivityState, job::JobIdentifier, tr};
+use trsync_manager::message::DaemonMessage;
+
+/// Workspaces displayed in menu. Workspaces are discovered, removed or renamed while
+/// running, so their items are rebuilt when activities jobs change.
+#[derive(Default)]
+pub struct WorkspaceItems {
+    displayed: Vec<(String, i32, String)>,
+}
+
+impl WorkspaceItems {
+    /// Workspaces to display, if they changed since previous call
+    pub fn changed(&mut self, activity_state: &Mutex<ActivityState>) -> Option<Vec<JobIdentifier>> {
+        let mut job_identifiers: Vec<JobIdentifier> = activity_state
+            .lock()
+            .unwrap()
+            .activities()
+            .keys()
+            .cloned()
+            .collect();
+        job_identifiers.sort_by_key(|job_identifier| job_identifier.to_string());
+        let displayed: Vec<(String, i32, String)> = job_identifiers
+            .iter()
+            .map(|job_identifier| {
+                (
+                    job_identifier.instance_name.clone(),
+                    job_identifier.workspace_id,
+                    job_identifier.to_string(),
+                )
+            })
+            .collect();
+        if displayed == self.displayed {
+            return None;
+        }
+
+        self.displayed = displayed;
+        Some(job_identifiers)
+    }
+}
+
+/// Labels of pause and resume items of given workspace, with their pause value
+pub fn items(job_identifier: &JobIdentifier) -> [(String, bool); 2] {
+    [
+        (tr!("Pause {}", job_identifier), true),
+        (tr!("Resume {}", job_identifier), false),
+    ]
+}
+
+pub fn send_pause(main_sender: &Sender<DaemonMessage>, job_identifier: JobIdentifier, pause: bool) {
+    let message = if pause {
+        DaemonMessage::Pause(job_identifier)
+    } else {
+        DaemonMessage::Resume(job_identifier)
+    };
+    if main_sender.send(message).is_err() {
+        log::error!("Unable to send pause message")
+    }
+}
@@ -1,16 +1,57 @@
 [server]
 instances = algoo,bux
 local_folder = /home/<your user>/Tracim
+# Optional workspaces folders layout, relative to local_folder (placeholders : {instance}, {address}, {username}, {label}, {workspace_id})
+# folder_layout = {address}/{label}
+# Optional local folder policy of workspaces removed from configuration : keep, archive or delete (archive and delete are confirmed in configuration window)
+# removed_workspace_policy = keep
 icons_path = </home/<username>/.local/share/icons if install for one or some user only, /usr/share/icons if install for all users>
+# Optional bandwidth limits (KiB/s) shared by all instances
+# max_upload_rate = 512
+# max_download_rate = 2048
+# Optional limit of simultaneous transfers of all workspaces, unlimited by default (or 0)
+# max_parallel_transfers = 2
+# Optional synchronization window (all workspaces). Outside of it, changes are queued and transferred later
+# sync_hours = 08:00-19:00
+# sync_days = mon-fri
+# sync_networks = 192.168.10.0/24
+# Optional control socket path (unix only), default is $XDG_RUNTIME_DIR/trsync.sock or ~/.trsync.sock
+# control_socket = /run/user/1000/trsync.sock
+# Optional, synchronize each workspace in its own trsync process (restarted if it crashes),
+# can't be used with bandwidth limits, max_parallel_transfers or priorities
+# isolated_processes = true
+# Optional trsync executable used by isolated processes, default is the one next to trsync_manager
+# trsync_bin = /usr/local/bin/trsync
 
 [instance.algoo]
 address = algoo.tracim.fr
 username = bux
 unsecure = false
 workspaces_ids = 42,43
+# Optional bandwidth limits (KiB/s) for this instance only
+# max_upload_rate = 256
+
+# Optional workspace specific settings
+[instance.algoo::42]
+# Transfers of higher priority workspaces start first, requires max_parallel_transfers in [server] section
+priority = 10
+# Synchronization window of this workspace, replace global one
+sync_hours = 22:00-06:00
+# Folder of this workspace, replace the one computed from folder_layout (relative to local_folder if not absolute)
+# folder_path = /home/<your user>/Projects/ClientX
 
 [instance.bux]
 address = tracim.bux.fr
 username = bux
 unsecure = false
-workspaces_ids = 24,23
\ No newline at end of file
+# All workspaces user belongs to (joined ones are added automatically), except excluded ones
+workspaces_ids = *
+# excluded_workspaces_ids = 23
+# Optional http transport settings
+# proxy = http://proxy.bux.fr:3128
+# ca_certificates = /etc/ssl/bux/ca.pem
+# client_certificate = /home/<your user>/.bux/cert.pem
+# client_key = /home/<your user>/.bux/key.pem
+# base_path = /tracim/api
+# timeout = 30
+# transfer_timeout = 600
\ No newline at end of file
@@ -1,10 +1,20 @@
-use std::env;
+use std::{env, path::PathBuf, sync::Arc};
 
 use anyhow::Result;
 use env_logger::Env;
 use error::Error;
-use structopt::StructOpt;
-use trsync_core::{control::RemoteControlBuilder, instance::WorkspaceId};
+use structopt::{
+    clap::{Error as ClapError, ErrorKind},
+    StructOpt,
+};
+use trsync_core::{
+    client::DEFAULT_CLIENT_TIMEOUT,
+    control::RemoteControlBuilder,
+    instance::WorkspaceId,
+    schedule::SyncSchedule,
+    transfer::{RateLimiter, TransferControl},
+    transport::{TransportConfig, DEFAULT_BASE_PATH, DEFAULT_TRANSFER_TIMEOUT},
+};
 extern crate notify;
 
 pub mod conflict;
@@ -12,11 +22,13 @@ pub mod context;
 pub mod database;
 pub mod error;
 pub mod event;
+pub mod history;
 pub mod ignore;
 pub mod local;
 pub mod message;
 pub mod operation;
 pub mod path;
+pub mod pipe;
 pub mod remote;
 pub mod run;
 pub mod run2;
@@ -30,17 +42,21 @@ mod tests;
 #[derive(StructOpt, Debug)]
 #[structopt(name = "basic")]
 struct Opt {
+    #[structopt(subcommand)]
+    command: Option<Command>,
+
+    // Positional arguments are required without subcommand (see `sync_arguments`)
     #[structopt(parse(from_os_str))]
-    path: std::path::PathBuf,
+    path: Option<std::path::PathBuf>,
 
     #[structopt(name = "tracim_address")]
-    tracim_address: String,
+    tracim_address: Option<String>,
 
     #[structopt(name = "workspace_id")]
-    workspace_id: i32,
+    workspace_id: Option<i32>,
 
     #[structopt(name = "username")]
-    username: String,
+    username: Option<String>,
 
     #[structopt(name = "--no-ssl", short, long)]
     no_ssl: bool,
@@ -50,28 +66,176 @@ struct Opt {
 
     #[structopt(name = "--exit-after-sync", long)]
     exit_after_sync: bool,
+
+    #[structopt(name = "--proxy", long)]
+    proxy: Option<String>,
+
+    #[structopt(name = "--ca-certificate", long, parse(from_os_str))]
+    ca_certificates: Vec<std::path::PathBuf>,
+
+    #[structopt(name = "--client-certificate", long, parse(from_os_str))]
+    client_certificate: Option<std::path::PathBuf>,
+
+    #[structopt(name = "--client-key", long, parse(from_os_str))]
+    client_key: Option<std::path::PathBuf>,
+
+    #[structopt(name = "--base-path", long, default_value = DEFAULT_BASE_PATH)]
+    base_path: String,
+
+    #[structopt(name = "--timeout", long)]
+    timeout: Option<u64>,
+
+    #[structopt(name = "--transfer-timeout", long)]
+    transfer_timeout: Option<u64>,
+
+    /// Be driven by a parent process (json lines on stdin and stdout)
+    #[structopt(name = "--pipe", long)]
+    pipe: bool,
+
+    /// Wait startup changes confirmation (from parent process)
+    #[structopt(name = "--confirm-startup-sync", long)]
+    confirm_startup_sync: bool,
+
+    #[structopt(name = "--sync-hours", long)]
+    sync_hours: Option<String>,
+
+    #[structopt(name = "--sync-days", long)]
+    sync_days: Option<String>,
+
+    /// Allowed networks (ex. "192.168.10.0/24,10.1.2.3")
+    #[structopt(name = "--sync-networks", long)]
+    sync_networks: Option<String>,
+
+    /// KiB/s
+    #[structopt(name = "--max-upload-rate", long)]
+    max_upload_rate: Option<u64>,
+
+    /// KiB/s
+    #[structopt(name = "--max-download-rate", long)]
+    max_download_rate: Option<u64>,
+}
+
+#[derive(StructOpt, Debug)]
+enum Command {
+    /// Print operations history of given path (current folder by default), which must be
+    /// inside a synchronized folder
+    History {
+        #[structopt(parse(from_os_str))]
+        path: Option<PathBuf>,
+    },
 }
 
 impl Opt {
+    /// Synchronization positional arguments, or exit like clap on missing ones
+    fn sync_arguments(&self) -> (&PathBuf, &String, i32, &String) {
+        match (
+            &self.path,
+            &self.tracim_address,
+            self.workspace_id,
+            &self.username,
+        ) {
+            (Some(path), Some(tracim_address), Some(workspace_id), Some(username)) => {
+                (path, tracim_address, workspace_id, username)
+            }
+            _ => ClapError::with_description(
+                "The following required arguments were not provided: \
+                <path> <tracim_address> <workspace_id> <username>",
+                ErrorKind::MissingRequiredArgument,
+            )
+            .exit(),
+        }
+    }
+
     fn to_context(&self, password: String) -> Result<context::Context, Error> {
+        let (path, tracim_address, workspace_id, username) = self.sync_arguments();
         // TODO: no unwrap ...
-        let workspace_name = self.path.file_name().unwrap().to_str().unwrap().to_string();
+        let workspace_name = path.file_name().unwrap().to_str().unwrap().to_string();
         context::Context::new(
             !self.no_ssl,
-            self.tracim_address.clone(),
-            self.username.clone(),
+            tracim_address.clone(),
+            username.clone(),
             password.clone(),
-            util::canonicalize_to_string(&self.path)?,
-            WorkspaceId(self.workspace_id),
+            util::canonicalize_to_string(path)?,
+            WorkspaceId(workspace_id),
             workspace_name,
             self.exit_after_sync,
+            TransportConfig {
+                proxy: self.proxy.clone(),
+                ca_certificates: self.ca_certificates.clone(),
+                client_certificate: self.client_certificate.clone(),
+                client_key: self.client_key.clone(),
+                base_path: self.base_path.clone(),
+                timeout: self.timeout.unwrap_or(DEFAULT_CLIENT_TIMEOUT),
+                transfer_timeout: self.transfer_timeout.unwrap_or(DEFAULT_TRANSFER_TIMEOUT),
+            },
+        )
+        .and_then(|context| Ok(context.schedule(self.schedule()?).transfer(self.transfer())))
+    }
+
+    fn schedule(&self) -> Result<SyncSchedule, Error> {
+        SyncSchedule::new(
+            self.sync_hours.as_deref().unwrap_or(""),
+            self.sync_days.as_deref().unwrap_or(""),
+            self.sync_networks.as_deref().unwrap_or(""),
         )
+        .map_err(|error| Error::UnexpectedError(format!("Invalid sync window : {}", error)))
+    }
+
+    fn transfer(&self) -> TransferControl {
+        TransferControl::default()
+            .upload_limiter(Arc::new(RateLimiter::new(self.max_upload_rate)))
+            .download_limiter(Arc::new(RateLimiter::new(self.max_download_rate)))
     }
 }
 
+fn print_history(path: Option<PathBuf>) -> Result<(), Error> {
+    let path = match path {
+        Some(path) => path,
+        None => env::current_dir()?,
+    };
+    // Deleted files can't be canonicalized, so use their parent
+    let path = match (path.canonicalize(), path.parent(), path.file_name()) {
+        (Ok(path), _, _) => path,
+        (Err(_), Some(parent), Some(file_name)) => {
+            let parent = if parent.as_os_str().is_empty() {
+                env::current_dir()?
+            } else {
+                parent.canonicalize()?
+            };
+            parent.join(file_name)
+        }
+        (Err(error), _, _) => return Err(error.into()),
+    };
+    let workspace_path = path
+        .ancestors()
+        .find(|ancestor| database::db_path(ancestor).exists())
+        .ok_or(Error::UnexpectedError(format!(
+            "{} is not inside a synchronized folder",
+            path.display()
+        )))?;
+    let relative_path = path.strip_prefix(workspace_path)?;
+    let relative_path = match relative_path.as_os_str().is_empty() {
+        true => None,
+        false => Some(relative_path),
+    };
+
+    let connection = database::connection(workspace_path)
+        .map_err(|error| Error::UnexpectedError(format!("{:#}", error)))?;
+    let entries = history::entries(&connection, relative_path, None)
+        .map_err(|error| Error::UnexpectedError(format!("Read history : {:#}", error)))?;
+    for entry in entries {
+        println!("{}", entry);
+    }
+    Ok(())
+}
+
 fn main() -> Result<(), Error> {
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
     let opt = Opt::from_args();
+    if let Some(Command::History { path }) = opt.command {
+        return print_history(path);
+    }
+    opt.sync_arguments();
 
     // Ask password by input or get it from env var
     let password = if let Some(env_var_pass) = &opt.env_var_pass {
@@ -89,7 +253,12 @@ fn main() -> Result<(), Error> {
     };
 
     let context = opt.to_context(password.clone())?;
-    if let Err(error) = run2::run(context, RemoteControlBuilder::default().build()) {
+    let result = if opt.pipe {
+        pipe::serve(context, opt.confirm_startup_sync)
+    } else {
+        run2::run(context, RemoteControlBuilder::default().build())
+    };
+    if let Err(error) = result {
         return Err(Error::UnexpectedError(format!("{:#}", error)));
     }
     log::info!("Exit application");
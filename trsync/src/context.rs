@@ -1,11 +1,14 @@
 use std::fmt;
 use std::path::Path;
-use std::time::Duration;
 
 use anyhow::Result;
-use trsync_core::client::{Tracim, DEFAULT_CLIENT_TIMEOUT};
+use trsync_core::client::Tracim;
 use trsync_core::instance::WorkspaceId;
 use trsync_core::job::JobIdentifier;
+use trsync_core::schedule::SyncSchedule;
+use trsync_core::server::ServerCapabilities;
+use trsync_core::transfer::TransferControl;
+use trsync_core::transport::TransportConfig;
 
 use crate::database::DB_NAME;
 use crate::error::Error;
@@ -21,6 +24,11 @@ pub struct Context {
     pub workspace_id: WorkspaceId,
     pub workspace_name: String,
     pub exit_after_sync: bool,
+    pub transport: TransportConfig,
+    pub transfer: TransferControl,
+    pub schedule: SyncSchedule,
+    /// Known once connected to server
+    pub capabilities: Option<ServerCapabilities>,
 }
 
 impl Context {
@@ -34,9 +42,9 @@ impl Context {
         workspace_id: WorkspaceId,
         workspace_name: String,
         exit_after_sync: bool,
+        transport: TransportConfig,
     ) -> Result<Self, Error> {
-        let protocol = if ssl { "https" } else { "http" };
-        let base_address = format!("{}://{}/api/", protocol, address);
+        let base_address = format!("{}/", transport.api_url(!ssl, &address));
         let database_path = Path::new(&folder_path).join(DB_NAME).display().to_string();
         Ok(Self {
             instance_name: address,
@@ -48,9 +56,34 @@ impl Context {
             workspace_id,
             workspace_name,
             exit_after_sync,
+            transport,
+            transfer: TransferControl::default(),
+            schedule: SyncSchedule::default(),
+            capabilities: None,
         })
     }
 
+    /// Name used to identify this job (instance address by default)
+    pub fn instance_name(mut self, value: String) -> Self {
+        self.instance_name = value;
+        self
+    }
+
+    pub fn transfer(mut self, value: TransferControl) -> Self {
+        self.transfer = value;
+        self
+    }
+
+    pub fn schedule(mut self, value: SyncSchedule) -> Self {
+        self.schedule = value;
+        self
+    }
+
+    pub fn capabilities(mut self, value: Option<ServerCapabilities>) -> Self {
+        self.capabilities = value;
+        self
+    }
+
     pub fn workspace_url(&self, suffix: &str) -> String {
         format!(
             "{}workspaces/{}/{}",
@@ -59,16 +92,15 @@ impl Context {
     }
 
     pub fn client(&self) -> Result<Tracim> {
-        let client = reqwest::blocking::Client::builder()
-            .timeout(Duration::from_secs(DEFAULT_CLIENT_TIMEOUT))
-            .build()?;
         Ok(Tracim::new(
             self.base_address.clone(),
             self.workspace_id,
-            client,
+            &self.transport,
             self.username.clone(),
             self.password.clone(),
-        ))
+        )?
+        .transfer_control(self.transfer.clone())
+        .capabilities(self.capabilities.clone()))
     }
 
     pub fn job_identifier(&self) -> JobIdentifier {
@@ -76,6 +108,7 @@ impl Context {
             self.instance_name.clone(),
             self.workspace_id.0,
             self.workspace_name.clone(),
+            self.folder_path.clone(),
         )
     }
 }
@@ -89,6 +122,9 @@ impl fmt::Debug for Context {
             .field("base_address", &self.base_address)
             .field("workspace_id", &self.workspace_id)
             .field("exit_after_sync", &self.exit_after_sync)
+            .field("transport", &self.transport)
+            .field("schedule", &self.schedule)
+            .field("capabilities", &self.capabilities)
             .finish()
     }
 }
@@ -1,5 +1,8 @@
 use anyhow::{Context, Result};
-use std::path::{Path, PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use rusqlite::{params, Connection};
 
@@ -7,6 +10,9 @@ use crate::error::Error;
 use trsync_core::types::{ContentId, LastModifiedTimestamp, RelativeFilePath, RevisionId};
 
 pub const DB_NAME: &str = ".trsync.db";
+/// Presence of this file in workspace folder means its database can be outdated (workspace
+/// was removed from configuration), local state is then forgotten at next start
+pub const STALE_MARKER_NAME: &str = ".trsync.stale";
 
 pub struct Database {
     database_file_path: String,
@@ -264,6 +270,20 @@ pub fn db_path(workspace_path: &Path) -> PathBuf {
     workspace_path.join(DB_NAME)
 }
 
+pub fn stale_marker_path(workspace_path: &Path) -> PathBuf {
+    workspace_path.join(STALE_MARKER_NAME)
+}
+
+/// Mark workspace local state as outdated, if any
+pub fn mark_stale(workspace_path: &Path) -> Result<()> {
+    if db_path(workspace_path).exists() {
+        let marker_path = stale_marker_path(workspace_path);
+        fs::write(&marker_path, "")
+            .context(format!("Write stale marker {}", marker_path.display()))?;
+    }
+    Ok(())
+}
+
 pub fn connection(workspace_path: &Path) -> Result<Connection> {
     let db_path = db_path(workspace_path);
     Connection::open(&db_path).context(format!("Open database connection on {}", db_path.display()))
@@ -0,0 +1,274 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context, Result as AnyhowResult};
+use chrono::{Local, TimeZone};
+use rusqlite::{params, Connection};
+use trsync_core::instance::{ContentId, RevisionId, WorkspaceId};
+
+use crate::event::Event;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Local change sent to the server
+    Upload,
+    /// Remote change applied on disk
+    Download,
+}
+
+impl Direction {
+    fn as_str(&self) -> &str {
+        match self {
+            Direction::Upload => "upload",
+            Direction::Download => "download",
+        }
+    }
+
+    fn from_str(value: &str) -> AnyhowResult<Self> {
+        match value {
+            "upload" => Ok(Direction::Upload),
+            "download" => Ok(Direction::Download),
+            _ => bail!("Unknown direction '{}'", value),
+        }
+    }
+}
+
+impl From<&Event> for Direction {
+    fn from(value: &Event) -> Self {
+        match value {
+            Event::Remote(_) => Direction::Download,
+            Event::Local(_) => Direction::Upload,
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Upload => f.write_str("🖴⬆"),
+            Direction::Download => f.write_str("☁⬇"),
+        }
+    }
+}
+
+/// An executed operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// Unix timestamp (seconds)
+    pub timestamp: i64,
+    pub workspace_id: WorkspaceId,
+    pub content_id: Option<ContentId>,
+    pub path: Option<PathBuf>,
+    pub direction: Direction,
+    pub executor: String,
+    pub revision_id: Option<RevisionId>,
+    pub error: Option<String>,
+}
+
+impl HistoryEntry {
+    pub fn datetime(&self) -> String {
+        match Local.timestamp_opt(self.timestamp, 0).single() {
+            Some(datetime) => datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+            None => self.timestamp.to_string(),
+        }
+    }
+}
+
+impl Display for HistoryEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} ({}, content {}, revision {})",
+            self.datetime(),
+            self.direction,
+            self.path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or("?".to_string()),
+            self.executor,
+            self.content_id
+                .map(|content_id| content_id.to_string())
+                .unwrap_or("?".to_string()),
+            self.revision_id
+                .map(|revision_id| revision_id.to_string())
+                .unwrap_or("?".to_string()),
+        )?;
+        if let Some(error) = &self.error {
+            write!(f, " : {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Operations history, stored in workspace database
+pub struct History {
+    connection: Connection,
+    workspace_id: WorkspaceId,
+}
+
+impl History {
+    pub fn new(connection: Connection, workspace_id: WorkspaceId) -> Self {
+        Self {
+            connection,
+            workspace_id,
+        }
+    }
+
+    pub fn create_tables(&self) -> AnyhowResult<()> {
+        self.connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS history (
+                timestamp INTEGER NOT NULL,
+                workspace_id INTEGER NOT NULL,
+                content_id INTEGER,
+                relative_path TEXT,
+                direction TEXT NOT NULL,
+                executor TEXT NOT NULL,
+                revision_id INTEGER,
+                error TEXT
+            );",
+                [],
+            )
+            .context("Create history table")?;
+        self.connection
+            .execute(
+                "CREATE INDEX IF NOT EXISTS idx_history_relative_path ON history (relative_path)",
+                [],
+            )
+            .context("Create history relative_path index")?;
+        Ok(())
+    }
+
+    pub fn workspace_id(&self) -> WorkspaceId {
+        self.workspace_id
+    }
+
+    pub fn now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or(0)
+    }
+
+    pub fn record(&self, entry: &HistoryEntry) -> AnyhowResult<()> {
+        self.connection
+            .execute(
+                "INSERT INTO history (
+                timestamp, workspace_id, content_id, relative_path, direction, executor, revision_id, error
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    entry.timestamp,
+                    entry.workspace_id.0,
+                    entry.content_id.map(|content_id| content_id.0),
+                    entry
+                        .path
+                        .as_ref()
+                        .map(|path| path.display().to_string()),
+                    entry.direction.as_str(),
+                    entry.executor,
+                    entry.revision_id.map(|revision_id| revision_id.0),
+                    entry.error,
+                ],
+            )
+            .context("Insert history entry")?;
+        Ok(())
+    }
+}
+
+/// Most recent history entries first. If path given, only entries of this path (or its
+/// children if folder) are returned.
+pub fn entries(
+    connection: &Connection,
+    path: Option<&Path>,
+    limit: Option<usize>,
+) -> AnyhowResult<Vec<HistoryEntry>> {
+    let path = path.map(|path| path.display().to_string());
+    let limit = limit.map(|limit| limit as i64).unwrap_or(-1);
+    let mut statement = connection.prepare(
+            "SELECT timestamp, workspace_id, content_id, relative_path, direction, executor, revision_id, error
+            FROM history
+            WHERE ?1 IS NULL OR relative_path = ?1 OR relative_path LIKE ?1 || '/%'
+            ORDER BY timestamp DESC, rowid DESC
+            LIMIT ?2",
+        )?;
+    let rows = statement.query_map(params![path, limit], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i32>(1)?,
+            row.get::<_, Option<i32>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, String>(5)?,
+            row.get::<_, Option<i32>>(6)?,
+            row.get::<_, Option<String>>(7)?,
+        ))
+    })?;
+
+    let mut entries = vec![];
+    for row in rows {
+        let (timestamp, workspace_id, content_id, path, direction, executor, revision_id, error) =
+            row.context("Read history row")?;
+        entries.push(HistoryEntry {
+            timestamp,
+            workspace_id: WorkspaceId(workspace_id),
+            content_id: content_id.map(ContentId),
+            path: path.map(PathBuf::from),
+            direction: Direction::from_str(&direction)?,
+            executor,
+            revision_id: revision_id.map(RevisionId),
+            error,
+        })
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::database::connection;
+    use crate::tests::tmpdir;
+
+    fn entry(timestamp: i64, path: &str, error: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            timestamp,
+            workspace_id: WorkspaceId(1),
+            content_id: Some(ContentId(2)),
+            path: Some(PathBuf::from(path)),
+            direction: Direction::Upload,
+            executor: "CreatedOnRemote".to_string(),
+            revision_id: None,
+            error: error.map(|v| v.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_history_entries() {
+        // Given
+        let tmpdir_ = tmpdir();
+        let history = History::new(connection(&tmpdir_).unwrap(), WorkspaceId(1));
+        history.create_tables().unwrap();
+        history.record(&entry(1, "a.txt", None)).unwrap();
+        history
+            .record(&entry(2, "Folder/b.txt", Some("Oops")))
+            .unwrap();
+        history.record(&entry(3, "Folder2/c.txt", None)).unwrap();
+
+        // When
+        let connection = connection(&tmpdir_).unwrap();
+        let all = entries(&connection, None, None).unwrap();
+        let folder = entries(&connection, Some(Path::new("Folder")), None).unwrap();
+        let last = entries(&connection, None, Some(1)).unwrap();
+
+        // Then
+        assert_eq!(
+            all.iter().map(|e| e.timestamp).collect::<Vec<i64>>(),
+            vec![3, 2, 1]
+        );
+        assert_eq!(folder, vec![entry(2, "Folder/b.txt", Some("Oops"))]);
+        assert_eq!(last, vec![entry(3, "Folder2/c.txt", None)]);
+    }
+}
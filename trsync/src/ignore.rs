@@ -2,6 +2,7 @@ use std::{
     convert::TryFrom,
     fs::{self, OpenOptions},
     io::{self, Write},
+    path::PathBuf,
 };
 
 use async_std::path::Path;
@@ -16,9 +17,12 @@ pub enum IgnoreError {
     IoError(#[from] io::Error),
 }
 
+/// Content ids (lines like `#1234`) and local paths relative to workspace folder (other
+/// lines) of `.trsyncignore` file
 #[derive(Clone)]
 pub struct Ignore {
     content_ids: Vec<ContentId>,
+    paths: Vec<PathBuf>,
 }
 
 impl TryFrom<&Context> for Ignore {
@@ -35,15 +39,18 @@ impl TryFrom<&Context> for Ignore {
         };
 
         let mut content_ids = vec![];
+        let mut paths = vec![];
         for line in file_content.lines() {
             if line.starts_with('#') {
                 if let Ok(content_id_raw) = line.strip_prefix('#').unwrap_or("").parse() {
                     content_ids.push(ContentId(content_id_raw));
                 }
+            } else if !line.trim().is_empty() {
+                paths.push(PathBuf::from(line.trim()));
             }
         }
 
-        Ok(Self { content_ids })
+        Ok(Self { content_ids, paths })
     }
 }
 
@@ -53,6 +60,9 @@ impl From<&Ignore> for String {
         for content_id in value.content_ids() {
             lines.push(format!("#{}", content_id.0))
         }
+        for path in value.paths() {
+            lines.push(path.display().to_string())
+        }
         lines.join("\n")
     }
 }
@@ -61,6 +71,7 @@ impl Ignore {
     pub fn empty() -> Self {
         Self {
             content_ids: vec![],
+            paths: vec![],
         }
     }
 
@@ -68,20 +79,34 @@ impl Ignore {
         self.content_ids.push(content_id)
     }
 
+    pub fn push_path(&mut self, path: PathBuf) {
+        self.paths.push(path)
+    }
+
     pub fn content_ids(&self) -> &[ContentId] {
         &self.content_ids
     }
 
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
     pub fn is_ignored(&self, content_id: &ContentId) -> bool {
         self.content_ids.contains(content_id)
     }
 
+    /// True if path is an ignored path or inside an ignored folder
+    pub fn is_path_ignored(&self, path: &std::path::Path) -> bool {
+        self.paths.iter().any(|ignored| path.starts_with(ignored))
+    }
+
     pub fn write(&self, context: &Context) -> Result<(), io::Error> {
         let path = Path::new(&context.folder_path).join(".trsyncignore");
         let content: String = self.into();
         OpenOptions::new()
             .write(true)
             .create(true)
+            .truncate(true)
             .open(&path)?
             .write_all(content.as_bytes())
     }
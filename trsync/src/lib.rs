@@ -3,11 +3,13 @@ pub mod context;
 pub mod database;
 pub mod error;
 pub mod event;
+pub mod history;
 pub mod ignore;
 pub mod local;
 pub mod message;
 pub mod operation;
 mod path;
+pub mod pipe;
 pub mod reader;
 pub mod remote;
 pub mod run;
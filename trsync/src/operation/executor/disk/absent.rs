@@ -27,6 +27,10 @@ impl AbsentFromDiskExecutor {
 }
 
 impl Executor for AbsentFromDiskExecutor {
+    fn name(&self) -> &'static str {
+        "AbsentFromDisk"
+    }
+
     fn execute(
         &self,
         state: &dyn State,
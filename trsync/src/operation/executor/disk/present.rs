@@ -36,6 +36,10 @@ impl PresentOnDiskExecutor {
 }
 
 impl Executor for PresentOnDiskExecutor {
+    fn name(&self) -> &'static str {
+        "PresentOnDisk"
+    }
+
     fn execute(
         &self,
         state: &dyn State,
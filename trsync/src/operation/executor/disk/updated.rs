@@ -33,6 +33,10 @@ impl UpdatedOnDiskExecutor {
 }
 
 impl Executor for UpdatedOnDiskExecutor {
+    fn name(&self) -> &'static str {
+        "UpdatedOnDisk"
+    }
+
     fn execute(
         &self,
         state: &dyn State,
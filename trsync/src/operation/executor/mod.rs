@@ -9,6 +9,9 @@ pub mod disk;
 pub mod remote;
 
 pub trait Executor {
+    /// Executor kind, as recorded in history
+    fn name(&self) -> &'static str;
+
     fn execute(
         &self,
         state: &dyn State,
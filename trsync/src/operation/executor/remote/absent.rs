@@ -30,6 +30,10 @@ impl AbsentFromRemoteExecutor {
 }
 
 impl Executor for AbsentFromRemoteExecutor {
+    fn name(&self) -> &'static str {
+        "AbsentFromRemote"
+    }
+
     fn execute(
         &self,
         state: &dyn State,
@@ -64,9 +64,20 @@ impl CreatedOnRemoteExecutor {
     fn content_type(&self) -> ContentType {
         ContentType::from_path(&self.absolute_path())
     }
+
+    fn disk_timestamp(&self) -> Result<DiskTimestamp> {
+        let absolute_path = self.absolute_path();
+        let disk_timestamp = last_modified_timestamp(&absolute_path)
+            .context(format!("Get disk timestamp of {}", absolute_path.display()))?;
+        Ok(DiskTimestamp(disk_timestamp.as_millis() as u64))
+    }
 }
 
 impl Executor for CreatedOnRemoteExecutor {
+    fn name(&self) -> &'static str {
+        "CreatedOnRemote"
+    }
+
     fn execute(
         &self,
         state: &dyn State,
@@ -105,6 +116,15 @@ impl Executor for CreatedOnRemoteExecutor {
                     &file_name.0, parent
                 ))?
             }
+            // Retrying is useless while file is unchanged
+            Err(error) if error.is_file_rejection() => {
+                log::warn!("Server refused {} : {}", self.path.display(), error);
+                return Ok(vec![StateModification::Reject(
+                    self.path.clone(),
+                    error.to_string(),
+                    self.disk_timestamp()?,
+                )]);
+            }
             Err(error) => return Err(ExecutorError::Tracim(error)),
         };
 
@@ -162,12 +182,10 @@ impl Executor for CreatedOnRemoteExecutor {
                 .get_content(content_id)
                 .context(format!("Get just created content {}", content_id))?,
         )?;
-        let disk_timestamp = last_modified_timestamp(&absolute_path)
-            .context(format!("Get disk timestamp of {}", absolute_path.display()))?;
         Ok(vec![StateModification::Add(
             content,
             self.path.clone(),
-            DiskTimestamp(disk_timestamp.as_millis() as u64),
+            self.disk_timestamp()?,
         )])
     }
 }
@@ -73,13 +73,7 @@ impl ModifiedOnRemoteExecutor {
         absolute_path: &PathBuf,
         ignore_events: &mut Vec<Event>,
     ) -> Result<(), TracimClientError> {
-        tracim
-            .fill_content_with_file(content_id, content_type, absolute_path)
-            .context(format!(
-                "Fill remote file {} with {}",
-                content_id,
-                absolute_path.display(),
-            ))?;
+        tracim.fill_content_with_file(content_id, content_type, absolute_path)?;
         ignore_events.push(Event::Remote(RemoteEvent::Updated(content_id)));
         Ok(())
     }
@@ -99,6 +93,10 @@ impl ModifiedOnRemoteExecutor {
 }
 
 impl Executor for ModifiedOnRemoteExecutor {
+    fn name(&self) -> &'static str {
+        "ModifiedOnRemote"
+    }
+
     fn execute(
         &self,
         state: &dyn State,
@@ -113,22 +111,39 @@ impl Executor for ModifiedOnRemoteExecutor {
         ))?;
 
         if content_type.fillable() {
-            if let Err(TracimClientError::ContentDeletedOrArchived) = self.update_content(
+            match self.update_content(
                 tracim,
                 content_id,
                 content_type,
                 &absolute_path,
                 ignore_events,
             ) {
-                // TODO : manage archived case
-                self.restore_content(tracim, content_id, ignore_events)?;
-                self.update_content(
-                    tracim,
-                    content_id,
-                    content_type,
-                    &absolute_path,
-                    ignore_events,
-                )?;
+                Ok(_) => {}
+                Err(TracimClientError::ContentDeletedOrArchived) => {
+                    // TODO : manage archived case
+                    self.restore_content(tracim, content_id, ignore_events)?;
+                    self.update_content(
+                        tracim,
+                        content_id,
+                        content_type,
+                        &absolute_path,
+                        ignore_events,
+                    )?;
+                }
+                // Retrying is useless while file is unchanged
+                Err(error) if error.is_file_rejection() => {
+                    log::warn!("Server refused {} : {}", self.db_path.display(), error);
+                    let last_modified = self.last_modified(state).context(format!(
+                        "Get last modified datetime of {}",
+                        absolute_path.display()
+                    ))?;
+                    return Ok(vec![StateModification::Reject(
+                        self.db_path.clone(),
+                        error.to_string(),
+                        last_modified,
+                    )]);
+                }
+                Err(error) => return Err(ExecutorError::Tracim(error)),
             }
         }
 
@@ -130,6 +130,10 @@ impl NamedOnRemoteExecutor {
 }
 
 impl Executor for NamedOnRemoteExecutor {
+    fn name(&self) -> &'static str {
+        "NamedOnRemote"
+    }
+
     fn execute(
         &self,
         state: &dyn State,
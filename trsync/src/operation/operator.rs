@@ -1,14 +1,18 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
 
 use crate::{
     event::{remote::RemoteEvent, Event},
+    history::{History, HistoryEntry},
+    ignore::Ignore,
     local::{reducer::DiskEventWrap, watcher::DiskEvent},
     state::State,
 };
 use trsync_core::{
     client::{TracimClient, TracimClientError},
     error::{ExecutorError, OperatorError},
-    instance::ContentId,
+    instance::{ContentId, RevisionId},
     types::ContentType,
 };
 
@@ -28,12 +32,24 @@ use super::{
 
 const RETRY_COUNT_MAX: usize = 5;
 
+/// Event which produced an executor error, with its target
+#[derive(Debug, Clone)]
+pub struct FailedEvent {
+    /// Event as received (before its qualification), so it can be ignored next time
+    pub event: Event,
+    pub content_id: Option<ContentId>,
+    pub path: Option<PathBuf>,
+}
+
 pub struct Operator<'a> {
     state: &'a mut Box<dyn State>,
     workspace_folder: PathBuf,
     tracim: Box<dyn TracimClient>,
     ignore_events: Vec<Event>,
     avoid_same_sums: bool,
+    history: Option<History>,
+    ignore: Ignore,
+    failed_event: Option<FailedEvent>,
 }
 
 impl<'a> Operator<'a> {
@@ -48,6 +64,9 @@ impl<'a> Operator<'a> {
             tracim,
             ignore_events: vec![],
             avoid_same_sums: false,
+            history: None,
+            ignore: Ignore::empty(),
+            failed_event: None,
         }
     }
 
@@ -56,6 +75,28 @@ impl<'a> Operator<'a> {
         self
     }
 
+    pub fn history(mut self, value: History) -> Self {
+        self.history = Some(value);
+        self
+    }
+
+    /// Events concerning these paths will be ignored
+    pub fn ignore(mut self, value: Ignore) -> Self {
+        self.ignore = value;
+        self
+    }
+
+    /// Ignore given event the next time it is operated
+    pub fn ignore_event(mut self, value: Event) -> Self {
+        self.ignore_events.push(value);
+        self
+    }
+
+    /// Event which produced the last error, if it was produced by an executor
+    pub fn failed_event(&self) -> Option<&FailedEvent> {
+        self.failed_event.as_ref()
+    }
+
     fn is_attachment(&self, event: &Event) -> Result<bool, OperatorError> {
         match event {
             Event::Remote(event) => match event {
@@ -87,6 +128,8 @@ impl<'a> Operator<'a> {
     }
 
     pub fn operate(&mut self, event: &Event) -> Result<(), OperatorError> {
+        let received_event = event;
+        self.failed_event = None;
         if self.ignore_events.contains(event) {
             self.ignore_events.retain(|x| x != event);
             log::info!("Ignore event (planned ignore) : {:?}", &event);
@@ -98,20 +141,52 @@ impl<'a> Operator<'a> {
             return Ok(());
         }
 
+        let (event_content_id, event_path) = self.event_target(event);
+        if let Some(path) = &event_path {
+            if self.ignore.is_path_ignored(path) {
+                log::info!("Ignore event (ignored path) : {:?}", &event);
+                return Ok(());
+            }
+            if self.is_in_rejected_folder(path)? {
+                log::info!("Ignore event (in rejected folder) : {:?}", &event);
+                return Ok(());
+            }
+        }
+
+        let event = match self.qualify_rejected(event)? {
+            Some(event) => event,
+            None => {
+                log::info!(
+                    "Forget rejection (deleted before acceptance) : {:?}",
+                    &event
+                );
+                return Ok(());
+            }
+        };
+
         log::info!("Proceed event : {:?}", &event);
-        let event = self.qualify(event)?;
+        let event = self.qualify(&event)?;
 
         let executors = self.executors(&event);
         let mut retry_count = 0;
         let mut state_changes = vec![];
+        let mut executed = vec![];
 
         for executor in &executors {
             loop {
                 if retry_count >= RETRY_COUNT_MAX {
                     // TODO: keep event to proceed it when connection is back
-                    return Err(OperatorError::ExecutorError(
-                        ExecutorError::MaximumRetryCount(event.display(self.tracim.as_ref())),
-                    ));
+                    let error =
+                        ExecutorError::MaximumRetryCount(event.display(self.tracim.as_ref()));
+                    self.fail(
+                        received_event,
+                        &event,
+                        executor.name(),
+                        event_content_id,
+                        event_path,
+                        &error,
+                    );
+                    return Err(OperatorError::ExecutorError(error));
                 }
                 match executor.execute(
                     self.state.as_ref(),
@@ -119,6 +194,21 @@ impl<'a> Operator<'a> {
                     &mut self.ignore_events,
                 ) {
                     Ok(state_changes_) => {
+                        executed.push((
+                            executor.name(),
+                            state_changes_
+                                .first()
+                                .and_then(|change| change.content_id())
+                                .or(event_content_id),
+                            state_changes_
+                                .iter()
+                                .rev()
+                                .find_map(|change| change.revision_id()),
+                            state_changes_
+                                .iter()
+                                .find_map(|change| change.rejection_reason())
+                                .map(|reason| reason.to_string()),
+                        ));
                         state_changes.extend(state_changes_);
                         break;
                     }
@@ -126,7 +216,17 @@ impl<'a> Operator<'a> {
                         retry_count += 1;
                         continue;
                     }
-                    Err(err) => return Err(OperatorError::ExecutorError(err)),
+                    Err(err) => {
+                        self.fail(
+                            received_event,
+                            &event,
+                            executor.name(),
+                            event_content_id,
+                            event_path,
+                            &err,
+                        );
+                        return Err(OperatorError::ExecutorError(err));
+                    }
                 };
             }
         }
@@ -135,9 +235,165 @@ impl<'a> Operator<'a> {
             self.state.change(state_change)?
         }
 
+        for (executor_name, content_id, revision_id, rejection_reason) in executed {
+            let path = content_id
+                .and_then(|content_id| self.state.path(content_id).ok())
+                .map(|path| path.to_path_buf())
+                .or(event_path.clone());
+            self.record(
+                &event,
+                executor_name,
+                content_id,
+                revision_id,
+                path,
+                rejection_reason,
+            );
+        }
+
         Ok(())
     }
 
+    /// True if one of path parents has been refused by server (its children can't be
+    /// created on server)
+    fn is_in_rejected_folder(&self, path: &Path) -> Result<bool, OperatorError> {
+        for ancestor in path.ancestors().skip(1) {
+            if ancestor.as_os_str().is_empty() {
+                break;
+            }
+            if self
+                .state
+                .rejection(ancestor)
+                .context(format!("Get rejection of {}", ancestor.display()))?
+                .is_some()
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Local events of a file refused by server and never accepted since (so, unknown by
+    /// server) : a modification retries its creation, a deletion or a renaming forgets its
+    /// rejection. Return None if there is nothing more to do.
+    fn qualify_rejected(&mut self, event: &Event) -> Result<Option<Event>, OperatorError> {
+        let (db_path, disk_event) = match event {
+            Event::Local(DiskEventWrap(db_path, disk_event)) => (db_path, disk_event),
+            Event::Remote(_) => return Ok(Some(event.clone())),
+        };
+        if self
+            .state
+            .content_id_for_path(db_path.clone())
+            .context(format!("Get content_id for {}", db_path.display()))?
+            .is_some()
+            || self
+                .state
+                .rejection(db_path)
+                .context(format!("Get rejection of {}", db_path.display()))?
+                .is_none()
+        {
+            return Ok(Some(event.clone()));
+        }
+
+        let disk_path = match disk_event {
+            DiskEvent::Created(_) => return Ok(Some(event.clone())),
+            DiskEvent::Deleted(_) => None,
+            DiskEvent::Modified(disk_path) | DiskEvent::Renamed(_, disk_path) => {
+                Some(disk_path.clone())
+            }
+        };
+        if disk_path.as_ref() != Some(db_path) {
+            self.state
+                .unreject(db_path)
+                .context(format!("Forget rejection of {}", db_path.display()))?;
+        }
+
+        Ok(disk_path.map(|disk_path| {
+            Event::Local(DiskEventWrap::new(
+                disk_path.clone(),
+                DiskEvent::Created(disk_path),
+            ))
+        }))
+    }
+
+    /// Content and path concerned by event, as known before operating it
+    fn event_target(&self, event: &Event) -> (Option<ContentId>, Option<PathBuf>) {
+        match event {
+            Event::Remote(event) => {
+                let content_id = event.content_id();
+                let path = self
+                    .state
+                    .path(content_id)
+                    .ok()
+                    .map(|path| path.to_path_buf());
+                (Some(content_id), path)
+            }
+            Event::Local(DiskEventWrap(db_path, disk_event)) => {
+                let content_id = self
+                    .state
+                    .content_id_for_path(db_path.clone())
+                    .ok()
+                    .flatten();
+                let path = match disk_event {
+                    DiskEvent::Deleted(_) => db_path.clone(),
+                    DiskEvent::Created(disk_path)
+                    | DiskEvent::Modified(disk_path)
+                    | DiskEvent::Renamed(_, disk_path) => disk_path.clone(),
+                };
+                (content_id, Some(path))
+            }
+        }
+    }
+
+    fn fail(
+        &mut self,
+        received_event: &Event,
+        event: &Event,
+        executor_name: &str,
+        content_id: Option<ContentId>,
+        path: Option<PathBuf>,
+        error: &ExecutorError,
+    ) {
+        self.record(
+            event,
+            executor_name,
+            content_id,
+            None,
+            path.clone(),
+            Some(error.to_string()),
+        );
+        self.failed_event = Some(FailedEvent {
+            event: received_event.clone(),
+            content_id,
+            path,
+        });
+    }
+
+    fn record(
+        &self,
+        event: &Event,
+        executor_name: &str,
+        content_id: Option<ContentId>,
+        revision_id: Option<RevisionId>,
+        path: Option<PathBuf>,
+        error: Option<String>,
+    ) {
+        if let Some(history) = &self.history {
+            let entry = HistoryEntry {
+                timestamp: History::now(),
+                workspace_id: history.workspace_id(),
+                content_id,
+                path,
+                direction: event.into(),
+                executor: executor_name.to_string(),
+                revision_id,
+                error,
+            };
+            if let Err(error) = history.record(&entry) {
+                log::error!("Unable to record history entry : {:#}", error);
+            }
+        }
+    }
+
     fn qualify(&self, event: &Event) -> Result<Event, ExecutorError> {
         if let Event::Remote(RemoteEvent::Updated(content_id)) = event {
             // When content is moved from workspace to another, Tracim indicate an update.
@@ -238,8 +494,10 @@ mod test {
     use trsync_core::instance::ContentId;
 
     use super::*;
+    use crate::state::Rejection;
     use crate::tests::*;
     use rstest::*;
+    use trsync_core::instance::DiskTimestamp;
 
     #[rstest]
     // REMOTE DELETE
@@ -388,6 +646,34 @@ mod test {
         assert_eq!(state_files, expected_state);
     }
 
+    #[test]
+    fn test_skip_failed_event_once() {
+        // Given (an unknown updated content is qualified as a created one)
+        let tmpdir_ = tmpdir();
+        let mut state = build_memory_state(&vec![], Some(&tmpdir_));
+        let event = Event::Remote(RemoteEvent::Updated(ContentId(1)));
+        let mut client = MockTracimClient::new();
+        client
+            .expect_get_content()
+            .returning(|_| Err(TracimClientError::Unknown("Server error".to_string())));
+        let mut operator = Operator::new(&mut state, tmpdir_.clone(), Box::new(client));
+        assert!(operator.operate(&event).is_err());
+        let failed_event = operator.failed_event().unwrap().event.clone();
+
+        // When (client without expectation would panic if event is operated)
+        let result = Operator::new(
+            &mut state,
+            tmpdir_.clone(),
+            Box::new(MockTracimClient::new()),
+        )
+        .ignore_event(failed_event.clone())
+        .operate(&event);
+
+        // Then
+        assert_eq!(failed_event, event);
+        assert!(result.is_ok());
+    }
+
     #[rstest]
     // LOCAL DELETE
     // Delete a file
@@ -559,4 +845,112 @@ mod test {
         let state_files = state_files(previous_event_state.as_ref());
         assert_eq!(state_files, expected_state);
     }
+
+    #[rstest]
+    fn test_operator_reject_forbidden_folder() {
+        // Given
+        let tmpdir_ = tmpdir();
+        let mut state = build_memory_state(&vec![], Some(&tmpdir_));
+        apply_on_disk(
+            &vec![
+                OperateOnDisk::Create("Folder".to_string()),
+                OperateOnDisk::Create("Folder/a.txt".to_string()),
+            ],
+            &tmpdir_,
+        );
+        let mut client = MockTracimClient::new();
+        client
+            .expect_create_content()
+            .times(1)
+            .returning(|_, _, _, _| {
+                Err(TracimClientError::ForbiddenFileName("Folder".to_string()))
+            });
+        let created = |path: &str| {
+            Event::Local(DiskEventWrap::new(
+                PathBuf::from(path),
+                DiskEvent::Created(PathBuf::from(path)),
+            ))
+        };
+        let mut operator = Operator::new(&mut state, tmpdir_, Box::new(client));
+
+        // When
+        let result = operator.operate(&created("Folder"));
+        let child_result = operator.operate(&created("Folder/a.txt"));
+
+        // Then
+        assert!(result.is_ok());
+        assert!(child_result.is_ok());
+        assert_eq!(state_files(state.as_ref()), Vec::<String>::new());
+        assert_eq!(
+            state
+                .rejection(Path::new("Folder"))
+                .unwrap()
+                .map(|rejection| rejection.reason),
+            Some("Forbidden file name: `Folder`".to_string())
+        );
+    }
+
+    fn rejected_state(tmpdir_: &Path) -> Box<dyn State> {
+        let mut state = build_memory_state(&vec![], Some(&tmpdir_.to_path_buf()));
+        apply_on_disk(&vec![OperateOnDisk::Create("a.txt".to_string())], tmpdir_);
+        state
+            .reject(
+                PathBuf::from("a.txt"),
+                Rejection {
+                    reason: "Forbidden file name: `a.txt`".to_string(),
+                    timestamp: DiskTimestamp(0),
+                },
+            )
+            .unwrap();
+        state
+    }
+
+    #[rstest]
+    fn test_operator_modified_rejected_file_retry_creation() {
+        // Given
+        let tmpdir_ = tmpdir();
+        let mut state = rejected_state(&tmpdir_);
+        let mut client = MockTracimClient::new();
+        MockTracimClientCase::apply_multiples(
+            &tmpdir_,
+            &mut client,
+            vec![
+                MockTracimClientCase::CreateOk(("a.txt".to_string(), None, 1)),
+                MockTracimClientCase::FillRemoteOk(1, "a.txt".to_string(), 2),
+                MockTracimClientCase::GetOk((1, 1, "a.txt".to_string(), None)),
+            ],
+        );
+        let event = Event::Local(DiskEventWrap::new(
+            PathBuf::from("a.txt"),
+            DiskEvent::Modified(PathBuf::from("a.txt")),
+        ));
+
+        // When
+        let result = Operator::new(&mut state, tmpdir_, Box::new(client)).operate(&event);
+
+        // Then
+        assert!(result.is_ok());
+        assert_eq!(state_files(state.as_ref()), vec!["a.txt"]);
+        assert_eq!(state.rejection(Path::new("a.txt")).unwrap(), None);
+    }
+
+    #[rstest]
+    fn test_operator_deleted_rejected_file_forget_rejection() {
+        // Given
+        let tmpdir_ = tmpdir();
+        let mut state = rejected_state(&tmpdir_);
+        let client = MockTracimClient::new();
+        let event = Event::Local(DiskEventWrap::new(
+            PathBuf::from("a.txt"),
+            DiskEvent::Deleted(PathBuf::from("a.txt")),
+        ));
+
+        // When
+        let result = Operator::new(&mut state, tmpdir_, Box::new(client)).operate(&event);
+
+        // Then
+        assert!(result.is_ok());
+        assert_eq!(state_files(state.as_ref()), Vec::<String>::new());
+        assert_eq!(state.rejection(Path::new("a.txt")).unwrap(), None);
+    }
 }
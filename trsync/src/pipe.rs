@@ -0,0 +1,372 @@
+use std::{
+    io::{self, BufRead, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context as AnyhowContext, Result as AnyhowResult};
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use trsync_core::{
+    activity::{Activity, JobUpdate, WrappedActivity},
+    change::{local::LocalChange, remote::RemoteChange},
+    control::RemoteControlBuilder,
+    error::{Decision, ErrorChannels, ErrorClass, ErrorSubject},
+    instance::ContentId,
+    status::StatusUpdate,
+    sync::SyncChannels,
+    user::UserRequest,
+};
+
+use crate::{context::Context, error::Error, run2};
+
+/// Environment variable giving password to a child process
+pub const PASSWORD_ENV_VAR: &str = "TRSYNC_PASSWORD";
+const POLL_DELAY: Duration = Duration::from_millis(250);
+
+/// Messages sent by a `trsync --pipe` child process on its stdout, one json document per
+/// line (ex. `{"message":"activity","data":"Idle"}`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "message", content = "data", rename_all = "snake_case")]
+pub enum ChildMessage {
+    Activity(Activity),
+    Status(StatusUpdate),
+    /// Startup changes are waiting a confirmation
+    StartupSync(Vec<RemoteChange>, Vec<LocalChange>),
+    /// Synchronization failed and is waiting a decision
+    Error(ChildError),
+    /// Workspace was deleted or user is not member of it anymore, synchronization is stopped
+    AccessLost,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChildError {
+    pub message: String,
+    pub class: ErrorClass,
+    /// Content which can be ignored to get rid of the error
+    pub content_id: Option<ContentId>,
+    pub subject: Option<ErrorSubject>,
+}
+
+/// Messages sent by parent process on stdin of a `trsync --pipe` child process, one json
+/// document per line (ex. `{"message":"confirm_startup_sync","data":true}`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "message", content = "data", rename_all = "snake_case")]
+pub enum ParentMessage {
+    Stop,
+    Pause,
+    Resume,
+    Resync,
+    ConfirmStartupSync(bool),
+    Decision(Decision),
+}
+
+pub fn write_message<W: Write, T: serde::Serialize>(
+    writer: &mut W,
+    message: &T,
+) -> Result<(), Error> {
+    let line = serde_json::to_string(message)
+        .map_err(|error| Error::UnexpectedError(format!("Serialize message : {}", error)))?;
+    writeln!(writer, "{}", line)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Next message, or None when other side closed the pipe
+pub fn read_message<R: BufRead, T: DeserializeOwned>(reader: &mut R) -> Result<Option<T>, Error> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line).map(Some).map_err(|error| {
+        Error::UnexpectedError(format!("Invalid message '{}' : {}", line.trim(), error))
+    })
+}
+
+/// Run synchronization driven by parent process through stdin and stdout
+pub fn serve(context: Context, confirm_startup_sync: bool) -> AnyhowResult<()> {
+    let stop_signal = Arc::new(AtomicBool::new(false));
+    let pause_signal = Arc::new(AtomicBool::new(false));
+    let resync_signal = Arc::new(AtomicBool::new(false));
+    let (activity_sender, activity_receiver) = unbounded();
+    let (user_request_sender, user_request_receiver) = unbounded();
+    let (decision_sender, decision_receiver) = unbounded();
+    let sync_channels = SyncChannels::default();
+    let error_channels = ErrorChannels::new(decision_sender, decision_receiver);
+    let finished = Arc::new(AtomicBool::new(false));
+
+    let listener = ParentListener {
+        stop_signal: stop_signal.clone(),
+        pause_signal: pause_signal.clone(),
+        resync_signal: resync_signal.clone(),
+        sync_channels: sync_channels.clone(),
+        error_channels: error_channels.clone(),
+    };
+    thread::Builder::new()
+        .name("parent_listener".to_string())
+        .spawn(move || listener.run())
+        .context("Start parent listener")?;
+
+    let reporter = ParentReporter {
+        activity_receiver,
+        user_request_receiver,
+        sync_channels: sync_channels.clone(),
+        error_channels: error_channels.clone(),
+        finished: finished.clone(),
+    };
+    let reporter = thread::Builder::new()
+        .name("parent_reporter".to_string())
+        .spawn(move || reporter.run())
+        .context("Start parent reporter")?;
+
+    let remote = RemoteControlBuilder::default()
+        .stop_signal(stop_signal)
+        .pause_signal(pause_signal)
+        .resync_signal(resync_signal)
+        .activity_sender(Some(activity_sender))
+        .confirm_startup_sync(confirm_startup_sync)
+        .user_request_sender(Some(user_request_sender))
+        .error_channels(Some(error_channels))
+        .sync_channels(Some(sync_channels))
+        .build();
+    let result = run2::run(context, remote);
+
+    // Let last messages reach parent before exit
+    finished.store(true, Ordering::Relaxed);
+    if reporter.join().is_err() {
+        log::error!("Parent reporter panicked");
+    }
+    result
+}
+
+/// Apply parent messages received on stdin
+struct ParentListener {
+    stop_signal: Arc<AtomicBool>,
+    pause_signal: Arc<AtomicBool>,
+    resync_signal: Arc<AtomicBool>,
+    sync_channels: SyncChannels,
+    error_channels: ErrorChannels,
+}
+
+impl ParentListener {
+    fn run(&self) {
+        let stdin = io::stdin();
+        let mut reader = stdin.lock();
+        loop {
+            match read_message::<_, ParentMessage>(&mut reader) {
+                Ok(Some(message)) => self.apply(message),
+                Ok(None) => {
+                    log::info!("Parent process closed the pipe, stop");
+                    self.stop();
+                    break;
+                }
+                Err(error) => log::error!("Unable to read parent message : {}", error),
+            }
+        }
+    }
+
+    fn apply(&self, message: ParentMessage) {
+        log::debug!("Received parent message {:?}", message);
+        match message {
+            ParentMessage::Stop => self.stop(),
+            ParentMessage::Pause => self.pause_signal.store(true, Ordering::Relaxed),
+            ParentMessage::Resume => self.pause_signal.store(false, Ordering::Relaxed),
+            ParentMessage::Resync => self.resync_signal.store(true, Ordering::Relaxed),
+            ParentMessage::ConfirmStartupSync(confirm) => self.confirm(confirm),
+            ParentMessage::Decision(decision) => self.decide(decision),
+        }
+    }
+
+    fn confirm(&self, confirm: bool) {
+        // TODO : no unwrap
+        *self.sync_channels.changes().lock().unwrap() = None;
+        if self
+            .sync_channels
+            .confirm_sync_sender()
+            .send(confirm)
+            .is_err()
+        {
+            log::error!("Unable to send startup sync confirmation to runner");
+        }
+    }
+
+    fn decide(&self, decision: Decision) {
+        // TODO : no unwrap
+        *self.error_channels.error().lock().unwrap() = None;
+        if self
+            .error_channels
+            .decision_sender()
+            .send(decision)
+            .is_err()
+        {
+            log::error!("Unable to send decision to runner");
+        }
+    }
+
+    /// Stop, including when runner is waiting a confirmation or a decision
+    fn stop(&self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+        if self.sync_channels.changes().lock().unwrap().is_some() {
+            self.confirm(false);
+        }
+        if self.error_channels.error().lock().unwrap().is_some() {
+            self.decide(Decision::RestartSpaceSync);
+        }
+    }
+}
+
+/// Report runner activity, pending confirmation and error to parent on stdout
+struct ParentReporter {
+    activity_receiver: Receiver<WrappedActivity>,
+    user_request_receiver: Receiver<UserRequest>,
+    sync_channels: SyncChannels,
+    error_channels: ErrorChannels,
+    finished: Arc<AtomicBool>,
+}
+
+impl ParentReporter {
+    fn run(&self) {
+        let stdout = io::stdout();
+        let mut writer = stdout.lock();
+        let mut startup_sync_reported = false;
+        let mut error_reported = false;
+
+        loop {
+            let finished = self.finished.load(Ordering::Relaxed);
+            let mut messages = vec![];
+
+            match self.activity_receiver.recv_timeout(POLL_DELAY) {
+                Ok(activity) => messages.extend(Self::activity_message(&activity)),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => thread::sleep(POLL_DELAY),
+            }
+            messages.extend(
+                self.activity_receiver
+                    .try_iter()
+                    .filter_map(|activity| Self::activity_message(&activity)),
+            );
+            for user_request in self.user_request_receiver.try_iter() {
+                // Monitor window opening is decided by parent
+                if let UserRequest::WorkspaceAccessLost(_, _) = user_request {
+                    messages.push(ChildMessage::AccessLost);
+                }
+            }
+
+            // TODO : no unwrap
+            match self.sync_channels.changes().lock().unwrap().as_ref() {
+                Some((remote_changes, local_changes)) if !startup_sync_reported => {
+                    messages.push(ChildMessage::StartupSync(
+                        remote_changes.clone(),
+                        local_changes.clone(),
+                    ));
+                    startup_sync_reported = true;
+                }
+                Some(_) => {}
+                None => startup_sync_reported = false,
+            }
+            match self.error_channels.error().lock().unwrap().as_ref() {
+                Some(error) if !error_reported => {
+                    messages.push(ChildMessage::Error(ChildError {
+                        message: error.to_string(),
+                        class: error.class(),
+                        content_id: error.ignorable_content_id(),
+                        subject: self.error_channels.subject().lock().unwrap().clone(),
+                    }));
+                    error_reported = true;
+                }
+                Some(_) => {}
+                None => error_reported = false,
+            }
+
+            for message in messages {
+                if let Err(error) = write_message(&mut writer, &message) {
+                    log::error!("Unable to send message to parent : {}", error);
+                }
+            }
+
+            if finished {
+                break;
+            }
+        }
+    }
+
+    /// Job removal is decided by parent, so it is not reported
+    fn activity_message(activity: &WrappedActivity) -> Option<ChildMessage> {
+        match activity.update() {
+            JobUpdate::Activity(activity) => Some(ChildMessage::Activity(activity.clone())),
+            JobUpdate::Status(update) => Some(ChildMessage::Status(update.clone())),
+            JobUpdate::Removed => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::BufReader;
+
+    use super::*;
+
+    #[test]
+    fn test_stop_unblocks_startup_confirmation() {
+        // Given
+        let (decision_sender, decision_receiver) = unbounded();
+        let listener = ParentListener {
+            stop_signal: Arc::new(AtomicBool::new(false)),
+            pause_signal: Arc::new(AtomicBool::new(false)),
+            resync_signal: Arc::new(AtomicBool::new(false)),
+            sync_channels: SyncChannels::default(),
+            error_channels: ErrorChannels::new(decision_sender, decision_receiver),
+        };
+        *listener.sync_channels.changes().lock().unwrap() = Some((vec![], vec![]));
+
+        // When
+        listener.apply(ParentMessage::Stop);
+
+        // Then
+        assert!(listener.stop_signal.load(Ordering::Relaxed));
+        assert_eq!(
+            listener.sync_channels.confirm_sync_receiver().try_recv(),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn test_messages_round_trip() {
+        // Given
+        let mut buffer = vec![];
+        write_message(&mut buffer, &ParentMessage::ConfirmStartupSync(true)).unwrap();
+        write_message(
+            &mut buffer,
+            &ParentMessage::Decision(Decision::IgnoreAndRestartSpaceSync(ContentId(42))),
+        )
+        .unwrap();
+        let mut reader = BufReader::new(&buffer[..]);
+
+        // When
+        let first: Option<ParentMessage> = read_message(&mut reader).unwrap();
+        let second: Option<ParentMessage> = read_message(&mut reader).unwrap();
+        let end: Option<ParentMessage> = read_message(&mut reader).unwrap();
+
+        // Then
+        assert_eq!(
+            String::from_utf8(buffer.clone()).unwrap().lines().next(),
+            Some(r#"{"message":"confirm_startup_sync","data":true}"#)
+        );
+        assert!(matches!(
+            first,
+            Some(ParentMessage::ConfirmStartupSync(true))
+        ));
+        assert!(matches!(
+            second,
+            Some(ParentMessage::Decision(
+                Decision::IgnoreAndRestartSpaceSync(ContentId(42))
+            ))
+        ));
+        assert!(end.is_none());
+    }
+}
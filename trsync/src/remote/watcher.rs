@@ -13,7 +13,9 @@ use std::{
     time::{Duration, Instant},
 };
 use thiserror::Error;
+use trsync_core::activity::WrappedActivity;
 use trsync_core::instance::ContentId as ContentId2;
+use trsync_core::status::{StatusUpdate, TlmState};
 use trsync_core::types::{ContentId, RemoteEventType, RevisionId};
 
 use futures_util::StreamExt;
@@ -30,9 +32,9 @@ const LAST_ACTIVITY_TIMEOUT: u64 = 60;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TracimLiveEvent {
-    event_id: i32,
-    event_type: String,
-    fields: Value,
+    pub event_id: i32,
+    pub event_type: String,
+    pub fields: Value,
 }
 
 #[derive(Error, Debug)]
@@ -58,6 +60,13 @@ impl FromStr for TracimLiveEvent {
     }
 }
 
+impl TracimLiveEvent {
+    /// Event of a live messages stream line, None if line is not an event data line
+    pub fn from_line(line: &str) -> Option<Result<Self, ParseTracimLiveEventError>> {
+        line.strip_prefix("data: ").map(Self::from_str)
+    }
+}
+
 pub struct RemoteWatcher {
     connection: Connection,
     context: Context,
@@ -65,6 +74,8 @@ pub struct RemoteWatcher {
     stop_signal: Arc<AtomicBool>,
     restart_signal: Arc<AtomicBool>,
     operational_sender: Sender<RemoteEvent>,
+    activity_sender: Option<Sender<WrappedActivity>>,
+    last_event_id: Option<i32>,
 }
 
 // TODO : Must have a local db with tuple (content_id,modified_timestamp)
@@ -87,9 +98,36 @@ impl RemoteWatcher {
             stop_signal,
             restart_signal,
             operational_sender,
+            activity_sender: None,
+            last_event_id: None,
         }
     }
 
+    pub fn activity_sender(mut self, value: Option<Sender<WrappedActivity>>) -> Self {
+        self.activity_sender = value;
+        self
+    }
+
+    pub fn set_tlm_state(&self, state: TlmState) {
+        if let Some(activity_sender) = &self.activity_sender {
+            if let Err(error) = activity_sender.send(WrappedActivity::status(
+                self.context.job_identifier(),
+                StatusUpdate::Tlm(state),
+            )) {
+                log::error!("Error when sending TLM state : {:?}", error);
+            }
+        }
+    }
+
+    /// Server can send again events missed during a disconnection
+    fn can_replay_events(&self) -> bool {
+        self.context
+            .capabilities
+            .as_ref()
+            .map(|capabilities| capabilities.event_replay)
+            .unwrap_or(false)
+    }
+
     pub fn listen(&mut self) -> Result<(), Error> {
         task::block_on::<_, Result<(), Error>>(async {
             let client = self.context.client().map_err(|err| {
@@ -98,52 +136,80 @@ impl RemoteWatcher {
             let user_id = client.get_user_id().map_err(|err| {
                 Error::UnexpectedError(format!("Error when create get user id : {}", err))
             })?;
-            let response = client
-                .get_user_live_messages_response(user_id)
-                .await
-                .map_err(|err| {
-                    Error::UnexpectedError(format!(
-                        "Error when get live message response : {}",
-                        err
-                    ))
-                })?;
-            let mut stream = response.bytes_stream();
-
-            let mut last_activity = Instant::now();
-            loop {
-                match timeout(Duration::from_millis(250), stream.next()).await {
-                    Ok(Some(things)) => {
-                        last_activity = Instant::now();
-                        match &things {
-                            Ok(lines) => {
-                                if let Err(error) = self.proceed_event_lines(lines) {
-                                    log::error!(
-                                        "Error when proceed remote event lines: {:?}",
-                                        error
-                                    )
+
+            let mut reconnecting = false;
+            'connection: loop {
+                let after_event_id = self.last_event_id.filter(|_| self.can_replay_events());
+                let response = match client
+                    .get_user_live_messages_response(user_id, after_event_id)
+                    .await
+                {
+                    Ok(response) => response,
+                    // Network can still be down : restart (with its backoff) as without replay
+                    Err(err) if reconnecting => {
+                        log::error!("Error when reconnect to live messages : {}", err);
+                        self.restart_signal.swap(true, Ordering::Relaxed);
+                        break 'connection;
+                    }
+                    Err(err) => {
+                        return Err(Error::UnexpectedError(format!(
+                            "Error when get live message response : {}",
+                            err
+                        )))
+                    }
+                };
+                let mut stream = response.bytes_stream();
+                self.set_tlm_state(TlmState::Listening);
+
+                let mut last_activity = Instant::now();
+                loop {
+                    match timeout(Duration::from_millis(250), stream.next()).await {
+                        Ok(Some(things)) => {
+                            last_activity = Instant::now();
+                            match &things {
+                                Ok(lines) => {
+                                    if let Err(error) = self.proceed_event_lines(lines) {
+                                        log::error!(
+                                            "Error when proceed remote event lines: {:?}",
+                                            error
+                                        )
+                                    }
+                                }
+                                Err(err) => {
+                                    log::error!("Error when reading remote TLM : {:?}", err);
+                                    // TODO : What to do here ?
                                 }
-                            }
-                            Err(err) => {
-                                log::error!("Error when reading remote TLM : {:?}", err);
-                                // TODO : What to do here ?
                             }
                         }
-                    }
-                    _ => {
-                        if last_activity.elapsed().as_secs() > LAST_ACTIVITY_TIMEOUT {
-                            log::info!(
-                                "No activity since '{}' seconds, break",
-                                LAST_ACTIVITY_TIMEOUT
-                            );
-                            self.restart_signal.swap(true, Ordering::Relaxed);
-                            break;
+                        _ => {
+                            if last_activity.elapsed().as_secs() > LAST_ACTIVITY_TIMEOUT {
+                                self.set_tlm_state(TlmState::Disconnected);
+                                // Missed events will be sent again, no need of full sync
+                                if let (true, Some(last_event_id)) =
+                                    (self.can_replay_events(), self.last_event_id)
+                                {
+                                    log::info!(
+                                        "No activity since '{}' seconds, reconnect after event {}",
+                                        LAST_ACTIVITY_TIMEOUT,
+                                        last_event_id
+                                    );
+                                    reconnecting = true;
+                                    continue 'connection;
+                                }
+                                log::info!(
+                                    "No activity since '{}' seconds, break",
+                                    LAST_ACTIVITY_TIMEOUT
+                                );
+                                self.restart_signal.swap(true, Ordering::Relaxed);
+                                break 'connection;
+                            }
                         }
                     }
-                }
 
-                if self.stop_signal.load(Ordering::Relaxed) {
-                    log::info!("Finished remote listening (on stop signal)");
-                    break;
+                    if self.stop_signal.load(Ordering::Relaxed) {
+                        log::info!("Finished remote listening (on stop signal)");
+                        break 'connection;
+                    }
                 }
             }
 
@@ -153,33 +219,39 @@ impl RemoteWatcher {
         Ok(())
     }
 
-    #[allow(clippy::manual_strip)]
-    fn proceed_event_lines(&self, lines: &Bytes) -> Result<(), Error> {
+    fn proceed_event_lines(&mut self, lines: &Bytes) -> Result<(), Error> {
         if lines.starts_with(b"event: message") {
             for line in str::from_utf8(lines)?.lines() {
-                if line.starts_with("data: ") {
-                    let json_as_str = &line[6..];
-                    match TracimLiveEvent::from_str(json_as_str) {
-                        Ok(remote_event) => self.proceed_remote_event(remote_event)?,
-                        Err(error) => {
-                            log::error!(
-                                "Error when decoding event : '{}'. Event as str was: '{}'",
-                                error,
-                                json_as_str
-                            )
-                        }
-                    };
-                }
+                match TracimLiveEvent::from_line(line) {
+                    Some(Ok(remote_event)) => {
+                        self.last_event_id = Some(remote_event.event_id);
+                        self.proceed_remote_event(remote_event)?
+                    }
+                    Some(Err(error)) => log::error!("Error when decoding event : '{}'", error),
+                    None => {}
+                };
             }
         }
 
         Ok(())
     }
 
+    /// Content event type, if concerned content type is synchronized with this server
+    fn supported_event_type(&self, event_type: &str) -> Option<RemoteEventType> {
+        let (event_type_, content_type) = RemoteEventType::parse_content_event(event_type).ok()?;
+        if let Some(capabilities) = &self.context.capabilities {
+            if !capabilities.supports(&content_type) {
+                log::debug!("Ignore event {} (not supported by server)", event_type);
+                return None;
+            }
+        }
+        Some(event_type_)
+    }
+
     fn proceed_remote_event(&self, remote_event: TracimLiveEvent) -> Result<(), Error> {
         log::debug!("Proceed remote event {:?}", remote_event);
 
-        if RemoteEventType::from_str(&remote_event.event_type).is_ok() {
+        if let Some(event_type) = self.supported_event_type(&remote_event.event_type) {
             let content_id =
                 remote_event.fields["content"]
                     .as_object()
@@ -222,28 +294,10 @@ impl RemoteWatcher {
                         &remote_event.event_type.as_str(),
                         content_id,
                     );
-                    let event_type = remote_event.event_type.as_str();
                     let message = match event_type {
-                        "content.modified.html-document"
-                        | "content.modified.file"
-                        | "content.modified.folder" => RemoteEvent::Updated(ContentId2(content_id)),
-                        "content.created.html-document"
-                        | "content.created.file"
-                        | "content.created.folder" => RemoteEvent::Created(ContentId2(content_id)),
-                        "content.deleted.html-document"
-                        | "content.deleted.file"
-                        | "content.deleted.folder" => RemoteEvent::Deleted(ContentId2(content_id)),
-                        "content.undeleted.html-document"
-                        | "content.undeleted.file"
-                        | "content.undeleted.folder" => {
-                            RemoteEvent::Created(ContentId2(content_id))
-                        }
-                        _ => {
-                            return Err(Error::UnexpectedError(format!(
-                                "Not managed event type : '{}'",
-                                event_type
-                            )))
-                        }
+                        RemoteEventType::Modified => RemoteEvent::Updated(ContentId2(content_id)),
+                        RemoteEventType::Created => RemoteEvent::Created(ContentId2(content_id)),
+                        RemoteEventType::Deleted => RemoteEvent::Deleted(ContentId2(content_id)),
                     };
 
                     Some(message)
@@ -284,3 +338,20 @@ pub struct RemoteContent {
     pub is_archived: bool,
     pub sub_content_types: Vec<String>,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_live_event_from_line() {
+        let line = r#"data: {"event_id": 42, "event_type": "content.modified.file", "fields": {}}"#;
+
+        let event = TracimLiveEvent::from_line(line).unwrap().unwrap();
+
+        assert_eq!(event.event_id, 42);
+        assert_eq!(event.event_type, "content.modified.file");
+        assert!(TracimLiveEvent::from_line("event: message").is_none());
+        assert!(TracimLiveEvent::from_line("data: {").unwrap().is_err());
+    }
+}
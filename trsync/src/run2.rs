@@ -1,12 +1,13 @@
 extern crate notify;
 use crate::context::Context as TrSyncContext;
-use crate::database::{connection, db_path};
+use crate::database::{connection, db_path, stale_marker_path};
 use crate::event::remote::RemoteEvent;
 use crate::event::Event;
+use crate::history::History;
 use crate::ignore::Ignore;
 use crate::local::reducer::LocalReceiverReducer;
 use crate::local::watcher::{DiskEvent, LocalWatcher};
-use crate::operation::operator::Operator;
+use crate::operation::operator::{FailedEvent, Operator};
 use crate::remote::watcher::RemoteWatcher;
 use crate::state::disk::DiskState;
 use crate::state::State;
@@ -16,8 +17,9 @@ use crate::sync::{ResolveMethod, StartupSyncResolver};
 use anyhow::{bail, Context, Result as AnyhowResult};
 use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
 use std::convert::TryFrom;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::mem::discriminant;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::{fs, thread};
@@ -27,7 +29,12 @@ use trsync_core::change::remote::RemoteChange;
 use trsync_core::change::Change;
 use trsync_core::client::{Tracim, TracimClient};
 use trsync_core::control::RemoteControl;
-use trsync_core::error::{Decision, ExecutorError, OperatorError, RunnerError};
+use trsync_core::error::{
+    Decision, ErrorClass, ErrorSubject, ExecutorError, OperatorError, Recovery, RunnerError,
+};
+use trsync_core::instance::ContentId;
+use trsync_core::status::{ConnectionState, StatusUpdate, TlmState};
+use trsync_core::user::UserRequest;
 
 struct Runner {
     context: TrSyncContext,
@@ -40,6 +47,10 @@ struct Runner {
     remote_receiver: Receiver<RemoteEvent>,
     local_sender: Sender<DiskEvent>,
     local_receiver_reducer: LocalReceiverReducer,
+    pending_local: Arc<AtomicUsize>,
+    pending_remote: Arc<AtomicUsize>,
+    skip_once: Option<Event>,
+    failed_event: Option<FailedEvent>,
 }
 
 impl Runner {
@@ -63,9 +74,23 @@ impl Runner {
             remote_receiver,
             local_sender,
             local_receiver_reducer,
+            pending_local: Arc::new(AtomicUsize::new(0)),
+            pending_remote: Arc::new(AtomicUsize::new(0)),
+            skip_once: None,
+            failed_event: None,
         }
     }
 
+    /// Given event will not be operated (once) by this runner
+    fn skip_once(mut self, value: Option<Event>) -> Self {
+        self.skip_once = value;
+        self
+    }
+
+    fn failed_event(&self) -> Option<&FailedEvent> {
+        self.failed_event.as_ref()
+    }
+
     fn ensure_folders(&self) -> AnyhowResult<()> {
         fs::create_dir_all(&self.context.folder_path)?;
         Ok(())
@@ -74,6 +99,24 @@ impl Runner {
     fn ensure_db(&mut self) -> AnyhowResult<()> {
         let workspace_path = PathBuf::from(&self.context.folder_path);
         DiskState::new(connection(&workspace_path)?, workspace_path.clone()).create_tables()?;
+        self.history()?.create_tables()?;
+        Ok(())
+    }
+
+    /// Forget local state marked as outdated, workspace is then indexed like at first
+    /// synchronization instead of comparing it with an outdated database
+    fn forget_stale_state(&self) -> AnyhowResult<()> {
+        let workspace_path = PathBuf::from(&self.context.folder_path);
+        let marker_path = stale_marker_path(&workspace_path);
+        if marker_path.exists() {
+            log::info!(
+                "Local state of '{}' is outdated, index it again",
+                self.context.folder_path
+            );
+            DiskState::new(connection(&workspace_path)?, workspace_path.clone()).clear()?;
+            fs::remove_file(&marker_path)
+                .context(format!("Remove stale marker {}", marker_path.display()))?;
+        }
         Ok(())
     }
 
@@ -90,6 +133,7 @@ impl Runner {
         let remote_watcher_restart_signal = self.restart_signal.clone();
         let remote_watcher_operational_sender = self.remote_sender.clone();
         let remote_watcher_connection = connection(&PathBuf::from(&self.context.folder_path))?;
+        let remote_watcher_activity_sender = self.remote_control.activity_sender().cloned();
 
         thread::spawn(move || {
             let mut remote_watcher = RemoteWatcher::new(
@@ -99,9 +143,11 @@ impl Runner {
                 remote_watcher_stop_signal,
                 remote_watcher_restart_signal,
                 remote_watcher_operational_sender,
-            );
+            )
+            .activity_sender(remote_watcher_activity_sender);
             if let Err(error) = remote_watcher.listen() {
                 log::error!("{}", error);
+                remote_watcher.set_tlm_state(TlmState::Disconnected);
                 // FIXME BS : stop_signal ? restart_signal ?
             }
         });
@@ -153,6 +199,22 @@ impl Runner {
         Ok(())
     }
 
+    fn set_status(&self, update: StatusUpdate) -> AnyhowResult<()> {
+        if let Some(activity_sender) = self.remote_control.activity_sender() {
+            if let Err(error) = activity_sender.send(WrappedActivity::status(
+                self.context.job_identifier(),
+                update,
+            )) {
+                bail!(format!(
+                    "[{}::{}] Error when sending status : {:?}",
+                    self.context.instance_name, self.context.workspace_id, error
+                ));
+            }
+        };
+
+        Ok(())
+    }
+
     fn sync(&self, operator: &mut Operator) -> Result<(), RunnerError> {
         self.set_activity(Activity::StartupSync(None))?;
         if let Err(error) = self.sync_(operator) {
@@ -165,6 +227,7 @@ impl Runner {
 
     fn sync_(&self, operator: &mut Operator) -> Result<(), RunnerError> {
         let remote_changes = self.remote_changes()?;
+        self.set_status(StatusUpdate::Connection(ConnectionState::Connected))?;
         let local_changes = self.local_changes()?;
         let (remote_changes, local_changes) =
             StartupSyncResolver::new(remote_changes, local_changes, ResolveMethod::ForceLocal)
@@ -182,6 +245,10 @@ impl Runner {
             self.set_activity(Activity::Idle)?;
         }
 
+        self.set_status(StatusUpdate::Pending(
+            local_changes.len(),
+            remote_changes.len(),
+        ))?;
         let remote_changes = remote_changes
             .iter()
             .map(|remote_change| remote_change.into())
@@ -192,6 +259,7 @@ impl Runner {
             remote_changes,
         )
         .operate(operator)?;
+        self.set_status(StatusUpdate::Pending(local_changes.len(), 0))?;
 
         let local_changes = local_changes
             .iter()
@@ -203,6 +271,8 @@ impl Runner {
             local_changes,
         )
         .operate(operator)?;
+        self.set_status(StatusUpdate::Pending(0, 0))?;
+        self.set_status(StatusUpdate::FullSyncDone)?;
 
         Ok(())
     }
@@ -234,9 +304,11 @@ impl Runner {
     fn listen_remote(&self) -> AnyhowResult<()> {
         let operational_sender = self.operational_sender.clone();
         let remote_receiver = self.remote_receiver.clone();
+        let pending_remote = self.pending_remote.clone();
 
         thread::spawn(move || {
             while let Ok(remote_event) = remote_receiver.recv() {
+                pending_remote.fetch_add(1, Ordering::Relaxed);
                 if operational_sender
                     .send(Event::Remote(remote_event))
                     .is_err()
@@ -252,9 +324,11 @@ impl Runner {
     fn listen_local(&self) -> AnyhowResult<()> {
         let operational_sender = self.operational_sender.clone();
         let mut local_receiver_reducer = self.local_receiver_reducer.clone();
+        let pending_local = self.pending_local.clone();
 
         thread::spawn(move || {
             while let Ok(disk_event) = local_receiver_reducer.recv() {
+                pending_local.fetch_add(1, Ordering::Relaxed);
                 if operational_sender.send(Event::Local(disk_event)).is_err() {
                     log::info!("Terminate locate listener");
                 }
@@ -272,10 +346,107 @@ impl Runner {
         self.restart_signal.load(Ordering::Relaxed)
     }
 
+    /// Consume a full resync request. Watchers are stopped like on restart, so a
+    /// new runner is started, beginning with a startup sync.
+    fn take_resync_request(&self) -> bool {
+        if self
+            .remote_control
+            .resync_signal()
+            .swap(false, Ordering::Relaxed)
+        {
+            self.restart_signal.store(true, Ordering::Relaxed);
+            return true;
+        }
+        false
+    }
+
+    fn is_paused(&self) -> bool {
+        self.remote_control.pause_signal().load(Ordering::Relaxed)
+    }
+
+    /// Activity preventing operations, if any
+    fn blocking_activity(&self) -> Option<Activity> {
+        if self.is_paused() {
+            return Some(Activity::Paused);
+        }
+        if !self.context.schedule.is_open() {
+            return Some(Activity::OutsideSyncWindow);
+        }
+        None
+    }
+
+    /// Block while workspace is paused or outside of synchronization window. Events
+    /// detected meanwhile stay queued. Return false if stop or restart was requested.
+    fn wait_operable(&self) -> Result<bool, RunnerError> {
+        let mut blocking_activity = match self.blocking_activity() {
+            Some(activity) => activity,
+            None => return Ok(true),
+        };
+
+        log::info!(
+            "[{}::{}] Operations suspended ({})",
+            self.context.instance_name,
+            self.context.workspace_id,
+            blocking_activity,
+        );
+        self.set_activity(blocking_activity.clone())?;
+        loop {
+            if self.is_stop_requested() || self.is_restart_requested() {
+                return Ok(false);
+            }
+            thread::sleep(Duration::from_millis(250));
+
+            match self.blocking_activity() {
+                Some(activity) => {
+                    if discriminant(&activity) != discriminant(&blocking_activity) {
+                        self.set_activity(activity.clone())?;
+                        blocking_activity = activity;
+                    }
+                }
+                None => break,
+            }
+        }
+
+        log::info!(
+            "[{}::{}] Operations resumed",
+            self.context.instance_name,
+            self.context.workspace_id,
+        );
+        self.set_activity(Activity::Idle)?;
+        Ok(true)
+    }
+
     fn operate(&self, operator: &mut Operator) -> Result<(), RunnerError> {
         let client: Box<dyn TracimClient> = Box::new(self.client()?);
+        let mut pending = (0, 0);
 
         loop {
+            let pending_ = (
+                self.pending_local.load(Ordering::Relaxed),
+                self.pending_remote.load(Ordering::Relaxed),
+            );
+            if pending_ != pending {
+                self.set_status(StatusUpdate::Pending(pending_.0, pending_.1))?;
+                pending = pending_;
+            }
+
+            if !self.wait_operable()? {
+                log::info!(
+                    "[{}::{}] Finished operational (on stop or restart signal)",
+                    self.context.instance_name,
+                    self.context.workspace_id,
+                );
+                break;
+            }
+            if self.take_resync_request() {
+                log::info!(
+                    "[{}::{}] Finished operational (on resync request)",
+                    self.context.instance_name,
+                    self.context.workspace_id,
+                );
+                break;
+            }
+
             match self
                 .operational_receiver
                 .recv_timeout(Duration::from_millis(150))
@@ -324,10 +495,28 @@ impl Runner {
                         break;
                     }
 
+                    match &event {
+                        Event::Local(_) => self.pending_local.fetch_sub(1, Ordering::Relaxed),
+                        Event::Remote(_) => self.pending_remote.fetch_sub(1, Ordering::Relaxed),
+                    };
                     log::info!("Proceed event {:?}", &event);
                     let event_display = event.display(client.as_ref());
                     self.set_activity(Activity::Job(event_display.to_string()))?;
-                    operator.operate(&event)?;
+                    match operator.operate(&event) {
+                        Ok(_) => self.set_status(StatusUpdate::EventDone)?,
+                        Err(error) if error.class().recovery() == Recovery::SkipEvent => {
+                            log::warn!(
+                                "[{}::{}] Skip event {} ({}) : {}",
+                                self.context.instance_name,
+                                self.context.workspace_id,
+                                event_display,
+                                error.class(),
+                                error,
+                            );
+                            self.set_status(StatusUpdate::Failed)?;
+                        }
+                        Err(error) => return Err(error.into()),
+                    }
                     self.set_activity(Activity::Idle)?;
                 }
             }
@@ -348,34 +537,79 @@ impl Runner {
         )))
     }
 
+    fn history(&self) -> AnyhowResult<History> {
+        let workspace_path = PathBuf::from(&self.context.folder_path);
+        Ok(History::new(
+            connection(&workspace_path).context(format!(
+                "Create connection for history for {}",
+                workspace_path.display()
+            ))?,
+            self.context.workspace_id,
+        ))
+    }
+
     fn client(&self) -> AnyhowResult<Tracim> {
         self.context
             .client()
             .context("Create tracim client for startup sync")
     }
 
+    /// Query server capabilities, so following clients and watchers adapt to it
+    fn ensure_capabilities(&mut self) -> Result<(), RunnerError> {
+        let capabilities = self
+            .client()?
+            .server_capabilities()
+            .context("Determine server capabilities")?;
+        log::info!(
+            "[{}::{}] Server is {}",
+            self.context.instance_name,
+            self.context.workspace_id,
+            capabilities
+        );
+        self.context.capabilities = Some(capabilities);
+        Ok(())
+    }
+
     pub fn run(&mut self) -> Result<(), RunnerError> {
-        let is_first_sync = !db_path(&PathBuf::from(&self.context.folder_path)).exists();
+        self.ensure_capabilities()?;
+        let db_exists = db_path(&PathBuf::from(&self.context.folder_path)).exists();
         self.ensure_folders()?;
         self.ensure_db()?;
+        self.forget_stale_state()?;
 
         let mut state = self.state()?;
+        // Database can exist but be empty after a local state reset
+        let is_first_sync = !db_exists || state.contents()?.is_empty();
         let mut operator = Operator::new(
             &mut state,
             PathBuf::from(&self.context.folder_path),
             Box::new(self.client()?),
         )
-        .avoid_same_sums(is_first_sync);
+        .avoid_same_sums(is_first_sync)
+        .history(self.history()?)
+        .ignore(self.ignore.clone());
+        if let Some(event) = self.skip_once.take() {
+            operator = operator.ignore_event(event);
+        }
+
+        let result = self.run_(&mut operator);
+        self.failed_event = operator.failed_event().cloned();
+        result
+    }
 
+    fn run_(&self, operator: &mut Operator) -> Result<(), RunnerError> {
         self.watchers()?;
-        self.sync(&mut operator)?;
+        if !self.wait_operable()? {
+            return Ok(());
+        }
+        self.sync(operator)?;
 
         if self.context.exit_after_sync {
             return Ok(());
         }
 
         self.listen()?;
-        self.operate(&mut operator)?;
+        self.operate(operator)?;
 
         Ok(())
     }
@@ -410,6 +644,16 @@ impl OperateChanges {
                     Err(OperatorError::ExecutorError(ExecutorError::MissingParent(_, _))) => {
                         remaining_changes.push(change.clone())
                     }
+                    Err(error) if error.class().recovery() == Recovery::SkipEvent => {
+                        log::warn!(
+                            "[{}::{}] Skip change {} ({}) : {}",
+                            self.context.instance_name,
+                            self.context.workspace_id,
+                            change,
+                            error.class(),
+                            error,
+                        );
+                    }
                     Err(err) => return Err(err),
                 };
                 self.set_activity(Activity::StartupSync(None))?;
@@ -458,25 +702,142 @@ impl OperateChanges {
     }
 }
 
+/// Describe what the error was about, from the event which failed if known
+fn error_subject(
+    context: &TrSyncContext,
+    failed_event: Option<&FailedEvent>,
+    error: &RunnerError,
+) -> ErrorSubject {
+    match failed_event {
+        Some(failed_event) => ErrorSubject {
+            event: Some(match context.client() {
+                Ok(client) => failed_event.event.display(&client),
+                Err(_) => format!("{:?}", failed_event.event),
+            }),
+            content_id: failed_event.content_id,
+            path: failed_event.path.clone(),
+        },
+        None => ErrorSubject {
+            content_id: error.ignorable_content_id(),
+            ..Default::default()
+        },
+    }
+}
+
+/// Remove local copy of content and forget it, so it will be downloaded again
+/// at next startup sync
+fn forget_local_copy(context: &TrSyncContext, content_id: ContentId) -> AnyhowResult<()> {
+    let workspace_path = PathBuf::from(&context.folder_path);
+    let mut state: Box<dyn State> = Box::new(DiskState::new(
+        connection(&workspace_path)?,
+        workspace_path.clone(),
+    ));
+    let path: PathBuf = state
+        .path(content_id)
+        .context(format!("Get path of {}", content_id))?
+        .into();
+    remove_local_path(&workspace_path.join(&path))
+        .context(format!("Remove local copy {}", path.display()))?;
+    state
+        .forgot_with_children(content_id)
+        .context(format!("Forgot {}", content_id))
+}
+
+fn remove_local_path(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else if path.exists() {
+        fs::remove_file(path)
+    } else {
+        Ok(())
+    }
+}
+
+/// True if workspace has been deleted or user is not member of it anymore (false if it can't
+/// be determined)
+fn is_access_lost(context: &TrSyncContext) -> bool {
+    match context
+        .client()
+        .and_then(|client| Ok(client.workspace_accessible()?))
+    {
+        Ok(accessible) => !accessible,
+        Err(error) => {
+            log::error!(
+                "[{}::{}] Unable to check workspace access : {:#}",
+                context.instance_name,
+                context.workspace_id,
+                error
+            );
+            false
+        }
+    }
+}
+
 pub fn run(context: TrSyncContext, remote: RemoteControl) -> AnyhowResult<()> {
+    let mut skip_once = None;
     loop {
         let mut ignore = Ignore::try_from(&context)
             .context(format!("Read {} .trsyncignore", context.folder_path))?;
-        let mut runner = Runner::new(context.clone(), remote.clone(), ignore.clone());
+        let mut runner = Runner::new(context.clone(), remote.clone(), ignore.clone())
+            .skip_once(skip_once.take());
         if let Err(error) = runner.run() {
-            log::error!("Operate error : {:#}", &error);
+            log::error!("Operate error ({}) : {:#}", error.class(), &error);
+            runner.set_status(StatusUpdate::Failed)?;
 
-            // TODO : absolutely ugly. I think we should drop anyhow !!
-            if format!("{}", error).to_lowercase().contains("connection") {
+            if error.class().recovery() == Recovery::Backoff {
+                runner.set_status(StatusUpdate::Connection(ConnectionState::Disconnected))?;
                 log::info!("Connection error, retry in 30s.");
                 thread::sleep(Duration::from_secs(30));
+                if remote.stop_signal().load(Ordering::Relaxed) {
+                    remote.stop_signal().swap(false, Ordering::Relaxed);
+                    break;
+                }
                 continue;
             }
 
+            if error.class().recovery() == Recovery::Stop {
+                log::error!(
+                    "[{}::{}] Stop synchronization : {}",
+                    context.instance_name,
+                    context.workspace_id,
+                    error.class().hint().unwrap_or_default()
+                );
+                runner.set_activity(Activity::Error)?;
+                break;
+            }
+
+            if matches!(error.class(), ErrorClass::Permission | ErrorClass::NotFound)
+                && is_access_lost(&context)
+            {
+                log::warn!(
+                    "[{}::{}] Workspace is not accessible anymore, stop its synchronization \
+                    (local files are kept)",
+                    context.instance_name,
+                    context.workspace_id
+                );
+                runner.set_activity(Activity::AccessLost)?;
+                if let Some(user_request_sender) = remote.user_request_sender() {
+                    if user_request_sender
+                        .send(UserRequest::WorkspaceAccessLost(
+                            context.instance_name.clone(),
+                            context.workspace_id,
+                        ))
+                        .is_err()
+                    {
+                        log::debug!("No user to notify about access loss")
+                    }
+                }
+                break;
+            }
+
             if let Some(error_channels) = remote.error_channels() {
                 runner.set_activity(Activity::Error)?;
+                *error_channels.subject().lock().unwrap() =
+                    Some(error_subject(&context, runner.failed_event(), &error));
                 *error_channels.error().lock().unwrap() = Some(error);
-                match error_channels.decision_receiver().recv() {
+                let decision = error_channels.decision_receiver().recv();
+                *error_channels.subject().lock().unwrap() = None;
+                match decision {
                     Ok(Decision::RestartSpaceSync) => {}
                     Ok(Decision::IgnoreAndRestartSpaceSync(content_id)) => {
                         ignore.push(content_id);
@@ -488,6 +849,44 @@ pub fn run(context: TrSyncContext, remote: RemoteControl) -> AnyhowResult<()> {
                             )
                         }
                     }
+                    Ok(Decision::SkipEventAndRestartSpaceSync) => {
+                        skip_once = runner.failed_event().map(|failed| failed.event.clone());
+                    }
+                    Ok(Decision::PauseSpaceSync) => {
+                        remote.pause_signal().store(true, Ordering::Relaxed);
+                    }
+                    Ok(Decision::ResetAndRestartSpaceSync) => {
+                        let workspace_path = PathBuf::from(&context.folder_path);
+                        if let Err(error) = connection(&workspace_path).and_then(|connection| {
+                            DiskState::new(connection, workspace_path.clone()).clear()
+                        }) {
+                            log::error!(
+                                "Fail to reset '{}' local state: {:#}",
+                                context.folder_path,
+                                error
+                            )
+                        }
+                    }
+                    Ok(Decision::RedownloadAndRestartSpaceSync(content_id)) => {
+                        if let Err(error) = forget_local_copy(&context, content_id) {
+                            log::error!(
+                                "Fail to forget '{}' local copy of {}: {:#}",
+                                context.folder_path,
+                                content_id,
+                                error
+                            )
+                        }
+                    }
+                    Ok(Decision::IgnorePathAndRestartSpaceSync(path)) => {
+                        ignore.push_path(path);
+                        if let Err(error) = ignore.write(&context) {
+                            log::error!(
+                                "Fail to write '{}' ignore file: {}",
+                                context.folder_path,
+                                error
+                            )
+                        }
+                    }
                     Err(_) => {
                         log::error!(
                             "Unable to communicate from trsync run to error decision receiver"
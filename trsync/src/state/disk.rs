@@ -1,4 +1,7 @@
-use std::{cmp::Ordering, path::PathBuf};
+use std::{
+    cmp::Ordering,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{bail, Context, Result as AnyhowResult};
 use rusqlite::{params, Connection};
@@ -10,7 +13,7 @@ use trsync_core::{
 
 use crate::path::ContentPath;
 
-use super::{State, StateError};
+use super::{Rejection, State, StateError};
 
 pub struct DiskState {
     connection: Connection,
@@ -50,6 +53,38 @@ impl DiskState {
                 [],
             )
             .context("Create content_id index")?;
+        self.connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS rejected (
+                relative_path TEXT PRIMARY KEY,
+                reason TEXT NOT NULL,
+                last_modified_timestamp INTEGER NOT NULL
+            );",
+                [],
+            )
+            .context("Create rejected table")?;
+        Ok(())
+    }
+
+    /// Forget all known contents
+    pub fn clear(&self) -> AnyhowResult<()> {
+        self.connection
+            .execute("DELETE FROM file", [])
+            .context("Clear file table")?;
+        self.connection
+            .execute("DELETE FROM rejected", [])
+            .context("Clear rejected table")?;
+        Ok(())
+    }
+
+    /// Forget rejection of path, when it has been accepted by server
+    fn delete_rejection(&self, relative_path: &Path) -> AnyhowResult<()> {
+        self.connection
+            .execute(
+                "DELETE FROM rejected WHERE relative_path = ?",
+                params![relative_path.display().to_string()],
+            )
+            .context(format!("Forget rejection of {}", relative_path.display()))?;
         Ok(())
     }
 
@@ -239,6 +274,8 @@ impl State for DiskState {
                     return Err(StateError::UnknownError(error.to_string()))
                 }
             };
+        self.delete_rejection(&relative_path)
+            .map_err(|error| StateError::UnknownError(format!("{:#}", error)))?;
 
         Ok(())
     }
@@ -274,9 +311,41 @@ impl State for DiskState {
                 content_id.0,
             ],
         )?;
+        self.delete_rejection(&new_path)?;
 
         Ok(())
     }
+
+    fn reject(&mut self, relative_path: PathBuf, rejection: Rejection) -> AnyhowResult<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO rejected (relative_path, reason, last_modified_timestamp) VALUES (?, ?, ?)",
+            params![
+                relative_path.display().to_string(),
+                rejection.reason,
+                rejection.timestamp.0,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn unreject(&mut self, relative_path: &Path) -> AnyhowResult<()> {
+        self.delete_rejection(relative_path)
+    }
+
+    fn rejection(&self, relative_path: &Path) -> AnyhowResult<Option<Rejection>> {
+        match self.connection.query_row::<(String, u64), _, _>(
+            "SELECT reason, last_modified_timestamp FROM rejected WHERE relative_path = ?",
+            params![relative_path.display().to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ) {
+            Ok((reason, timestamp)) => Ok(Some(Rejection {
+                reason,
+                timestamp: DiskTimestamp(timestamp),
+            })),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(error) => bail!(error),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,9 @@
 use anyhow::{bail, Context, Result as AnyhowResult};
-use std::{cmp::Ordering, collections::HashMap, path::PathBuf};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use trsync_core::{
     content::Content,
@@ -9,11 +13,12 @@ use trsync_core::{
 
 use crate::path::ContentPath;
 
-use super::{State, StateError};
+use super::{Rejection, State, StateError};
 
 pub struct MemoryState {
     contents: HashMap<ContentId, Content>,
     timestamps: HashMap<ContentId, DiskTimestamp>,
+    rejections: HashMap<PathBuf, Rejection>,
 }
 
 impl MemoryState {
@@ -36,6 +41,7 @@ impl MemoryState {
         Ok(Self {
             contents,
             timestamps,
+            rejections: HashMap::new(),
         })
     }
 }
@@ -117,9 +123,10 @@ impl State for MemoryState {
     fn add(
         &mut self,
         content: Content,
-        _: PathBuf,
+        relative_path: PathBuf,
         timestamp: DiskTimestamp,
     ) -> Result<(), StateError> {
+        self.rejections.remove(&relative_path);
         self.timestamps.insert(content.id(), timestamp);
         self.contents.insert(content.id(), content);
 
@@ -146,4 +153,18 @@ impl State for MemoryState {
 
         Ok(())
     }
+
+    fn reject(&mut self, relative_path: PathBuf, rejection: Rejection) -> AnyhowResult<()> {
+        self.rejections.insert(relative_path, rejection);
+        Ok(())
+    }
+
+    fn unreject(&mut self, relative_path: &Path) -> AnyhowResult<()> {
+        self.rejections.remove(relative_path);
+        Ok(())
+    }
+
+    fn rejection(&self, relative_path: &Path) -> AnyhowResult<Option<Rejection>> {
+        Ok(self.rejections.get(relative_path).cloned())
+    }
 }
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result as AnyhowResult};
 
@@ -16,6 +16,13 @@ pub mod disk;
 pub mod memory;
 pub mod modification;
 
+/// Local file which can't be sent to server while it is unchanged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejection {
+    pub reason: String,
+    pub timestamp: DiskTimestamp,
+}
+
 pub trait State {
     fn known(&self, id: ContentId) -> AnyhowResult<bool>;
     fn get(&self, id: ContentId) -> AnyhowResult<Option<Content>>;
@@ -54,6 +61,10 @@ pub trait State {
         parent_id: Option<ContentId>,
         timestamp: DiskTimestamp,
     ) -> AnyhowResult<()>;
+    fn reject(&mut self, relative_path: PathBuf, rejection: Rejection) -> AnyhowResult<()>;
+    fn rejection(&self, relative_path: &Path) -> AnyhowResult<Option<Rejection>>;
+    /// Forget rejection of path (file deleted or renamed before being accepted by server)
+    fn unreject(&mut self, relative_path: &Path) -> AnyhowResult<()>;
 
     fn change(&mut self, change: StateModification) -> Result<(), StateError> {
         match change {
@@ -78,6 +89,9 @@ pub trait State {
                     new_timestamp,
                 )
                 .context(format!("Update content {}", content_id))?,
+            StateModification::Reject(relative_path, reason, timestamp) => self
+                .reject(relative_path.clone(), Rejection { reason, timestamp })
+                .context(format!("Reject {}", relative_path.display()))?,
         };
 
         Ok(())
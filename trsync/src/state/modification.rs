@@ -15,4 +15,32 @@ pub enum StateModification {
         Option<ContentId>,
         DiskTimestamp,
     ),
+    /// Local file refused by server (reason), as modified at given timestamp
+    Reject(PathBuf, String, DiskTimestamp),
+}
+
+impl StateModification {
+    pub fn content_id(&self) -> Option<ContentId> {
+        match self {
+            StateModification::Forgot(content_id) => Some(*content_id),
+            StateModification::Add(content, _, _) => Some(content.id()),
+            StateModification::Update(content_id, _, _, _, _) => Some(*content_id),
+            StateModification::Reject(_, _, _) => None,
+        }
+    }
+
+    pub fn revision_id(&self) -> Option<RevisionId> {
+        match self {
+            StateModification::Forgot(_) | StateModification::Reject(_, _, _) => None,
+            StateModification::Add(content, _, _) => Some(content.revision_id()),
+            StateModification::Update(_, _, revision_id, _, _) => Some(*revision_id),
+        }
+    }
+
+    pub fn rejection_reason(&self) -> Option<&str> {
+        match self {
+            StateModification::Reject(_, reason, _) => Some(reason),
+            _ => None,
+        }
+    }
 }
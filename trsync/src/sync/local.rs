@@ -1,4 +1,7 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{bail, Context, Result};
 use rusqlite::{params, Connection};
@@ -23,11 +26,12 @@ impl LocalSync {
     pub fn changes(&self) -> Result<Vec<LocalChange>> {
         let mut changes = vec![];
         let mut disk_relative_paths = vec![];
+        let rejections = self.rejections().context("Read rejected paths")?;
 
         // Read from disk to see changes or new
         for entry in WalkDir::new(&self.workspace_path)
             .into_iter()
-            .filter_entry(|e| !ignore_file(e.path()))
+            .filter_entry(|e| !ignore_file(e.path()) && !self.is_rejected_folder(e, &rejections))
         {
             let entry_debug = format!("{:?}", &entry);
             let entry = entry.context(format!("Read disk entry {:?}", entry_debug))?;
@@ -44,6 +48,13 @@ impl LocalSync {
                 continue;
             }
 
+            if self
+                .is_rejected_file(&entry, &rejections)
+                .context(format!("Test if {:?} is rejected", &entry))?
+            {
+                continue;
+            }
+
             if let Some(change) = self
                 .change(&entry)
                 .context(format!("Determine change for {:?}", &entry))?
@@ -97,6 +108,63 @@ impl LocalSync {
         }
     }
 
+    /// Paths refused by server, with their disk timestamp at this moment
+    fn rejections(&self) -> Result<HashMap<PathBuf, DiskTimestamp>> {
+        let mut rejections = HashMap::new();
+
+        for row in self
+            .connection
+            .prepare("SELECT relative_path, last_modified_timestamp FROM rejected")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        {
+            let (raw_relative_path, timestamp): (String, u64) =
+                row.context("Read rejected path from db")?;
+            rejections.insert(PathBuf::from(raw_relative_path), DiskTimestamp(timestamp));
+        }
+
+        Ok(rejections)
+    }
+
+    fn relative_path(&self, entry: &DirEntry) -> PathBuf {
+        entry
+            .path()
+            .strip_prefix(&self.workspace_path)
+            .expect("Manipulated path are in the workspace folder")
+            .to_path_buf()
+    }
+
+    /// Rejected folder (and so its content) is ignored until it is renamed
+    fn is_rejected_folder(
+        &self,
+        entry: &DirEntry,
+        rejections: &HashMap<PathBuf, DiskTimestamp>,
+    ) -> bool {
+        entry.file_type().is_dir() && rejections.contains_key(&self.relative_path(entry))
+    }
+
+    /// Rejected file is ignored until it is modified
+    fn is_rejected_file(
+        &self,
+        entry: &DirEntry,
+        rejections: &HashMap<PathBuf, DiskTimestamp>,
+    ) -> Result<bool> {
+        if entry.file_type().is_dir() {
+            return Ok(false);
+        }
+
+        match rejections.get(&self.relative_path(entry)) {
+            Some(rejected_timestamp) => {
+                let modified = DiskTimestamp(
+                    last_modified_timestamp(entry.path())
+                        .context(format!("Get disk timestamp of {}", entry.path().display()))?
+                        .as_millis() as u64,
+                );
+                Ok(&modified == rejected_timestamp)
+            }
+            None => Ok(false),
+        }
+    }
+
     fn previously_known(&self, path: &Path) -> Result<bool> {
         match self.connection.query_row::<u64, _, _>(
             "SELECT 1 FROM file WHERE relative_path = ?",
@@ -148,7 +216,10 @@ mod test {
     use trsync_core::change::local::LocalChange;
 
     use super::*;
-    use crate::{state::disk::DiskState, tests::*};
+    use crate::{
+        state::{disk::DiskState, Rejection, State},
+        tests::*,
+    };
 
     #[test]
     fn test_empty() {
@@ -224,6 +295,49 @@ mod test {
         assert_eq!(state, vec![LocalChange::Updated(PathBuf::from("a.txt"))])
     }
 
+    #[test]
+    fn test_rejected_file() {
+        // Given
+        let tmpdir_ = tmpdir();
+        let mut state = DiskState::new(connection(&tmpdir_), tmpdir_.clone());
+        state.create_tables().unwrap();
+        let local_sync = LocalSync::new(connection(&tmpdir_), tmpdir_.clone());
+        apply_on_disk(
+            &vec![
+                OperateOnDisk::Create("a.txt".to_string()),
+                OperateOnDisk::Create("b.txt".to_string()),
+            ],
+            &tmpdir_,
+        );
+        let timestamp = last_modified_timestamp(&tmpdir_.join("a.txt"))
+            .unwrap()
+            .as_millis() as u64;
+        state
+            .reject(
+                PathBuf::from("a.txt"),
+                Rejection {
+                    reason: "Too big".to_string(),
+                    timestamp: DiskTimestamp(timestamp),
+                },
+            )
+            .unwrap();
+        state
+            .reject(
+                PathBuf::from("b.txt"),
+                Rejection {
+                    reason: "Too big".to_string(),
+                    timestamp: DiskTimestamp(0),
+                },
+            )
+            .unwrap();
+
+        // When
+        let state = local_sync.changes().unwrap();
+
+        // Then
+        assert_eq!(state, vec![LocalChange::New(PathBuf::from("b.txt"))])
+    }
+
     #[test]
     fn test_one_file_deleted() {
         // Given
@@ -1,18 +1,16 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::path::PathBuf;
 
-use anyhow::{bail, Context, Result};
+use anyhow::{Context, Result};
 use rusqlite::{params, Connection};
 use trsync_core::{
     change::remote::RemoteChange,
-    client::{RemoteContent, TracimClient},
+    client::{contents_pages, TracimClient},
     content::Content,
     instance::{ContentId, RevisionId},
+    types::ContentType,
 };
 
-use crate::{
-    ignore::Ignore,
-    state::{memory::MemoryState, State},
-};
+use crate::ignore::Ignore;
 
 pub struct RemoteSync {
     ignore: Ignore,
@@ -29,217 +27,179 @@ impl RemoteSync {
         }
     }
 
-    fn state(&self) -> Result<MemoryState> {
-        let mut contents = HashMap::new();
-        let all_remote_contents = self.all_remote_contents()?;
-
-        for remote_content in &all_remote_contents {
-            let is_deleted = self
-                .is_deleted(remote_content, &all_remote_contents)
-                .context(format!(
-                    "Try to determine if {} is deleted",
-                    remote_content.content_id
-                ))?;
-            let is_attachment = self.is_attachment(remote_content, &all_remote_contents);
-
-            if !is_deleted && !is_attachment {
-                let content: Content = Content::from_remote(remote_content)?;
-                contents.insert(content.id(), content);
+    /// Write remote contents, page by page, in `remote_content` temporary table. Then
+    /// write in `remote_state` temporary table contents which must be synchronized (not
+    /// deleted, archived or in a deleted or archived folder, and not attachments) with their
+    /// path. Only one page of contents is in memory at once.
+    fn state(&self) -> Result<()> {
+        self.connection
+            .execute_batch(
+                "CREATE TEMP TABLE IF NOT EXISTS remote_content (
+                    content_id INTEGER PRIMARY KEY,
+                    revision_id INTEGER NOT NULL,
+                    parent_id INTEGER,
+                    file_name TEXT NOT NULL,
+                    is_folder INTEGER NOT NULL,
+                    is_file INTEGER NOT NULL,
+                    removed INTEGER NOT NULL
+                );
+                CREATE TEMP TABLE IF NOT EXISTS remote_state (
+                    content_id INTEGER PRIMARY KEY,
+                    revision_id INTEGER NOT NULL,
+                    relative_path TEXT NOT NULL,
+                    depth INTEGER NOT NULL,
+                    is_folder INTEGER NOT NULL
+                );
+                DELETE FROM remote_content;
+                DELETE FROM remote_state;",
+            )
+            .context("Prepare remote state tables")?;
+
+        for page in contents_pages(self.client.as_ref()) {
+            let page = page.context("Read contents from remote")?;
+            let transaction = self
+                .connection
+                .unchecked_transaction()
+                .context("Start remote contents transaction")?;
+            for remote_content in page {
+                let content = Content::from_remote(&remote_content)?;
+                transaction
+                    .execute(
+                        "INSERT OR REPLACE INTO remote_content VALUES (?, ?, ?, ?, ?, ?, ?)",
+                        params![
+                            content.id().0,
+                            content.revision_id().0,
+                            content.parent_id().map(|parent_id| parent_id.0),
+                            content.file_name().0,
+                            content.type_() == &ContentType::Folder,
+                            content.type_() == &ContentType::File,
+                            remote_content.is_deleted || remote_content.is_archived,
+                        ],
+                    )
+                    .context(format!("Write remote content {}", content.id()))?;
             }
+            transaction
+                .commit()
+                .context("Commit remote contents transaction")?;
         }
 
-        MemoryState::new(contents, HashMap::new())
-            .context("Build memory state from remote contents")
+        // Contents without parent in remote contents are considered as deleted
+        self.connection
+            .execute(
+                "INSERT INTO remote_state
+                WITH RECURSIVE kept(content_id, revision_id, relative_path, depth, is_folder) AS (
+                    SELECT content_id, revision_id, file_name, 0, is_folder
+                    FROM remote_content
+                    WHERE parent_id IS NULL AND NOT removed
+                    UNION ALL
+                    SELECT child.content_id, child.revision_id,
+                        kept.relative_path || '/' || child.file_name, kept.depth + 1,
+                        child.is_folder
+                    FROM remote_content AS child
+                    JOIN kept ON child.parent_id = kept.content_id
+                    WHERE NOT child.removed AND NOT (child.is_file AND NOT kept.is_folder)
+                )
+                SELECT content_id, revision_id, relative_path, depth, is_folder FROM kept",
+                [],
+            )
+            .context("Determine remote state")?;
+
+        Ok(())
     }
 
     pub fn changes(&self) -> Result<Vec<RemoteChange>> {
         let mut changes = vec![];
-        let remote_state = self.state().context("Determine remote state")?;
-
-        for content in remote_state.contents()? {
-            if self.ignore.is_ignored(&content.id()) {
+        self.state().context("Determine remote state")?;
+
+        // Folders first, parents before their children
+        let mut statement = self.connection.prepare(
+            "SELECT remote_state.content_id, remote_state.revision_id,
+                remote_state.relative_path, file.revision_id
+            FROM remote_state
+            LEFT JOIN file ON file.content_id = remote_state.content_id
+            ORDER BY NOT remote_state.is_folder, remote_state.depth, remote_state.content_id",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                ContentId(row.get(0)?),
+                RevisionId(row.get(1)?),
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<i32>>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (content_id, revision_id, relative_path, known_revision_id) =
+                row.context("Read remote state")?;
+            if self.ignore.is_ignored(&content_id) {
                 continue;
             }
 
-            let path = remote_state
-                .path(content.id())
-                .context(format!(
-                    "Determine path for remotely modified content {}",
-                    content.id()
-                ))?
-                .to_path_buf();
-            if self.previously_known(content.id()).context(format!(
-                "Determine if content {} is previously known",
-                content.id()
-            ))? {
-                if content.revision_id()
-                    != self.known_revision_id(content.id()).context(format!(
-                        "Read previously known content {} revision_id",
-                        content.id()
-                    ))?
-                {
-                    changes.push(RemoteChange::Updated(content.id(), path));
+            let path: PathBuf = relative_path.split('/').collect();
+            match known_revision_id {
+                Some(known_revision_id) if RevisionId(known_revision_id) != revision_id => {
+                    changes.push(RemoteChange::Updated(content_id, path))
                 }
-            } else {
-                changes.push(RemoteChange::New(content.id(), path));
+                Some(_) => {}
+                None => changes.push(RemoteChange::New(content_id, path)),
             }
         }
 
-        for content_id in self
-            .previously_known_content_ids()
-            .context("Read previously known content ids")?
-        {
-            let path = self.previously_known_path(content_id).context(format!(
-                "Determine previously known path for content {}",
-                content_id
-            ))?;
-            if !remote_state.known(content_id).context(format!(
-                "Check if previously known content {} is known in remote state",
-                content_id
-            ))? {
-                changes.push(RemoteChange::Disappear(content_id, path));
-            }
+        let mut statement = self.connection.prepare(
+            "SELECT content_id, relative_path FROM file
+            WHERE content_id NOT IN (SELECT content_id FROM remote_state)",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok((ContentId(row.get(0)?), row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (content_id, relative_path) = row.context("Read disappeared content")?;
+            changes.push(RemoteChange::Disappear(
+                content_id,
+                PathBuf::from(relative_path),
+            ));
         }
 
         Ok(changes)
     }
-
-    fn all_remote_contents(&self) -> Result<Vec<RemoteContent>> {
-        self.client
-            .get_contents()
-            .context("Read contents from remote")
-    }
-
-    fn is_deleted(
-        &self,
-        remote_content: &RemoteContent,
-        all_remote_contents: &[RemoteContent],
-    ) -> Result<bool> {
-        if remote_content.is_deleted || remote_content.is_archived {
-            return Ok(true);
-        }
-
-        let mut current = remote_content;
-        while let Some(parent_id) = current.parent_id {
-            current = match all_remote_contents
-                .iter()
-                .find(|c| c.content_id == ContentId(parent_id))
-            {
-                Some(content) => content,
-                // If not found, consider content as deleted because no parent
-                None => return Ok(true),
-            };
-
-            if current.is_deleted || current.is_archived {
-                return Ok(true);
-            }
-        }
-
-        Ok(false)
-    }
-
-    fn is_attachment(
-        &self,
-        remote_content: &RemoteContent,
-        all_remote_contents: &[RemoteContent],
-    ) -> bool {
-        if remote_content.content_type != "file" {
-            return false;
-        }
-
-        if let Some(parent_id) = remote_content.parent_id {
-            for remote_content_ in all_remote_contents {
-                if remote_content_.content_id.0 == parent_id {
-                    return remote_content_.content_type != "folder";
-                }
-            }
-        }
-
-        false
-    }
-
-    fn previously_known(&self, id: ContentId) -> Result<bool> {
-        match self.connection.query_row::<u64, _, _>(
-            "SELECT 1 FROM file WHERE content_id = ?",
-            params![id.0],
-            |row| row.get(0),
-        ) {
-            Ok(_) => Ok(true),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(false),
-            Err(error) => bail!(error),
-        }
-    }
-
-    fn known_revision_id(&self, id: ContentId) -> Result<RevisionId> {
-        match self.connection.query_row::<i32, _, _>(
-            "SELECT revision_id FROM file WHERE content_id = ?",
-            params![id.0],
-            |row| Ok(row.get(0).unwrap()),
-        ) {
-            Ok(id) => Ok(RevisionId(id)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => {
-                bail!("No row found for content {}", id)
-            }
-            Err(error) => bail!("Read revision_id for {} from db but : {}", id, error),
-        }
-    }
-
-    fn previously_known_content_ids(&self) -> Result<Vec<ContentId>> {
-        let mut content_ids = vec![];
-
-        for raw_content_id in self
-            .connection
-            .prepare("SELECT content_id FROM file")?
-            .query_map([], |row| row.get(0))?
-        {
-            content_ids.push(ContentId(raw_content_id?))
-        }
-
-        Ok(content_ids)
-    }
-
-    fn previously_known_path(&self, id: ContentId) -> Result<PathBuf> {
-        match self.connection.query_row::<String, _, _>(
-            "SELECT relative_path FROM file WHERE content_id = ?",
-            params![id.0],
-            |row| Ok(row.get(0).unwrap()),
-        ) {
-            Ok(relative_path) => Ok(PathBuf::from(relative_path)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => {
-                bail!("No row found for content {}", id)
-            }
-            Err(error) => bail!("Read relative_path for {} from db but : {}", id, error),
-        }
-    }
 }
 
 #[cfg(test)]
 mod test {
     use trsync_core::change::remote::RemoteChange;
+    use trsync_core::client::{ContentsPage, RemoteContent, TracimClientError};
     use trsync_core::{client::MockTracimClient, instance::RevisionId};
 
     use crate::state::disk::DiskState;
-    use crate::state::State;
     use crate::tests::*;
 
     use super::*;
 
+    fn single_page(contents: Vec<RemoteContent>) -> Result<ContentsPage, TracimClientError> {
+        Ok(ContentsPage {
+            contents,
+            next_page_token: None,
+        })
+    }
+
     #[test]
     fn test_state_empty() {
         // Given
         let tmpdir_ = tmpdir();
         let mut client = MockTracimClient::new();
+        DiskState::new(connection(&tmpdir_), tmpdir_.clone())
+            .create_tables()
+            .unwrap();
         client
-            .expect_get_contents()
+            .expect_get_contents_page()
             .times(1)
-            .returning(|| Ok(vec![]));
+            .returning(|_| single_page(vec![]));
         let remote_sync = RemoteSync::new(Ignore::empty(), connection(&tmpdir_), Box::new(client));
 
         // When
-        let state = remote_sync.state().unwrap();
+        let changes = remote_sync.changes().unwrap();
 
         // Then
-        assert_eq!(state.contents().unwrap(), vec![])
+        assert_eq!(changes, vec![])
     }
 
     #[test]
@@ -247,8 +207,11 @@ mod test {
         // Given
         let tmpdir_ = tmpdir();
         let mut client = MockTracimClient::new();
-        client.expect_get_contents().times(1).returning(|| {
-            Ok(vec![
+        DiskState::new(connection(&tmpdir_), tmpdir_.clone())
+            .create_tables()
+            .unwrap();
+        client.expect_get_contents_page().times(1).returning(|_| {
+            single_page(vec![
                 RemoteContent {
                     content_id: ContentId(1),
                     current_revision_id: RevisionId(1),
@@ -278,11 +241,72 @@ mod test {
         let remote_sync = RemoteSync::new(Ignore::empty(), connection(&tmpdir_), Box::new(client));
 
         // When
-        let state = remote_sync.state().unwrap();
+        let changes = remote_sync.changes().unwrap();
+
+        // Then
+        assert_eq!(
+            changes,
+            vec![
+                RemoteChange::New(ContentId(1), PathBuf::from("a.txt")),
+                RemoteChange::New(ContentId(2), PathBuf::from("b.txt"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_state_paginated() {
+        // Given
+        let tmpdir_ = tmpdir();
+        let mut client = MockTracimClient::new();
+        DiskState::new(connection(&tmpdir_), tmpdir_.clone())
+            .create_tables()
+            .unwrap();
+        let remote_content =
+            |content_id, parent_id: Option<i32>, content_type: &str, filename: &str| {
+                RemoteContent {
+                    content_id: ContentId(content_id),
+                    current_revision_id: RevisionId(content_id),
+                    parent_id,
+                    content_type: content_type.to_string(),
+                    modified: "".to_string(),
+                    raw_content: None,
+                    filename: filename.to_string(),
+                    is_deleted: false,
+                    is_archived: false,
+                    sub_content_types: vec![],
+                }
+            };
+        // Child is given before its parent folder
+        let first_page = vec![remote_content(2, Some(1), "file", "a.txt")];
+        let second_page = vec![remote_content(1, None, "folder", "Folder")];
+        client
+            .expect_get_contents_page()
+            .with(mockall::predicate::eq(None))
+            .times(1)
+            .returning(move |_| {
+                Ok(ContentsPage {
+                    contents: first_page.clone(),
+                    next_page_token: Some("token".to_string()),
+                })
+            });
+        client
+            .expect_get_contents_page()
+            .with(mockall::predicate::eq(Some("token".to_string())))
+            .times(1)
+            .returning(move |_| single_page(second_page.clone()));
+        let remote_sync = RemoteSync::new(Ignore::empty(), connection(&tmpdir_), Box::new(client));
+
+        // When
+        let changes = remote_sync.changes().unwrap();
 
         // Then
-        let contents = state.contents().unwrap();
-        assert_eq!(contents.len(), 2);
+        assert_eq!(
+            changes,
+            vec![
+                RemoteChange::New(ContentId(1), PathBuf::from("Folder")),
+                RemoteChange::New(ContentId(2), PathBuf::from("Folder/a.txt"))
+            ]
+        );
     }
 
     #[test]
@@ -290,8 +314,11 @@ mod test {
         // Given
         let tmpdir_ = tmpdir();
         let mut client = MockTracimClient::new();
-        client.expect_get_contents().times(1).returning(|| {
-            Ok(vec![
+        DiskState::new(connection(&tmpdir_), tmpdir_.clone())
+            .create_tables()
+            .unwrap();
+        client.expect_get_contents_page().times(1).returning(|_| {
+            single_page(vec![
                 RemoteContent {
                     content_id: ContentId(1),
                     current_revision_id: RevisionId(1),
@@ -321,13 +348,16 @@ mod test {
         let remote_sync = RemoteSync::new(Ignore::empty(), connection(&tmpdir_), Box::new(client));
 
         // When
-        let state = remote_sync.state().unwrap();
+        let changes = remote_sync.changes().unwrap();
 
         // Then
-        let contents = state.contents().unwrap();
-        assert_eq!(contents.len(), 2);
-        assert_eq!(contents[0].id(), ContentId(1));
-        assert_eq!(contents[1].id(), ContentId(2));
+        assert_eq!(
+            changes,
+            vec![
+                RemoteChange::New(ContentId(1), PathBuf::from("Folder")),
+                RemoteChange::New(ContentId(2), PathBuf::from("Folder/a.txt"))
+            ]
+        );
     }
 
     #[test]
@@ -335,8 +365,11 @@ mod test {
         // Given
         let tmpdir_ = tmpdir();
         let mut client = MockTracimClient::new();
-        client.expect_get_contents().times(1).returning(|| {
-            Ok(vec![
+        DiskState::new(connection(&tmpdir_), tmpdir_.clone())
+            .create_tables()
+            .unwrap();
+        client.expect_get_contents_page().times(1).returning(|_| {
+            single_page(vec![
                 RemoteContent {
                     content_id: ContentId(1),
                     current_revision_id: RevisionId(1),
@@ -366,12 +399,13 @@ mod test {
         let remote_sync = RemoteSync::new(Ignore::empty(), connection(&tmpdir_), Box::new(client));
 
         // When
-        let state = remote_sync.state().unwrap();
+        let changes = remote_sync.changes().unwrap();
 
         // Then
-        let contents = state.contents().unwrap();
-        assert_eq!(contents.len(), 1);
-        assert_eq!(contents[0].id(), ContentId(1));
+        assert_eq!(
+            changes,
+            vec![RemoteChange::New(ContentId(1), PathBuf::from("a.txt"))]
+        );
     }
 
     #[test]
@@ -379,8 +413,11 @@ mod test {
         // Given
         let tmpdir_ = tmpdir();
         let mut client = MockTracimClient::new();
-        client.expect_get_contents().times(1).returning(|| {
-            Ok(vec![
+        DiskState::new(connection(&tmpdir_), tmpdir_.clone())
+            .create_tables()
+            .unwrap();
+        client.expect_get_contents_page().times(1).returning(|_| {
+            single_page(vec![
                 RemoteContent {
                     content_id: ContentId(1),
                     current_revision_id: RevisionId(1),
@@ -410,11 +447,10 @@ mod test {
         let remote_sync = RemoteSync::new(Ignore::empty(), connection(&tmpdir_), Box::new(client));
 
         // When
-        let state = remote_sync.state().unwrap();
+        let changes = remote_sync.changes().unwrap();
 
         // Then
-        let contents = state.contents().unwrap();
-        assert_eq!(contents.len(), 0);
+        assert_eq!(changes, vec![]);
     }
 
     #[test]
@@ -425,8 +461,8 @@ mod test {
         DiskState::new(connection(&tmpdir_), tmpdir_.clone())
             .create_tables()
             .unwrap();
-        client.expect_get_contents().times(1).returning(|| {
-            Ok(vec![RemoteContent {
+        client.expect_get_contents_page().times(1).returning(|_| {
+            single_page(vec![RemoteContent {
                 content_id: ContentId(1),
                 current_revision_id: RevisionId(1),
                 parent_id: None,
@@ -457,8 +493,8 @@ mod test {
         DiskState::new(connection(&tmpdir_), tmpdir_.clone())
             .create_tables()
             .unwrap();
-        client.expect_get_contents().times(1).returning(|| {
-            Ok(vec![RemoteContent {
+        client.expect_get_contents_page().times(1).returning(|_| {
+            single_page(vec![RemoteContent {
                 content_id: ContentId(1),
                 current_revision_id: RevisionId(1),
                 parent_id: None,
@@ -491,8 +527,8 @@ mod test {
         DiskState::new(connection(&tmpdir_), tmpdir_.clone())
             .create_tables()
             .unwrap();
-        client.expect_get_contents().times(1).returning(|| {
-            Ok(vec![RemoteContent {
+        client.expect_get_contents_page().times(1).returning(|_| {
+            single_page(vec![RemoteContent {
                 content_id: ContentId(1),
                 current_revision_id: RevisionId(2),
                 parent_id: None,
@@ -527,9 +563,9 @@ mod test {
             .create_tables()
             .unwrap();
         client
-            .expect_get_contents()
+            .expect_get_contents_page()
             .times(1)
-            .returning(|| Ok(vec![]));
+            .returning(|_| single_page(vec![]));
         insert_content(&connection(&tmpdir_), "a.txt", 1, 1, None, 0);
         let remote_sync = RemoteSync::new(Ignore::empty(), connection(&tmpdir_), Box::new(client));
 
@@ -545,4 +581,50 @@ mod test {
             )]
         )
     }
+
+    #[test]
+    fn test_state_without_attachments() {
+        // Given
+        let tmpdir_ = tmpdir();
+        let mut client = MockTracimClient::new();
+        DiskState::new(connection(&tmpdir_), tmpdir_.clone())
+            .create_tables()
+            .unwrap();
+        let remote_content =
+            |content_id, parent_id: Option<i32>, content_type: &str, filename: &str| {
+                RemoteContent {
+                    content_id: ContentId(content_id),
+                    current_revision_id: RevisionId(content_id),
+                    parent_id,
+                    content_type: content_type.to_string(),
+                    modified: "".to_string(),
+                    raw_content: None,
+                    filename: filename.to_string(),
+                    is_deleted: false,
+                    is_archived: false,
+                    sub_content_types: vec![],
+                }
+            };
+        let contents = vec![
+            remote_content(1, None, "html-document", "Note.document.html"),
+            remote_content(2, Some(1), "file", "attachment.png"),
+        ];
+        client
+            .expect_get_contents_page()
+            .times(1)
+            .returning(move |_| single_page(contents.clone()));
+        let remote_sync = RemoteSync::new(Ignore::empty(), connection(&tmpdir_), Box::new(client));
+
+        // When
+        let changes = remote_sync.changes().unwrap();
+
+        // Then
+        assert_eq!(
+            changes,
+            vec![RemoteChange::New(
+                ContentId(1),
+                PathBuf::from("Note.document.html")
+            )]
+        );
+    }
 }